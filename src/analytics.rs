@@ -0,0 +1,246 @@
+use crate::{
+    failed_searches,
+    mod_utils::{self, format_count},
+    types::{CommandEvent, Context, FavoriteItem, UsageTotals, BOT_EMBED_COLOR},
+};
+use poise::serenity_prelude as serenity;
+use poise::{ChoiceParameter, CreateReply};
+use std::collections::HashMap;
+use std::time::Instant;
+use tracing::warn;
+
+/// Daily counters are kept this long; the longest report window plus a few days of slack.
+pub const ANALYTICS_RETENTION_DAYS: i64 = 35;
+
+/// Rows per section of the `/admin stats` report.
+pub const REPORT_LIMIT: usize = 10;
+
+/// Invocations are finished by post_command or on_error, which also covers argument parse errors and panics.
+/// Anything still running an hour after it started is dropped once this many have piled up, in case one slips through.
+const RUNNING_PRUNE_THRESHOLD: usize = 1_000;
+
+/// The UTC day `timestamp` falls on, counted from the Unix epoch.
+pub fn day_of(timestamp: i64) -> i64 {
+    timestamp.div_euclid(86_400)
+}
+
+/// How far back `/admin stats` reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, poise::ChoiceParameter)]
+pub enum UsageWindow {
+    #[name = "Today"]
+    Today,
+    #[default]
+    #[name = "Last 7 days"]
+    Week,
+    #[name = "Last 30 days"]
+    Month,
+}
+
+impl UsageWindow {
+    /// The days covered at `now`, today first.
+    pub fn days(self, now: i64) -> Vec<i64> {
+        let count = match self {
+            Self::Today => 1,
+            Self::Week => 7,
+            Self::Month => 30,
+        };
+        let today = day_of(now);
+        (0..count).map(|offset| today - offset).collect()
+    }
+}
+
+/// What is known about a running invocation so far.
+#[derive(Debug, Clone)]
+pub struct Invocation {
+    pub started: Instant,
+    pub first_reply: Option<Instant>,
+    pub map_ids: Vec<i32>,
+    pub mod_titles: Vec<String>,
+    pub failed_query: Option<String>,
+}
+
+impl Invocation {
+    /// Milliseconds until the first reply, or until `finished` when nothing was sent.
+    /// Commands that wait on buttons afterwards would otherwise report their whole timeout.
+    pub fn latency_ms(&self, finished: Instant) -> u64 {
+        let until = self.first_reply.unwrap_or(finished);
+        until.saturating_duration_since(self.started).as_millis() as u64
+    }
+}
+
+/// Running invocations by [`poise::Context::id`], filled in by the framework hooks and the commands themselves.
+#[derive(Debug, Default)]
+pub struct Invocations {
+    running: HashMap<u64, Invocation>,
+}
+
+impl Invocations {
+    pub fn start(&mut self, id: u64, now: Instant) {
+        if self.running.len() >= RUNNING_PRUNE_THRESHOLD {
+            self.running.retain(|_, invocation| now.saturating_duration_since(invocation.started).as_secs() < 3_600);
+        }
+        let invocation = Invocation { started: now, first_reply: None, map_ids: Vec::new(), mod_titles: Vec::new(), failed_query: None };
+        self.running.insert(id, invocation);
+    }
+
+    /// Notes the first reply. Replies to invocations that never started, like a rate limit notice, are ignored.
+    pub fn replied(&mut self, id: u64, now: Instant) {
+        if let Some(invocation) = self.running.get_mut(&id) {
+            invocation.first_reply.get_or_insert(now);
+        }
+    }
+
+    pub fn resolved_map(&mut self, id: u64, map_id: i32) {
+        if let Some(invocation) = self.running.get_mut(&id) {
+            if !invocation.map_ids.contains(&map_id) {
+                invocation.map_ids.push(map_id);
+            }
+        }
+    }
+
+    pub fn resolved_mod(&mut self, id: u64, title: &str) {
+        if let Some(invocation) = self.running.get_mut(&id) {
+            if !invocation.mod_titles.iter().any(|t| t == title) {
+                invocation.mod_titles.push(title.to_string());
+            }
+        }
+    }
+
+    pub fn failed_search(&mut self, id: u64, query: &str) {
+        if let Some(invocation) = self.running.get_mut(&id) {
+            invocation.failed_query = Some(failed_searches::normalize_query(query));
+        }
+    }
+
+    pub fn finish(&mut self, id: u64) -> Option<Invocation> {
+        self.running.remove(&id)
+    }
+}
+
+/// Framework `pre_command` hook.
+pub fn started(ctx: Context<'_>) {
+    ctx.data().invocations.lock().unwrap().start(ctx.id(), Instant::now());
+}
+
+/// Framework `reply_callback`: notes when an invocation first replied.
+pub fn on_reply(ctx: Context<'_>, reply: CreateReply) -> CreateReply {
+    ctx.data().invocations.lock().unwrap().replied(ctx.id(), Instant::now());
+    reply
+}
+
+/// Counts the map an invocation resolved towards `/admin stats`.
+pub fn resolved_map(ctx: Context<'_>, map_id: i32) {
+    ctx.data().invocations.lock().unwrap().resolved_map(ctx.id(), map_id);
+}
+
+/// Counts the community mod an invocation resolved towards `/admin stats`.
+pub fn resolved_mod(ctx: Context<'_>, title: &str) {
+    ctx.data().invocations.lock().unwrap().resolved_mod(ctx.id(), title);
+}
+
+pub fn resolved_item(ctx: Context<'_>, item: &FavoriteItem) {
+    match item {
+        FavoriteItem::Map { id } => resolved_map(ctx, *id),
+        FavoriteItem::Mod { title, .. } => resolved_mod(ctx, title),
+    }
+}
+
+/// Counts a search that found nothing towards `/admin stats`.
+pub fn failed_search(ctx: Context<'_>, query: &str) {
+    ctx.data().invocations.lock().unwrap().failed_search(ctx.id(), query);
+}
+
+/// Framework `post_command` and error hook: stores the finished invocation. Storage errors are logged, never surfaced.
+pub async fn record(ctx: Context<'_>, success: bool) {
+    let Some(invocation) = ctx.data().invocations.lock().unwrap().finish(ctx.id()) else { return };
+    let event = CommandEvent {
+        command: ctx.command().qualified_name.clone(),
+        guild_id: ctx.guild_id().map(|id| id.get()),
+        success,
+        latency_ms: invocation.latency_ms(Instant::now()),
+        map_ids: invocation.map_ids,
+        mod_titles: invocation.mod_titles,
+        failed_query: invocation.failed_query,
+        at: chrono::Utc::now().timestamp(),
+    };
+    if let Err(e) = ctx.data().analytics.record(&event).await {
+        warn!(command = %event.command, "Analytics: Failed to record command usage: {}", e);
+    }
+}
+
+/// The `limit` biggest counts, ties broken by key.
+pub fn top<K: Clone + Ord>(counts: &HashMap<K, i64>, limit: usize) -> Vec<(K, i64)> {
+    let mut ranked: Vec<(K, i64)> = counts.iter().map(|(key, count)| (key.clone(), *count)).collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(limit);
+    ranked
+}
+
+/// One line per command: uses, failures and average latency.
+pub fn command_lines(totals: &UsageTotals) -> Vec<String> {
+    top(&totals.commands, REPORT_LIMIT)
+        .into_iter()
+        .map(|(command, count)| {
+            let failed = totals.failures.get(&command).copied().unwrap_or(0);
+            let average_ms = totals.latency_ms.get(&command).copied().unwrap_or(0) / count.max(1);
+            let failed = if failed > 0 { format!(" · ⚠️ {} failed", format_count(failed)) } else { String::new() };
+            format!("`/{}` · {}{} · ⏱️ {} ms avg", command, format_count(count), failed, format_count(average_ms))
+        })
+        .collect()
+}
+
+fn count_lines(ranked: Vec<(String, i64)>) -> Vec<String> {
+    ranked.into_iter().map(|(label, count)| format!("{} · {}", mod_utils::truncate_with_ellipsis(&label, 60), format_count(count))).collect()
+}
+
+/// The `/admin stats` report. Maps are named from `map_names` and servers from `guild_names`, falling back to their IDs.
+pub fn build_usage_embed(
+    window: UsageWindow,
+    totals: &UsageTotals,
+    map_names: &HashMap<i32, String>,
+    guild_names: &HashMap<u64, String>,
+) -> serenity::CreateEmbed {
+    let embed = serenity::CreateEmbed::default().title(format!("📊 Command Usage · {}", window.name())).color(BOT_EMBED_COLOR);
+    let total: i64 = totals.commands.values().sum();
+    if total == 0 {
+        return embed.description("No commands were recorded in this window.");
+    }
+    let failed: i64 = totals.failures.values().sum();
+    let searches_failed: i64 = totals.failed_searches.values().sum();
+    let description = format!(
+        "**{}** commands · **{}** failed · **{}** empty searches · **{}** servers",
+        format_count(total),
+        format_count(failed),
+        format_count(searches_failed),
+        format_count(totals.guilds.keys().filter(|key| *key != "dm").count() as i64)
+    );
+
+    let maps = top(&totals.maps, REPORT_LIMIT)
+        .into_iter()
+        .map(|(id, count)| (map_names.get(&id).cloned().unwrap_or_else(|| format!("Map {}", id)), count))
+        .collect();
+    let mods = top(&totals.mods, REPORT_LIMIT);
+    let searches = top(&totals.failed_searches, REPORT_LIMIT).into_iter().map(|(query, count)| (format!("`{}`", query), count)).collect();
+    let guilds = top(&totals.guilds, REPORT_LIMIT)
+        .into_iter()
+        .map(|(key, count)| {
+            let label = match key.parse::<u64>() {
+                Ok(id) => guild_names.get(&id).cloned().unwrap_or(key),
+                Err(_) => "Direct messages".to_string(),
+            };
+            (label, count)
+        })
+        .collect();
+
+    let sections = [
+        ("Top commands", command_lines(totals)),
+        ("Top maps", count_lines(maps)),
+        ("Top mods", count_lines(mods)),
+        ("Failed searches", count_lines(searches)),
+        ("Servers", count_lines(guilds)),
+    ];
+    sections
+        .into_iter()
+        .filter(|(_, lines)| !lines.is_empty())
+        .fold(embed.description(description), |embed, (name, lines)| embed.field(name, lines.join("\n"), false))
+}
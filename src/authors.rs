@@ -0,0 +1,178 @@
+use crate::{
+    mod_utils::{self, format_count},
+    pagination::{self, PAGE_SIZE},
+    types::{ApiModioMap, ApiModioUser, Data, BOT_EMBED_COLOR, MAP_TAG},
+};
+use poise::serenity_prelude as serenity;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tracing::info;
+
+/// ID Discord assigned to `/author`, known once commands are registered. Map embeds mention it to link the author's profile.
+static AUTHOR_COMMAND_ID: OnceLock<u64> = OnceLock::new();
+
+pub fn set_author_command_id(id: u64) {
+    let _ = AUTHOR_COMMAND_ID.set(id);
+}
+
+pub fn author_command_id() -> Option<u64> {
+    AUTHOR_COMMAND_ID.get().copied()
+}
+
+/// The "Author" field of a map embed: the name plus a clickable `/author` mention that opens the author's profile,
+/// or the mod.io profile link until the command is registered.
+pub fn author_field_value(user: &ApiModioUser, author_command_id: Option<u64>) -> String {
+    match (author_command_id, &user.profile_url) {
+        (Some(id), _) => format!("{} · </author:{}>", user.username, id),
+        (None, Some(url)) => format!("[{}]({})", user.username, url),
+        (None, None) => user.username.clone(),
+    }
+}
+
+/// A mod.io user who submitted at least one map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorEntry {
+    pub id: i32,
+    pub username: String,
+    pub profile_url: Option<String>,
+    pub map_ids: Vec<i32>,
+}
+
+/// Map submitters by mod.io user ID, rebuilt periodically from the map store.
+#[derive(Debug, Default)]
+pub struct AuthorIndex {
+    by_id: HashMap<i32, AuthorEntry>,
+}
+
+impl AuthorIndex {
+    /// Indexes the submitters of every `Map`-tagged entry.
+    pub fn from_maps<'a>(maps: impl IntoIterator<Item = &'a ApiModioMap>) -> Self {
+        let mut by_id: HashMap<i32, AuthorEntry> = HashMap::new();
+        for map in maps.into_iter().filter(|m| m.has_tag(MAP_TAG)) {
+            let user = &map.submitted_by;
+            by_id
+                .entry(user.id)
+                .or_insert_with(|| AuthorEntry {
+                    id: user.id,
+                    username: user.username.clone(),
+                    profile_url: user.profile_url.clone(),
+                    map_ids: Vec::new(),
+                })
+                .map_ids
+                .push(map.id);
+        }
+        Self { by_id }
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_id.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+
+    pub fn get(&self, id: i32) -> Option<&AuthorEntry> {
+        self.by_id.get(&id)
+    }
+
+    /// Case-insensitive username lookup. If several users share a name, the most prolific wins.
+    pub fn find_by_name(&self, name: &str) -> Option<&AuthorEntry> {
+        let name = name.trim();
+        self.by_id
+            .values()
+            .filter(|author| author.username.eq_ignore_ascii_case(name))
+            .max_by_key(|author| (author.map_ids.len(), -author.id))
+    }
+
+    /// Resolves an autocomplete value (the user ID) or a typed username.
+    pub fn resolve(&self, query: &str) -> Option<&AuthorEntry> {
+        query.trim().parse().ok().and_then(|id| self.get(id)).or_else(|| self.find_by_name(query))
+    }
+
+    /// Authors whose name contains `partial`, most maps first.
+    pub fn suggestions(&self, partial: &str, limit: usize) -> Vec<&AuthorEntry> {
+        let partial_lowercase = partial.trim().to_lowercase();
+        let mut matches: Vec<&AuthorEntry> = self
+            .by_id
+            .values()
+            .filter(|author| author.username.to_lowercase().contains(&partial_lowercase))
+            .collect();
+        matches.sort_by(|a, b| {
+            b.map_ids
+                .len()
+                .cmp(&a.map_ids.len())
+                .then_with(|| a.username.to_lowercase().cmp(&b.username.to_lowercase()))
+        });
+        matches.truncate(limit);
+        matches
+    }
+}
+
+/// Rebuilds `data.authors` from `maps`, see [`crate::map_cache`]. Returns how many authors were indexed.
+pub async fn rebuild_author_index(data: &Data, maps: &[ApiModioMap]) -> usize {
+    let index = AuthorIndex::from_maps(maps);
+    let count = index.len();
+    *data.authors.write().await = index;
+    info!(authors = count, maps = maps.len(), "Author index refreshed.");
+    count
+}
+
+/// Totals across an author's maps.
+#[derive(Debug, Clone)]
+pub struct AuthorStats<'a> {
+    pub map_count: usize,
+    pub downloads: i64,
+    pub subscribers: i64,
+    /// The most recently released map.
+    pub latest: Option<&'a ApiModioMap>,
+}
+
+pub fn author_stats(maps: &[ApiModioMap]) -> AuthorStats<'_> {
+    AuthorStats {
+        map_count: maps.len(),
+        downloads: maps.iter().map(|m| i64::from(m.stats.downloads_total)).sum(),
+        subscribers: maps.iter().map(|m| i64::from(m.stats.subscribers_total)).sum(),
+        latest: maps.iter().max_by_key(|m| m.date_live),
+    }
+}
+
+/// Most downloaded first, so the author's best-known work leads the list.
+pub fn sort_author_maps(maps: &mut [ApiModioMap]) {
+    maps.sort_by(|a, b| b.stats.downloads_total.cmp(&a.stats.downloads_total).then_with(|| a.name.cmp(&b.name)));
+}
+
+fn author_map_line(map: &ApiModioMap) -> String {
+    format!(
+        "[{}]({}) · ⬇️ {} · 👥 {}",
+        mod_utils::truncate_with_ellipsis(&map.name, 60),
+        map.profile_url,
+        format_count(i64::from(map.stats.downloads_total)),
+        format_count(i64::from(map.stats.subscribers_total))
+    )
+}
+
+/// One page of an author's maps, with their totals on every page. `maps` should already be sorted.
+pub fn build_author_embed(author: &AuthorEntry, maps: &[ApiModioMap], page: usize) -> serenity::CreateEmbed {
+    let stats = author_stats(maps);
+    let total_pages = pagination::page_count(maps.len(), PAGE_SIZE);
+    let lines: Vec<String> = pagination::page_slice(maps, page, PAGE_SIZE).iter().map(author_map_line).collect();
+    let latest = stats
+        .latest
+        .map(|m| format!("[{}]({}) · <t:{}:D>", m.name, m.profile_url, m.date_live))
+        .unwrap_or_else(|| "None".to_string());
+
+    let mut embed = serenity::CreateEmbed::default()
+        .title(format!("🛹 Maps by {}", author.username))
+        .description(lines.join("\n"))
+        .color(BOT_EMBED_COLOR)
+        .field("Maps", stats.map_count.to_string(), true)
+        .field("Downloads", format_count(stats.downloads), true)
+        .field("Subscribers", format_count(stats.subscribers), true)
+        .field("Latest Release", latest, false)
+        .footer(serenity::CreateEmbedFooter::new(format!("Page {}/{} · mod.io user {}", page + 1, total_pages, author.id)));
+    if let Some(url) = &author.profile_url {
+        embed = embed.url(url);
+    }
+    embed
+}
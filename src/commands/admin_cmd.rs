@@ -0,0 +1,101 @@
+use crate::{
+    analytics::{self, UsageWindow},
+    failed_searches,
+    link_health,
+    types::{Context, Error, SearchTarget, BOT_EMBED_COLOR},
+};
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+use std::collections::HashMap;
+use tracing::info;
+
+/// Bot administration commands (owners only).
+#[poise::command(slash_command, prefix_command, owners_only, subcommands("config", "links", "stats", "searches"), subcommand_required)]
+pub async fn admin(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Shows the effective bot configuration with secrets redacted.
+#[poise::command(slash_command, prefix_command, owners_only)]
+pub async fn config(ctx: Context<'_>) -> Result<(), Error> {
+    info!(user = %ctx.author().name, "Admin config command received");
+
+    let rendered = ctx.data().config.to_redacted_toml()?;
+    let embed = serenity::CreateEmbed::default()
+        .title("⚙️ Bot Configuration")
+        .description(format!("```toml\n{}\n```", rendered.trim()))
+        .color(BOT_EMBED_COLOR)
+        .timestamp(serenity::Timestamp::now())
+        .footer(serenity::CreateEmbedFooter::new(format!("Requested by {}", ctx.author().name)));
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Lists community mod download links that failed the last link check, per branch.
+#[poise::command(slash_command, prefix_command, owners_only)]
+pub async fn links(ctx: Context<'_>) -> Result<(), Error> {
+    info!(user = %ctx.author().name, "Admin links command received");
+
+    let statuses = ctx.data().link_health.all().await?;
+    if statuses.is_empty() {
+        let reply = CreateReply::default().content("No links have been checked yet. The link check runs on the `link_check` schedule.").ephemeral(true);
+        ctx.send(reply).await?;
+        return Ok(());
+    }
+    let report = link_health::broken_links_report(&*ctx.data().mod_cache.read().await, &statuses);
+    let embed = link_health::build_link_report_embed(&report, statuses.len()).timestamp(serenity::Timestamp::now());
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Reports command usage: top commands, maps and mods, failed searches and servers.
+#[poise::command(slash_command, prefix_command, owners_only)]
+pub async fn stats(
+    ctx: Context<'_>,
+    #[description = "How far back to look (default: last 7 days)"] window: Option<UsageWindow>,
+) -> Result<(), Error> {
+    let window = window.unwrap_or_default();
+    info!(user = %ctx.author().name, window = ?window, "Admin stats command received");
+
+    let totals = ctx.data().analytics.totals(&window.days(serenity::Timestamp::now().unix_timestamp())).await?;
+    let map_ids: Vec<i32> = analytics::top(&totals.maps, analytics::REPORT_LIMIT).into_iter().map(|(id, _)| id).collect();
+    let map_names: HashMap<i32, String> = ctx
+        .data()
+        .maps
+        .get_many(&map_ids)
+        .await?
+        .into_iter()
+        .flatten()
+        .map(|map| (map.id, map.name))
+        .collect();
+    let guild_names: HashMap<u64, String> = totals
+        .guilds
+        .keys()
+        .filter_map(|key| key.parse::<u64>().ok())
+        .filter_map(|id| Some((id, ctx.cache().guild(serenity::GuildId::new(id))?.name.clone())))
+        .collect();
+
+    let embed = analytics::build_usage_embed(window, &totals, &map_names, &guild_names)
+        .timestamp(serenity::Timestamp::now())
+        .footer(serenity::CreateEmbedFooter::new(format!("Requested by {}", ctx.author().name)));
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Lists the most common map or mod searches that found nothing, with the closest existing titles.
+#[poise::command(slash_command, prefix_command, owners_only)]
+pub async fn searches(
+    ctx: Context<'_>,
+    #[description = "Map or mod searches"] target: SearchTarget,
+) -> Result<(), Error> {
+    info!(user = %ctx.author().name, target = ?target, "Admin searches command received");
+
+    let searches = ctx.data().failed_searches.top(target, failed_searches::REPORT_LIMIT).await?;
+    let titles = if searches.is_empty() { Vec::new() } else { failed_searches::candidate_titles(ctx.data(), target).await? };
+    let embed = failed_searches::build_failed_searches_embed(target, &searches, &titles).timestamp(serenity::Timestamp::now());
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use crate::{
+    authors,
+    mod_utils,
+    pagination::{self, PAGE_SIZE},
+    types::{Context, Error},
+};
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+use tracing::{info, warn};
+
+const AUTOCOMPLETE_LIMIT: usize = 25;
+
+async fn author_autocomplete(ctx: Context<'_>, partial: &str) -> Vec<serenity::AutocompleteChoice> {
+    let index = ctx.data().authors.read().await;
+    index
+        .suggestions(partial, AUTOCOMPLETE_LIMIT)
+        .into_iter()
+        .map(|author| {
+            let plural = if author.map_ids.len() == 1 { "" } else { "s" };
+            let label = format!("{} ({} map{})", mod_utils::truncate_with_ellipsis(&author.username, 80), author.map_ids.len(), plural);
+            serenity::AutocompleteChoice::new(label, author.id.to_string())
+        })
+        .collect()
+}
+
+/// Show every map by a mod.io author, with their totals.
+#[poise::command(slash_command, prefix_command)]
+pub async fn author(
+    ctx: Context<'_>,
+    #[description = "Author name (use autocomplete)"]
+    #[autocomplete = "author_autocomplete"]
+    name: String,
+) -> Result<(), Error> {
+    info!(user = %ctx.author().name, query = %name, "Author command received");
+    let Some(author) = ctx.data().authors.read().await.resolve(&name).cloned() else {
+        warn!(query = %name, "Author not found in index");
+        let reply = CreateReply::default()
+            .content(format!("❌ No map author found matching '{}'. Use the autocomplete suggestions.", name))
+            .ephemeral(true);
+        ctx.send(reply).await?;
+        return Ok(());
+    };
+
+    let mut maps: Vec<_> = ctx.data().maps.get_many(&author.map_ids).await?.into_iter().flatten().collect();
+    authors::sort_author_maps(&mut maps);
+    let total_pages = pagination::page_count(maps.len(), PAGE_SIZE);
+    pagination::paginate(ctx, total_pages, |page| authors::build_author_embed(&author, &maps, page)).await
+}
@@ -0,0 +1,104 @@
+use crate::{
+    config,
+    digest::{self, DEFAULT_DIGEST_CRON},
+    types::{Context, DigestSubscription, Error},
+};
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+use tracing::info;
+
+async fn reply_ephemeral(ctx: Context<'_>, content: impl Into<String>) -> Result<(), Error> {
+    ctx.send(CreateReply::default().content(content).ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Scheduled summary of new maps, download gains and mod list changes.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    subcommands("enable", "disable", "status", "preview"),
+    subcommand_required,
+    required_permissions = "MANAGE_GUILD",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn digest(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Posts the digest in a channel on a schedule. Run it again to change the channel or schedule.
+#[poise::command(slash_command, prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn enable(
+    ctx: Context<'_>,
+    #[description = "Channel to post in (defaults to this one)"]
+    #[channel_types("Text", "News")]
+    channel: Option<serenity::GuildChannel>,
+    #[description = "Cron expression with seconds, UTC (defaults to Sundays 17:00)"] schedule: Option<String>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return Ok(()) };
+    let now = serenity::Timestamp::now().unix_timestamp();
+    let cron = schedule.map(|s| s.trim().to_string()).unwrap_or_else(|| DEFAULT_DIGEST_CRON.to_string());
+    if let Err(e) = digest::validate_digest_cron(&cron, now) {
+        return reply_ephemeral(ctx, format!("❌ `{}` isn't a usable schedule: {}.", cron, e)).await;
+    }
+    let channel_id = channel.map_or(ctx.channel_id(), |c| c.id);
+
+    let subscription = match ctx.data().digests.get(guild_id.get()).await? {
+        Some(existing) => DigestSubscription { channel_id: channel_id.get(), cron: cron.clone(), ..existing },
+        None => DigestSubscription {
+            guild_id: guild_id.get(),
+            channel_id: channel_id.get(),
+            cron: cron.clone(),
+            subscribed_at: now,
+            last_posted_at: None,
+            last_attempt_at: None,
+            mods_seen: digest::mod_snapshot(&*ctx.data().mod_cache.read().await),
+        },
+    };
+    ctx.data().digests.save(&subscription).await?;
+    info!(user = %ctx.author().name, guild_id = %guild_id, channel_id = %channel_id, cron = %cron, "Digest enabled");
+
+    let next = config::next_cron_run(&cron, now).map(|at| format!(" The next one is due <t:{}:R>.", at)).unwrap_or_default();
+    reply_ephemeral(ctx, format!("📰 Digests will be posted in <#{}> on `{}` (UTC).{}", channel_id, cron, next)).await
+}
+
+/// Stops posting digests in this server.
+#[poise::command(slash_command, prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn disable(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return Ok(()) };
+    if !ctx.data().digests.remove(guild_id.get()).await? {
+        return reply_ephemeral(ctx, "Digests aren't enabled here.").await;
+    }
+    info!(user = %ctx.author().name, guild_id = %guild_id, "Digest disabled");
+    reply_ephemeral(ctx, "Digests disabled.").await
+}
+
+/// Shows where and when digests are posted.
+#[poise::command(slash_command, prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return Ok(()) };
+    let Some(subscription) = ctx.data().digests.get(guild_id.get()).await? else {
+        return reply_ephemeral(ctx, "Digests aren't enabled here. Turn them on with `/digest enable`.").await;
+    };
+    let now = serenity::Timestamp::now().unix_timestamp();
+    let last = subscription.last_posted_at.map_or("never".to_string(), |at| format!("<t:{}:R>", at));
+    let next = config::next_cron_run(&subscription.cron, now).map_or("never".to_string(), |at| format!("<t:{}:R>", at));
+    reply_ephemeral(
+        ctx,
+        format!("Posting in <#{}> on `{}` (UTC)\nLast digest: {} · Next: {}", subscription.channel_id, subscription.cron, last, next),
+    )
+    .await
+}
+
+/// Shows what the next digest would contain, without posting it.
+#[poise::command(slash_command, prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn preview(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return Ok(()) };
+    let Some(subscription) = ctx.data().digests.get(guild_id.get()).await? else {
+        return reply_ephemeral(ctx, "Digests aren't enabled here. Turn them on with `/digest enable`.").await;
+    };
+    ctx.defer_ephemeral().await?;
+    let report = digest::compile_digest(ctx.data(), &subscription, serenity::Timestamp::now().unix_timestamp()).await?;
+    ctx.send(CreateReply::default().embed(digest::build_digest_embed(&report)).ephemeral(true)).await?;
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use crate::{
+    mod_utils,
+    pagination::{self, PAGE_SIZE},
+    repository::MapRepository,
+    types::{Collection, Context, Error, FavoriteItem, SCRIPT_MOD_TAG},
+};
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+use rand::Rng;
+use tracing::{error, info, warn};
+
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789"; // No 0/O or 1/I lookalikes
+const CODE_LENGTH: usize = 6;
+const MAX_CODE_ATTEMPTS: usize = 5;
+const MAX_COLLECTION_NAME_CHARS: usize = 50;
+
+pub fn generate_collection_code(rng: &mut impl Rng) -> String {
+    (0..CODE_LENGTH).map(|_| CODE_ALPHABET[rng.gen_range(0..CODE_ALPHABET.len())] as char).collect()
+}
+
+/// Codes are shown in upper case but accepted in any case, with stray whitespace.
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+/// One display line per favorite, resolving map names through the map store.
+/// Maps that vanished from the store are still listed so they can be removed.
+pub async fn describe_favorites(maps: &dyn MapRepository, items: &[FavoriteItem]) -> Vec<String> {
+    let map_ids: Vec<i32> = items
+        .iter()
+        .filter_map(|item| match item {
+            FavoriteItem::Map { id } => Some(*id),
+            FavoriteItem::Mod { .. } => None,
+        })
+        .collect();
+    let found_maps = match maps.get_many(&map_ids).await {
+        Ok(found) => found,
+        Err(e) => {
+            error!("Favorites: Map lookup failed: {}", e);
+            vec![None; map_ids.len()]
+        }
+    };
+    let mut found_maps = found_maps.into_iter();
+
+    items
+        .iter()
+        .map(|item| match item {
+            FavoriteItem::Map { id } => match found_maps.next().flatten() {
+                Some(map) if map.has_tag(SCRIPT_MOD_TAG) => format!("📜 [{}]({})", map.name, map.profile_url),
+                Some(map) => format!("🗺️ [{}]({})", map.name, map.profile_url),
+                None => format!("🗺️ Map #{} (no longer available)", id),
+            },
+            FavoriteItem::Mod { title, branch } => format!("🧩 **{}** ({})", title, branch),
+        })
+        .collect()
+}
+
+fn favorite_label(line: &str) -> String {
+    // Autocomplete labels can't render markdown, so strip it from the display line.
+    let plain = match (line.find('['), line.find("](")) {
+        (Some(start), Some(end)) if start < end => format!("{}{}", &line[..start], &line[start + 1..end]),
+        _ => line.replace("**", ""),
+    };
+    mod_utils::truncate_with_ellipsis(&plain, 100)
+}
+
+/// Autocomplete choices for a list of items: a plain-text label, with the item key as the value.
+pub async fn item_choices(maps: &dyn MapRepository, items: &[FavoriteItem], partial: &str) -> Vec<serenity::AutocompleteChoice> {
+    let lines = describe_favorites(maps, items).await;
+    let partial_lowercase = partial.to_lowercase();
+
+    items
+        .iter()
+        .zip(lines)
+        .map(|(item, line)| (item, favorite_label(&line)))
+        .filter(|(_, label)| label.to_lowercase().contains(&partial_lowercase))
+        .take(25)
+        .map(|(item, label)| serenity::AutocompleteChoice::new(label, item.key()))
+        .collect()
+}
+
+async fn favorite_autocomplete(ctx: Context<'_>, partial: &str) -> Vec<serenity::AutocompleteChoice> {
+    match ctx.data().favorites.list(ctx.author().id.get()).await {
+        Ok(items) => item_choices(ctx.data().maps.as_ref(), &items, partial).await,
+        Err(e) => {
+            error!("Autocomplete: Failed to load favorites: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Your starred maps and mods, and shareable collections of them.
+#[poise::command(slash_command, prefix_command, subcommands("list", "remove", "share", "load", "collections"), subcommand_required)]
+pub async fn favorites(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Lists your favorite maps and mods.
+#[poise::command(slash_command, prefix_command)]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    let items = ctx.data().favorites.list(ctx.author().id.get()).await?;
+    info!(user = %ctx.author().name, count = items.len(), "Favorites list requested");
+
+    if items.is_empty() {
+        let reply = CreateReply::default()
+            .content("You have no favorites yet. Press ⭐ **Favorite** under a `/map` or `/mod` result to add one.")
+            .ephemeral(true);
+        ctx.send(reply).await?;
+        return Ok(());
+    }
+
+    let lines = describe_favorites(ctx.data().maps.as_ref(), &items).await;
+    let title = format!("⭐ {}'s Favorites", ctx.author().name);
+    let footer = format!("{} favorites · Share them with /favorites share", lines.len());
+    let total_pages = pagination::page_count(lines.len(), PAGE_SIZE);
+    pagination::paginate(ctx, total_pages, |page| pagination::build_list_page_embed(&lines, page, &title, &footer)).await
+}
+
+/// Removes a map or mod from your favorites.
+#[poise::command(slash_command, prefix_command)]
+pub async fn remove(
+    ctx: Context<'_>,
+    #[description = "Favorite to remove"]
+    #[autocomplete = "favorite_autocomplete"]
+    favorite: String,
+) -> Result<(), Error> {
+    let Some(item) = FavoriteItem::from_key(&favorite) else {
+        ctx.send(CreateReply::default().content("Please pick a favorite from the autocomplete list.").ephemeral(true)).await?;
+        return Ok(());
+    };
+    let removed = ctx.data().favorites.remove(ctx.author().id.get(), &item).await?;
+    let content = if removed { "Removed from your favorites." } else { "That wasn't in your favorites." };
+    ctx.send(CreateReply::default().content(content).ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Shares your current favorites as a named collection with a short code.
+#[poise::command(slash_command, prefix_command)]
+pub async fn share(
+    ctx: Context<'_>,
+    #[description = "Collection name, e.g. \"my street setup\""]
+    #[max_length = 50]
+    name: String,
+) -> Result<(), Error> {
+    let user_id = ctx.author().id.get();
+    let name = mod_utils::truncate_with_ellipsis(name.trim(), MAX_COLLECTION_NAME_CHARS);
+    let items = ctx.data().favorites.list(user_id).await?;
+    if items.is_empty() || name.is_empty() {
+        let reply = CreateReply::default()
+            .content("You need at least one favorite and a collection name to share a collection.")
+            .ephemeral(true);
+        ctx.send(reply).await?;
+        return Ok(());
+    }
+
+    for _ in 0..MAX_CODE_ATTEMPTS {
+        let collection = Collection {
+            code: generate_collection_code(&mut rand::thread_rng()),
+            name: name.clone(),
+            owner_id: user_id,
+            items: items.clone(),
+        };
+        if ctx.data().favorites.create_collection(&collection).await? {
+            info!(user = %ctx.author().name, code = %collection.code, count = items.len(), "Collection shared");
+            ctx.say(format!(
+                "📁 Shared **{}** ({} items). Anyone can open it with `/favorites load code:{}`.",
+                collection.name, collection.items.len(), collection.code
+            ))
+            .await?;
+            return Ok(());
+        }
+        warn!(code = %collection.code, "Collection code collision, retrying");
+    }
+
+    Err(anyhow::anyhow!("Could not find a free collection code after {} attempts", MAX_CODE_ATTEMPTS))
+}
+
+/// Opens a shared collection, optionally adding everything to your favorites.
+#[poise::command(slash_command, prefix_command)]
+pub async fn load(
+    ctx: Context<'_>,
+    #[description = "Collection code"] code: String,
+    #[description = "Add all items to your favorites"] import: Option<bool>,
+) -> Result<(), Error> {
+    let code = normalize_code(&code);
+    let Some(collection) = ctx.data().favorites.get_collection(&code).await? else {
+        ctx.send(CreateReply::default().content(format!("❌ No collection found with code `{}`.", code)).ephemeral(true)).await?;
+        return Ok(());
+    };
+
+    if import.unwrap_or(false) {
+        let added = ctx.data().favorites.add_many(ctx.author().id.get(), &collection.items).await?;
+        info!(user = %ctx.author().name, %code, added, "Collection imported");
+        let reply = CreateReply::default()
+            .content(format!("Added {} new favorites from **{}**.", added, collection.name))
+            .ephemeral(true);
+        ctx.send(reply).await?;
+        return Ok(());
+    }
+
+    let lines = describe_favorites(ctx.data().maps.as_ref(), &collection.items).await;
+    let title = format!("📁 {}", collection.name);
+    let footer = format!("Code {} · {} items · Import with /favorites load import:True", collection.code, lines.len());
+    let total_pages = pagination::page_count(lines.len(), PAGE_SIZE);
+    pagination::paginate(ctx, total_pages, |page| {
+        pagination::build_list_page_embed(&lines, page, &title, &footer).field("Shared by", format!("<@{}>", collection.owner_id), true)
+    })
+    .await
+}
+
+/// Lists the collections you have shared.
+#[poise::command(slash_command, prefix_command)]
+pub async fn collections(ctx: Context<'_>) -> Result<(), Error> {
+    let owned = ctx.data().favorites.list_collections(ctx.author().id.get()).await?;
+    if owned.is_empty() {
+        let reply = CreateReply::default()
+            .content("You haven't shared any collections yet. Use `/favorites share` to create one.")
+            .ephemeral(true);
+        ctx.send(reply).await?;
+        return Ok(());
+    }
+
+    let lines: Vec<String> = owned.iter().map(|c| format!("📁 **{}** — `{}` ({} items)", c.name, c.code, c.items.len())).collect();
+    let title = format!("📁 {}'s Collections", ctx.author().name);
+    let footer = format!("{} collections · Open one with /favorites load", lines.len());
+    let total_pages = pagination::page_count(lines.len(), PAGE_SIZE);
+    pagination::paginate(ctx, total_pages, |page| pagination::build_list_page_embed(&lines, page, &title, &footer)).await
+}
@@ -1,247 +1,209 @@
-use crate::types::{Context, Error, ApiModioMap, BOT_EMBED_COLOR, MAP_TAG};
+use crate::{
+    analytics,
+    authors,
+    components,
+    failed_searches,
+    mod_utils,
+    repository::{MapRepository, TitleIndexEntry},
+    types::{Context, Error, ApiModioMap, SearchTarget, BOT_EMBED_COLOR, MAP_TAG},
+};
 use poise::serenity_prelude as serenity;
 use poise::CreateReply;
 use tracing::{info, warn, error};
-use deadpool_redis::redis::AsyncCommands; // Corrected import for the trait
 
-fn normalize_title_for_redis(title: &str) -> String {
-    title.to_lowercase().trim().to_string()
-}
+const AUTOCOMPLETE_LIMIT: usize = 25; // Discord's limit for autocomplete choices
 
-async fn map_name_autocomplete(
-    ctx: Context<'_>,
-    partial: &str,
-) -> Vec<String> {
-    let mut final_suggestions = Vec::new();
-    let limit = 25; // Discord's limit for autocomplete choices
+/// Signed download URLs this close to expiry are not shown; they'd likely be dead by the time someone clicks.
+pub const DOWNLOAD_EXPIRY_MARGIN_SECS: i64 = 10 * 60;
+/// Signed download URLs expiring within this window show when they stop working.
+pub const DOWNLOAD_EXPIRY_NOTICE_SECS: i64 = 24 * 60 * 60;
 
-    let mut redis_conn = match ctx.data().redis_pool.get().await {
-        Ok(conn) => conn,
+fn format_map_suggestion(title: &str, id: i32) -> String {
+    format!("{} (ID: {})", mod_utils::truncate_with_ellipsis(title, 80), id)
+}
+
+/// Builds `"Name (ID: 123)"` autocomplete choices from `tag`'s title index. Entries missing from the store fall back to their indexed title.
+pub async fn title_suggestions(maps: &dyn MapRepository, tag: &str, partial: &str) -> Vec<String> {
+    let entries = match maps.search_titles(tag, partial, AUTOCOMPLETE_LIMIT).await {
+        Ok(entries) => entries,
         Err(e) => {
-            error!("Autocomplete: Failed to get Redis connection: {}", e);
-            return final_suggestions; // Return empty on error
+            error!("Autocomplete: Title index lookup failed: {}", e);
+            return Vec::new();
         }
     };
 
-    let redis_key = "mod_titles:map";
-    let members_str: Vec<String> = if partial.is_empty() {
-        // Fetch first `limit` members if partial is empty (default suggestions)
-        info!("Autocomplete: Partial is empty, fetching default suggestions.");
-        match redis_conn.zrange(redis_key, 0, (limit - 1) as isize).await {
-            Ok(members) => members,
-            Err(e) => {
-                error!("Autocomplete: Redis ZRANGE error for default suggestions: {}", e);
-                return final_suggestions;
-            }
+    if entries.is_empty() {
+        return Vec::new();
+    }
+
+    let ids: Vec<i32> = entries.iter().map(|entry| entry.id).collect();
+    let mut final_suggestions: Vec<String> = match maps.get_many(&ids).await {
+        Ok(found) => entries
+            .iter()
+            .zip(found)
+            .map(|(entry, map)| match map {
+                Some(map_data) => format_map_suggestion(&map_data.name, map_data.id),
+                None => format_map_suggestion(&entry.title, entry.id),
+            })
+            .collect(),
+        Err(e) => {
+            error!("Autocomplete: Map details lookup failed: {}. Falling back to indexed titles.", e);
+            entries.iter().map(|entry| format_map_suggestion(&entry.title, entry.id)).collect()
         }
+    };
+
+    final_suggestions.truncate(AUTOCOMPLETE_LIMIT);
+    final_suggestions
+}
+
+/// [`title_suggestions`] for maps.
+pub async fn map_suggestions(maps: &dyn MapRepository, partial: &str) -> Vec<String> {
+    title_suggestions(maps, MAP_TAG, partial).await
+}
+
+pub async fn map_name_autocomplete(
+    ctx: Context<'_>,
+    partial: &str,
+) -> Vec<String> {
+    map_suggestions(ctx.data().maps.as_ref(), partial).await
+}
+
+/// Extracts the ID from an autocomplete choice such as `"Some Park (ID: 123)"`.
+pub fn parse_map_id(search: &str) -> Option<i32> {
+    let start_idx = search.rfind("(ID: ")?;
+    let end_idx = search.rfind(')')?;
+    if start_idx < end_idx {
+        search[start_idx + 5..end_idx].parse::<i32>().ok()
     } else {
-        // Existing logic for when partial is not empty
-        let partial_normalized = normalize_title_for_redis(partial);
-        let min_lex = format!("[{}", partial_normalized);
-        let max_lex = format!("[{}{}", partial_normalized, std::char::from_u32(0xFF).unwrap_or('~'));
-        
-        match redis_conn.zrangebylex_limit(redis_key, min_lex.clone(), max_lex.clone(), 0, limit as isize).await {
-            Ok(members) => members,
-            Err(e) => {
-                error!("Autocomplete: Redis ZRANGEBYLEX error: {}", e);
-                return final_suggestions;
-            }
+        None
+    }
+}
+
+async fn get_tagged(maps: &dyn MapRepository, tag: &str, id: i32) -> Result<Option<ApiModioMap>, Error> {
+    match maps.get_by_id(id).await? {
+        Some(map_item) if map_item.has_tag(tag) => Ok(Some(map_item)),
+        Some(_) => {
+            info!("{} Lookup: Mod ID {} found but is not tagged as {}.", tag, id, tag);
+            Ok(None)
         }
-    };
+        None => {
+            info!("{} Lookup: No entry found for ID: {}", tag, id);
+            Ok(None)
+        }
+    }
+}
 
-    if members_str.is_empty() {
-        return final_suggestions;
+/// Resolves a search term against `tag`'s entries: an autocomplete choice is looked up by ID,
+/// anything else must be an unambiguous title prefix. Errors mean the store itself was unreachable.
+pub async fn find_tagged(maps: &dyn MapRepository, tag: &str, search: &str) -> Result<Option<ApiModioMap>, Error> {
+    if let Some(id) = parse_map_id(search) {
+        info!("{} Lookup: Attempting to fetch entry by ID: {}", tag, id);
+        return get_tagged(maps, tag, id).await;
     }
 
-    let mut mod_ids_to_fetch: Vec<String> = Vec::new();
-    let mut member_map: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    warn!("{} Lookup: No ID parsed from search term: '{}'. Attempting prefix search.", tag, search);
+    let entries: Vec<TitleIndexEntry> = maps.search_titles(tag, search, 2).await?;
 
-    for member in members_str {
-        if let Some(colon_idx) = member.rfind(':') {
-            let id_part = member[colon_idx+1..].to_string();
-            let mod_key_for_mget = format!("mod:{}", id_part);
-            mod_ids_to_fetch.push(mod_key_for_mget.clone());
-            member_map.insert(mod_key_for_mget, member.clone());
+    match entries.as_slice() {
+        [entry] => {
+            info!("{} Lookup: Single match from prefix search, fetching mod: {}", tag, entry.id);
+            get_tagged(maps, tag, entry.id).await
+        }
+        [] => {
+            info!("{} Lookup: No matches found for manual search: '{}'.", tag, search);
+            Ok(None)
+        }
+        _ => {
+            info!("{} Lookup: Multiple potential matches for manual search: '{}'. Suggesting autocomplete.", tag, search);
+            Ok(None)
         }
     }
+}
 
-    if !mod_ids_to_fetch.is_empty() {
-        match redis_conn.mget::<Vec<String>, Vec<Option<String>>>(mod_ids_to_fetch.clone()).await {
-            Ok(mod_jsons) => {
-                for (i, mod_json_opt) in mod_jsons.into_iter().enumerate() {
-                    let original_member_key = &mod_ids_to_fetch[i]; // key used for member_map
-                    if let Some(mod_json_str) = mod_json_opt { 
-                        if let Ok(mod_data) = serde_json::from_str::<ApiModioMap>(&mod_json_str) {
-                            let display_title = if mod_data.name.len() > 80 {
-                                format!("{}...", &mod_data.name[..77])
-                            } else {
-                                mod_data.name.clone()
-                            };
-                            final_suggestions.push(format!("{} (ID: {})", display_title, mod_data.id));
-                        } else {
-                             // Deserialization failed, use fallback from member_map
-                             if let Some(original_member) = member_map.get(original_member_key) {
-                                if let Some(colon_idx) = original_member.rfind(':') {
-                                    let title_part = &original_member[..colon_idx];
-                                    let id_part = &original_member[colon_idx+1..];
-                                    let display_title = if title_part.len() > 80 { format!("{}...", &title_part[..77]) } else { title_part.to_string() };
-                                    final_suggestions.push(format!("{} (ID: {})", display_title, id_part));
-                                }
-                             }
-                        }
-                    } else {
-                        // MGET returned None for this key, use fallback
-                        if let Some(original_member) = member_map.get(original_member_key) {
-                           if let Some(colon_idx) = original_member.rfind(':') {
-                               let title_part = &original_member[..colon_idx];
-                               let id_part = &original_member[colon_idx+1..];
-                               let display_title = if title_part.len() > 80 { format!("{}...", &title_part[..77]) } else { title_part.to_string() };
-                               final_suggestions.push(format!("{} (ID: {})", display_title, id_part));
-                           }
-                        }
-                   }
-                   if final_suggestions.len() >= limit { break; }
-                }
-            }
-            Err(e) => {
-                error!("Autocomplete: Redis MGET error for mod details: {}. Falling back to ZSET members.", e);
-                for member_key_with_prefix in mod_ids_to_fetch { 
-                    if let Some(original_member) = member_map.get(&member_key_with_prefix) {
-                        if let Some(colon_idx) = original_member.rfind(':') {
-                            let title_part = &original_member[..colon_idx];
-                            let id_part = &original_member[colon_idx+1..];
-                            let display_title = if title_part.len() > 80 { format!("{}...", &title_part[..77]) } else { title_part.to_string() };
-                            final_suggestions.push(format!("{} (ID: {})", display_title, id_part));
-                        }
-                        if final_suggestions.len() >= limit { break; }
-                    }
-                }
-            }
-        }
+/// Resolves a `/map` search term, see [`find_tagged`].
+pub async fn find_map(maps: &dyn MapRepository, search: &str) -> Result<Option<ApiModioMap>, Error> {
+    find_tagged(maps, MAP_TAG, search).await
+}
+
+/// The download link for an entry's modfile as of `now`. mod.io's `binary_url` is signed and expires,
+/// and the copy cached in Redis may be stale, so expired (or nearly expired) links point to the mod.io page instead.
+pub fn download_field_value(entry: &ApiModioMap, download_label: &str, now: i64) -> String {
+    let Some(download) = entry.modfile.as_ref().map(|mf| &mf.download).filter(|d| !d.binary_url.is_empty()) else {
+        return "No download link".to_string();
+    };
+    let expires_at = download.date_expires;
+    if expires_at > 0 && expires_at - now <= DOWNLOAD_EXPIRY_MARGIN_SECS {
+        format!("[{} on mod.io]({}) (direct link expired)", download_label, entry.profile_url)
+    } else if expires_at > 0 && expires_at - now <= DOWNLOAD_EXPIRY_NOTICE_SECS {
+        format!("[{}]({}) · expires <t:{}:R>", download_label, download.binary_url, expires_at)
+    } else {
+        format!("[{}]({})", download_label, download.binary_url)
     }
-    
-    final_suggestions.truncate(limit); 
-    final_suggestions
 }
 
-/// Search for a Skater XL map by name.
+pub fn build_map_embed(entry: &ApiModioMap, requested_by: &str) -> serenity::CreateEmbed {
+    build_modio_embed(entry, "Download Map", requested_by)
+}
+
+/// Detail embed for any mod.io entry; `download_label` names the download link.
+pub fn build_modio_embed(entry: &ApiModioMap, download_label: &str, requested_by: &str) -> serenity::CreateEmbed {
+    let author = authors::author_field_value(&entry.submitted_by, authors::author_command_id());
+    let now = serenity::Timestamp::now();
+    let download_field_value = download_field_value(entry, download_label, now.unix_timestamp());
+    let size_mb = entry.modfile.as_ref().and_then(|mf| mf.filesize).map(|s| format!("{:.2} MB", s as f64 / (1024.0 * 1024.0))).unwrap_or_else(|| "Unknown".to_string());
+    let tags_str = entry.tags.as_ref().filter(|tv| !tv.is_empty()).map(|tv| tv.iter().map(|t| t.name.as_str()).collect::<Vec<_>>().join(", ")).unwrap_or_else(|| "None".to_string());
+    let image_url = entry.logo.thumb_1280x720.as_deref().unwrap_or(entry.logo.original.as_str());
+    let stats = format!(
+        "⬇️ {} · 👥 {} · {}",
+        mod_utils::format_count(i64::from(entry.stats.downloads_total)),
+        mod_utils::format_count(i64::from(entry.stats.subscribers_total)),
+        entry.stats.ratings_display_text
+    );
+
+    serenity::CreateEmbed::default()
+        .title(&entry.name)
+        .url(&entry.profile_url)
+        .description(&entry.summary)
+        .color(BOT_EMBED_COLOR)
+        .image(image_url)
+        .field("Author", author, true)
+        .field("Size", &size_mb, true)
+        .field("Stats", stats, false)
+        .field("Tags", tags_str, false)
+        .field("Link", download_field_value, false)
+        .timestamp(now)
+        .footer(serenity::CreateEmbedFooter::new(format!("ID: {} | Source: mod.io | Requested by {}", entry.id, requested_by)))
+}
+
+/// Find Skater XL maps.
 #[poise::command(slash_command, prefix_command)]
 pub async fn map(
     ctx: Context<'_>,
     #[description = "Map name or ID (use autocomplete for best results)"]
     #[autocomplete = "map_name_autocomplete"]
+    #[rest]
     search: String,
 ) -> Result<(), Error> {
     info!(user = %ctx.author().name, query = %search, "Map command received");
 
-    let mut redis_conn = match ctx.data().redis_pool.get().await {
-        Ok(conn) => conn,
+    let found_map_data = match find_map(ctx.data().maps.as_ref(), &search).await {
+        Ok(found) => found,
         Err(e) => {
-            error!("Map Command: Failed to get Redis connection: {}", e);
+            error!("Map Command: Failed to query the map database: {}", e);
             ctx.say("Sorry, I couldn't connect to the map database right now. Please try again later.").await?;
             return Ok(());
         }
     };
 
-    let mut mod_id_to_fetch: Option<i32> = None;
-
-    if let Some(start_idx) = search.rfind("(ID: ") {
-        if let Some(end_idx) = search.rfind(')') {
-            if start_idx < end_idx {
-                let id_str = &search[start_idx + 5..end_idx];
-                if let Ok(id) = id_str.parse::<i32>() {
-                    mod_id_to_fetch = Some(id);
-                }
-            }
-        }
-    }
-
-    let mut found_map_data: Option<ApiModioMap> = None;
-
-    if let Some(id) = mod_id_to_fetch {
-        let mod_key = format!("mod:{}", id);
-        info!("Map Command: Attempting to fetch map by ID from Redis: {}", mod_key);
-        match redis_conn.get::<&str, Option<String>>(&mod_key).await {
-            Ok(Some(mod_json_str)) => { 
-                match serde_json::from_str::<ApiModioMap>(&mod_json_str) {
-                    Ok(map_item) => {
-                        if map_item.tags.as_ref().map_or(false, |tags| tags.iter().any(|t| t.name == MAP_TAG)) {
-                           found_map_data = Some(map_item);
-                        } else {
-                            info!("Map Command: Mod ID {} found but is not tagged as a Map.", id);
-                        }
-                    }
-                    Err(e) => error!("Map Command: Failed to deserialize mod JSON from Redis for ID {}: {}", id, e),
-                }
-            }
-            Ok(None) => info!("Map Command: No map found in Redis for ID: {}", id),
-            Err(e) => error!("Map Command: Redis GET error for mod ID {}: {}", id, e),
-        }
-    } else {
-        warn!("Map Command: No ID parsed from search term: '{}'. Attempting prefix search.", search);
-        let normalized_search = normalize_title_for_redis(&search);
-        let redis_key = "mod_titles:map";
-        let min_lex = format!("[{}", normalized_search);
-        let max_lex = format!("[{}{}", normalized_search, std::char::from_u32(0xFF).unwrap_or('~'));
-
-        match redis_conn.zrangebylex_limit(redis_key, min_lex, max_lex, 0, 2).await {
-            Ok(members_str) => {
-                let members: Vec<String> = members_str;
-                if members.len() == 1 {
-                    if let Some(colon_idx) = members[0].rfind(':') {
-                        let id_str = &members[0][colon_idx+1..];
-                        if let Ok(id) = id_str.parse::<i32>() {
-                            let mod_key = format!("mod:{}", id);
-                            info!("Map Command: Single match from prefix search, fetching mod: {}", id);
-                            match redis_conn.get::<&str, Option<String>>(&mod_key).await {
-                                Ok(Some(mod_json_str)) => {
-                                    if let Ok(map_item) = serde_json::from_str::<ApiModioMap>(&mod_json_str) {
-                                        if map_item.tags.as_ref().map_or(false, |tags| tags.iter().any(|t| t.name == MAP_TAG)) {
-                                            found_map_data = Some(map_item);
-                                        }
-                                    }
-                                }
-                                Ok(None) => info!("Map Command: Mod (from prefix search) not found in Redis for ID: {}", id),
-                                Err(e) => error!("Map Command: Redis GET error for mod ID {} (from prefix search): {}", id, e),
-                            }
-                        }
-                    }
-                } else if members.len() > 1 {
-                     info!("Map Command: Multiple potential matches for manual search: '{}'. Suggesting autocomplete.", search);
-                } else {
-                    info!("Map Command: No matches found for manual search: '{}'.", search);
-                }
-            }
-            Err(e) => error!("Map Command: Redis ZRANGEBYLEX error for manual search: {}", e),
-        }
-    }
-
     let reply_message = if let Some(entry) = found_map_data {
         info!(map_name = %entry.name, map_id = entry.id, "Map found and processed");
-
-        let author = &entry.submitted_by.username;
-        let download_link = entry.modfile.as_ref().map(|mf| mf.download.binary_url.as_str()).unwrap_or("N/A");
-        let download_field_value = if download_link == "N/A" { "No download link".to_string() } else { format!("[Download Map]({})", download_link) };
-        let size_mb = entry.modfile.as_ref().and_then(|mf| mf.filesize).map(|s| format!("{:.2} MB", s as f64 / (1024.0 * 1024.0))).unwrap_or_else(|| "Unknown".to_string());
-        let tags_str = entry.tags.as_ref().filter(|tv| !tv.is_empty()).map(|tv| tv.iter().map(|t| t.name.as_str()).collect::<Vec<_>>().join(", ")).unwrap_or_else(|| "None".to_string());
-        let image_url = entry.logo.thumb_1280x720.as_deref().unwrap_or_else(|| entry.logo.original.as_str());
-
-        let embed = serenity::CreateEmbed::default()
-            .title(&entry.name)
-            .url(&entry.profile_url)
-            .description(&entry.summary)
-            .color(BOT_EMBED_COLOR)
-            .image(image_url)
-            .field("Author", author, true)
-            .field("Size", &size_mb, true)
-            .field("Tags", tags_str, false)
-            .field("Link", download_field_value, false)
-            .timestamp(serenity::Timestamp::now())
-            .footer(serenity::CreateEmbedFooter::new(format!("ID: {} | Source: mod.io | Requested by {}", entry.id, ctx.author().name)));
-        
-        CreateReply::default().embed(embed)
+        analytics::resolved_map(ctx, entry.id);
+        CreateReply::default()
+            .embed(build_map_embed(&entry, &ctx.author().name))
+            .components(components::map_action_rows(&entry))
     } else {
         warn!(query = %search, "Final: Map not found or ambiguous");
+        failed_searches::record(ctx, SearchTarget::Maps, &search).await;
         CreateReply::default()
             .content(format!("❌ Map not found matching: '{}'.\nTip: Use the autocomplete suggestions for best results, or make sure the name is exact.", search))
             .ephemeral(true)
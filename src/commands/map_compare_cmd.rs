@@ -0,0 +1,157 @@
+use crate::{
+    analytics,
+    commands::{map_cmd::find_map, map_random_cmd::rating_percent},
+    failed_searches,
+    mod_utils::{self, format_count},
+    types::{ApiModioMap, Context, Error, SearchTarget, BOT_EMBED_COLOR},
+};
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+use std::cmp::Ordering;
+use tracing::{error, info};
+
+/// Which of the two compared maps has the better value in a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// One line of the comparison. `better` is `None` for ties and for rows without a better value, like tags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompareRow {
+    pub label: &'static str,
+    pub left: String,
+    pub right: String,
+    pub better: Option<Side>,
+}
+
+/// `Left` if `left` is greater, `Right` if `right` is, `None` when equal or either is unknown.
+fn higher_wins<T: PartialOrd>(left: Option<T>, right: Option<T>) -> Option<Side> {
+    match left?.partial_cmp(&right?)? {
+        Ordering::Greater => Some(Side::Left),
+        Ordering::Less => Some(Side::Right),
+        Ordering::Equal => None,
+    }
+}
+
+fn flip(side: Option<Side>) -> Option<Side> {
+    side.map(|s| match s {
+        Side::Left => Side::Right,
+        Side::Right => Side::Left,
+    })
+}
+
+fn size_bytes(map: &ApiModioMap) -> Option<i64> {
+    map.modfile.as_ref().and_then(|file| file.filesize)
+}
+
+fn format_size(map: &ApiModioMap) -> String {
+    size_bytes(map).map(|bytes| format!("{:.2} MB", bytes as f64 / (1024.0 * 1024.0))).unwrap_or_else(|| "Unknown".to_string())
+}
+
+fn format_rating(map: &ApiModioMap) -> String {
+    rating_percent(map).map(|rating| format!("{:.0}% 👍", rating)).unwrap_or_else(|| "Unrated".to_string())
+}
+
+fn format_tags(map: &ApiModioMap) -> String {
+    let tags: Vec<&str> = map.tags.iter().flatten().map(|t| t.name.as_str()).collect();
+    if tags.is_empty() { "None".to_string() } else { mod_utils::truncate_with_ellipsis(&tags.join(", "), 60) }
+}
+
+/// The rows of `/maps compare`. Smaller files and more recent updates count as better.
+pub fn compare_rows(left: &ApiModioMap, right: &ApiModioMap) -> Vec<CompareRow> {
+    let row = |label, left: String, right: String, better| CompareRow { label, left, right, better };
+    vec![
+        row("Author", left.submitted_by.username.clone(), right.submitted_by.username.clone(), None),
+        row("Size", format_size(left), format_size(right), flip(higher_wins(size_bytes(left), size_bytes(right)))),
+        row("Tags", format_tags(left), format_tags(right), None),
+        row(
+            "Downloads",
+            format_count(i64::from(left.stats.downloads_total)),
+            format_count(i64::from(right.stats.downloads_total)),
+            higher_wins(Some(left.stats.downloads_total), Some(right.stats.downloads_total)),
+        ),
+        row(
+            "Subscribers",
+            format_count(i64::from(left.stats.subscribers_total)),
+            format_count(i64::from(right.stats.subscribers_total)),
+            higher_wins(Some(left.stats.subscribers_total), Some(right.stats.subscribers_total)),
+        ),
+        row("Rating", format_rating(left), format_rating(right), higher_wins(rating_percent(left), rating_percent(right))),
+        row("Released", format!("<t:{}:D>", left.date_live), format!("<t:{}:D>", right.date_live), None),
+        row(
+            "Updated",
+            format!("<t:{}:D>", left.date_updated),
+            format!("<t:{}:D>", right.date_updated),
+            higher_wins(Some(left.date_updated), Some(right.date_updated)),
+        ),
+    ]
+}
+
+fn highlight(value: &str, is_better: bool) -> String {
+    if is_better { format!("**{}** ✅", value) } else { value.to_string() }
+}
+
+/// Three inline columns: row labels, then each map's values with the better one of each row in bold.
+pub fn build_compare_embed(left: &ApiModioMap, right: &ApiModioMap) -> serenity::CreateEmbed {
+    let rows = compare_rows(left, right);
+    let labels: Vec<String> = rows.iter().map(|r| format!("**{}**", r.label)).collect();
+    let left_values: Vec<String> = rows.iter().map(|r| highlight(&r.left, r.better == Some(Side::Left))).collect();
+    let right_values: Vec<String> = rows.iter().map(|r| highlight(&r.right, r.better == Some(Side::Right))).collect();
+    let heading = |map: &ApiModioMap| mod_utils::truncate_with_ellipsis(&map.name, 60);
+
+    serenity::CreateEmbed::default()
+        .title("⚖️ Map Comparison")
+        .description(format!("[{}]({}) vs [{}]({})", heading(left), left.profile_url, heading(right), right.profile_url))
+        .color(BOT_EMBED_COLOR)
+        .field("\u{200b}", labels.join("\n"), true)
+        .field(heading(left), left_values.join("\n"), true)
+        .field(heading(right), right_values.join("\n"), true)
+        .footer(serenity::CreateEmbedFooter::new("✅ marks the better value · Smaller size and more recent updates win"))
+}
+
+/// Compare two maps side by side.
+#[poise::command(slash_command, prefix_command)]
+pub async fn compare(
+    ctx: Context<'_>,
+    #[description = "First map (use autocomplete)"]
+    #[autocomplete = "crate::commands::map_cmd::map_name_autocomplete"]
+    a: String,
+    #[description = "Second map (use autocomplete)"]
+    #[autocomplete = "crate::commands::map_cmd::map_name_autocomplete"]
+    b: String,
+) -> Result<(), Error> {
+    info!(user = %ctx.author().name, a = %a, b = %b, "Map compare command received");
+
+    let mut found = Vec::with_capacity(2);
+    for search in [&a, &b] {
+        match find_map(ctx.data().maps.as_ref(), search).await {
+            Ok(Some(map)) => {
+                analytics::resolved_map(ctx, map.id);
+                found.push(map);
+            }
+            Ok(None) => {
+                failed_searches::record(ctx, SearchTarget::Maps, search).await;
+                let reply = CreateReply::default()
+                    .content(format!("❌ Map not found matching: '{}'. Use the autocomplete suggestions.", search))
+                    .ephemeral(true);
+                ctx.send(reply).await?;
+                return Ok(());
+            }
+            Err(e) => {
+                error!("Map Compare: Failed to query the map database: {}", e);
+                ctx.say("Sorry, I couldn't connect to the map database right now. Please try again later.").await?;
+                return Ok(());
+            }
+        }
+    }
+
+    if found[0].id == found[1].id {
+        let reply = CreateReply::default().content("Pick two different maps to compare.").ephemeral(true);
+        ctx.send(reply).await?;
+        return Ok(());
+    }
+    ctx.send(CreateReply::default().embed(build_compare_embed(&found[0], &found[1]))).await?;
+    Ok(())
+}
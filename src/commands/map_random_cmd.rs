@@ -0,0 +1,337 @@
+use crate::{
+    commands::map_cmd::build_map_embed,
+    components,
+    mod_utils,
+    repository::MapRepository,
+    types::{ApiModioMap, Context, Error, BOT_EMBED_COLOR, MAP_TAG},
+};
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{collections::HashMap, time::Duration};
+use tracing::{error, info, warn};
+
+/// How many map entries are fetched from the store per round trip while looking for matches.
+const FETCH_BATCH_SIZE: usize = 50;
+const SECONDS_PER_DAY: i64 = 86_400;
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+/// Optional constraints for random map draws. Every set constraint must hold.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MapFilter {
+    pub tag: Option<String>,
+    /// Minimum share of positive ratings, in percent. Unrated maps never match.
+    pub min_rating: Option<f64>,
+    /// Maps with an unknown file size never match.
+    pub max_size_mb: Option<f64>,
+    /// Only maps added to mod.io within this many days.
+    pub max_age_days: Option<i64>,
+}
+
+/// Share of positive ratings in percent, or `None` when nobody has rated the map.
+pub fn rating_percent(map: &ApiModioMap) -> Option<f64> {
+    let positive = map.stats.ratings_positive.max(0) as f64;
+    let total = positive + map.stats.ratings_negative.max(0) as f64;
+    (total > 0.0).then(|| positive / total * 100.0)
+}
+
+impl MapFilter {
+    pub fn matches(&self, map: &ApiModioMap, now: i64) -> bool {
+        if !map.has_tag(MAP_TAG) {
+            return false;
+        }
+        if let Some(tag) = &self.tag {
+            if !map.tags.iter().flatten().any(|t| t.name.eq_ignore_ascii_case(tag.trim())) {
+                return false;
+            }
+        }
+        if let Some(min_rating) = self.min_rating {
+            if !rating_percent(map).is_some_and(|rating| rating >= min_rating) {
+                return false;
+            }
+        }
+        if let Some(max_size_mb) = self.max_size_mb {
+            let size = map.modfile.as_ref().and_then(|file| file.filesize);
+            if !size.is_some_and(|bytes| bytes as f64 <= max_size_mb * BYTES_PER_MB) {
+                return false;
+            }
+        }
+        if let Some(max_age_days) = self.max_age_days {
+            if now - map.date_added > max_age_days * SECONDS_PER_DAY {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Human-readable summary such as `"tag Street · 80%+ rating"`, empty when nothing is filtered.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(tag) = &self.tag {
+            parts.push(format!("tag {}", tag.trim()));
+        }
+        if let Some(min_rating) = self.min_rating {
+            parts.push(format!("{:.0}%+ rating", min_rating));
+        }
+        if let Some(max_size_mb) = self.max_size_mb {
+            parts.push(format!("up to {:.0} MB", max_size_mb));
+        }
+        if let Some(max_age_days) = self.max_age_days {
+            parts.push(format!("added in the last {} days", max_age_days));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Draws up to `count` distinct maps matching `filter`, uniformly at random.
+/// Fetches entries in shuffled batches, so a narrow filter only costs as many round trips as it takes.
+pub async fn pick_random_maps(
+    maps: &dyn MapRepository,
+    filter: &MapFilter,
+    count: usize,
+    now: i64,
+    rng: &mut (impl Rng + Send),
+) -> Result<Vec<ApiModioMap>, Error> {
+    let mut ids = maps.all_ids(MAP_TAG).await?;
+    ids.sort_unstable();
+    ids.dedup();
+    ids.shuffle(rng);
+
+    let mut picked = Vec::with_capacity(count);
+    for batch in ids.chunks(FETCH_BATCH_SIZE) {
+        for map in maps.get_many(batch).await?.into_iter().flatten() {
+            if filter.matches(&map, now) {
+                picked.push(map);
+                if picked.len() >= count {
+                    return Ok(picked);
+                }
+            }
+        }
+    }
+    Ok(picked)
+}
+
+/// One vote per member; voting again moves the vote.
+#[derive(Debug, Default)]
+pub struct RouletteVotes {
+    votes: HashMap<u64, usize>,
+}
+
+impl RouletteVotes {
+    pub fn cast(&mut self, user_id: u64, choice: usize) {
+        self.votes.insert(user_id, choice);
+    }
+
+    /// Vote count per choice, for `choices` options.
+    pub fn tally(&self, choices: usize) -> Vec<usize> {
+        let mut counts = vec![0; choices];
+        for &choice in self.votes.values() {
+            if let Some(count) = counts.get_mut(choice) {
+                *count += 1;
+            }
+        }
+        counts
+    }
+
+    /// The most voted choice. Ties go to the map drawn first; no votes means no winner.
+    pub fn winner(&self, choices: usize) -> Option<usize> {
+        let counts = self.tally(choices);
+        let best = *counts.iter().max()?;
+        (best > 0).then(|| counts.iter().position(|&count| count == best)).flatten()
+    }
+}
+
+fn roulette_line(index: usize, map: &ApiModioMap, votes: usize) -> String {
+    let rating = rating_percent(map).map(|r| format!("{:.0}% 👍", r)).unwrap_or_else(|| "unrated".to_string());
+    let plural = if votes == 1 { "" } else { "s" };
+    format!(
+        "**{}.** [{}]({}) by {} · {} — **{} vote{}**",
+        index + 1, map.name, map.profile_url, map.submitted_by.username, rating, votes, plural
+    )
+}
+
+/// The roulette message: every drawn map with its current votes, or the final result once `closed`.
+pub fn build_roulette_embed(maps: &[ApiModioMap], votes: &RouletteVotes, closes_at: i64, closed: bool) -> serenity::CreateEmbed {
+    let counts = votes.tally(maps.len());
+    let winner = closed.then(|| votes.winner(maps.len())).flatten();
+    let mut lines: Vec<String> = maps
+        .iter()
+        .enumerate()
+        .map(|(i, map)| {
+            let line = roulette_line(i, map, counts[i]);
+            if winner == Some(i) { format!("🏆 {}", line) } else { line }
+        })
+        .collect();
+    lines.push(String::new());
+    lines.push(match (closed, winner) {
+        (false, _) => format!("Vote with the buttons below. Voting closes <t:{}:R>.", closes_at),
+        (true, Some(i)) => format!("**{}** wins!", maps[i].name),
+        (true, None) => "Voting closed without any votes.".to_string(),
+    });
+
+    serenity::CreateEmbed::default()
+        .title(if closed { "🎰 Map Roulette — Results" } else { "🎰 Map Roulette" })
+        .description(lines.join("\n"))
+        .color(BOT_EMBED_COLOR)
+}
+
+fn roulette_buttons(id_prefix: &str, maps: &[ApiModioMap]) -> Vec<serenity::CreateActionRow> {
+    let buttons = maps
+        .iter()
+        .enumerate()
+        .map(|(i, map)| {
+            let label = mod_utils::truncate_with_ellipsis(&format!("{}. {}", i + 1, map.name), 80);
+            serenity::CreateButton::new(format!("{}:{}", id_prefix, i)).label(label).style(serenity::ButtonStyle::Primary)
+        })
+        .collect();
+    vec![serenity::CreateActionRow::Buttons(buttons)]
+}
+
+fn filter_from_args(tag: Option<String>, min_rating: Option<u8>, max_size_mb: Option<u32>, max_age_days: Option<u32>) -> MapFilter {
+    MapFilter {
+        tag: tag.filter(|t| !t.trim().is_empty()),
+        min_rating: min_rating.map(f64::from),
+        max_size_mb: max_size_mb.map(f64::from),
+        max_age_days: max_age_days.map(i64::from),
+    }
+}
+
+async fn draw(ctx: Context<'_>, filter: &MapFilter, count: usize) -> Result<Option<Vec<ApiModioMap>>, Error> {
+    let now = serenity::Timestamp::now().unix_timestamp();
+    let mut rng = StdRng::from_entropy();
+    match pick_random_maps(ctx.data().maps.as_ref(), filter, count, now, &mut rng).await {
+        Ok(picked) if picked.is_empty() => {
+            let reply = CreateReply::default()
+                .content(format!("❌ No maps match those filters ({}).", filter.describe()))
+                .ephemeral(true);
+            ctx.send(reply).await?;
+            Ok(None)
+        }
+        Ok(picked) => Ok(Some(picked)),
+        Err(e) => {
+            error!("Random Map: Failed to query the map database: {}", e);
+            ctx.say("Sorry, I couldn't connect to the map database right now. Please try again later.").await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Pick a random map, optionally filtered.
+#[poise::command(slash_command, prefix_command)]
+pub async fn random(
+    ctx: Context<'_>,
+    #[description = "Only maps with this tag"] tag: Option<String>,
+    #[description = "Minimum positive rating in percent"]
+    #[min = 0]
+    #[max = 100]
+    min_rating: Option<u8>,
+    #[description = "Maximum download size in MB"]
+    #[min = 1]
+    max_size_mb: Option<u32>,
+    #[description = "Only maps added within this many days"]
+    #[min = 1]
+    max_age_days: Option<u32>,
+) -> Result<(), Error> {
+    let filter = filter_from_args(tag, min_rating, max_size_mb, max_age_days);
+    info!(user = %ctx.author().name, filter = %filter.describe(), "Random map requested");
+
+    let Some(picked) = draw(ctx, &filter, 1).await? else { return Ok(()) };
+    let entry = &picked[0];
+    let content = match filter.describe() {
+        description if description.is_empty() => "🎲 Random map".to_string(),
+        description => format!("🎲 Random map ({})", description),
+    };
+    let reply = CreateReply::default()
+        .content(content)
+        .embed(build_map_embed(entry, &ctx.author().name))
+        .components(components::map_action_rows(entry));
+    ctx.send(reply).await?;
+    Ok(())
+}
+
+/// Draw a few random maps and let the channel vote on what to skate.
+#[poise::command(slash_command, prefix_command, guild_only)]
+pub async fn roulette(
+    ctx: Context<'_>,
+    #[description = "How many maps to draw (default 3)"]
+    #[min = 2]
+    #[max = 5]
+    maps: Option<u8>,
+    #[description = "Voting time in minutes (default 2)"]
+    #[min = 1]
+    #[max = 15]
+    minutes: Option<u8>,
+    #[description = "Only maps with this tag"] tag: Option<String>,
+    #[description = "Minimum positive rating in percent"]
+    #[min = 0]
+    #[max = 100]
+    min_rating: Option<u8>,
+    #[description = "Maximum download size in MB"]
+    #[min = 1]
+    max_size_mb: Option<u32>,
+    #[description = "Only maps added within this many days"]
+    #[min = 1]
+    max_age_days: Option<u32>,
+) -> Result<(), Error> {
+    let filter = filter_from_args(tag, min_rating, max_size_mb, max_age_days);
+    let count = maps.unwrap_or(3) as usize;
+    let voting_time = Duration::from_secs(u64::from(minutes.unwrap_or(2)) * 60);
+    info!(user = %ctx.author().name, count, filter = %filter.describe(), "Map roulette started");
+
+    let Some(drawn) = draw(ctx, &filter, count).await? else { return Ok(()) };
+    let closes_at = serenity::Timestamp::now().unix_timestamp() + voting_time.as_secs() as i64;
+    let id_prefix = format!("roulette:{}", ctx.id());
+    let mut votes = RouletteVotes::default();
+
+    let handle = ctx
+        .send(
+            CreateReply::default()
+                .embed(build_roulette_embed(&drawn, &votes, closes_at, false))
+                .components(roulette_buttons(&id_prefix, &drawn)),
+        )
+        .await?;
+
+    let deadline = tokio::time::Instant::now() + voting_time;
+    loop {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        let Some(press) = serenity::ComponentInteractionCollector::new(ctx)
+            .filter({
+                let filter_prefix = format!("{}:", id_prefix);
+                move |press| press.data.custom_id.starts_with(&filter_prefix)
+            })
+            .timeout(remaining)
+            .await
+        else {
+            break;
+        };
+        let choice = press.data.custom_id.rsplit(':').next().and_then(|i| i.parse::<usize>().ok());
+        if let Some(choice) = choice.filter(|&c| c < drawn.len()) {
+            votes.cast(press.user.id.get(), choice);
+        }
+        let update = serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new().embed(build_roulette_embed(&drawn, &votes, closes_at, false)),
+        );
+        if let Err(e) = press.create_response(ctx.serenity_context(), update).await {
+            // The vote is already counted; one failed acknowledgement must not end the roulette for everyone
+            warn!(user_id = press.user.id.get(), "Could not update the map roulette message: {}", e);
+        }
+    }
+
+    let results = CreateReply::default()
+        .embed(build_roulette_embed(&drawn, &votes, closes_at, true))
+        .components(Vec::new());
+    handle.edit(ctx, results).await?;
+
+    if let Some(winner) = votes.winner(drawn.len()).map(|i| &drawn[i]) {
+        info!(map_id = winner.id, map_name = %winner.name, "Map roulette winner");
+        let reply = CreateReply::default()
+            .content("🏆 The roulette has spoken:")
+            .embed(build_map_embed(winner, &ctx.author().name))
+            .components(components::map_action_rows(winner));
+        ctx.send(reply).await?;
+    }
+    Ok(())
+}
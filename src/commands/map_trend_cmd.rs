@@ -0,0 +1,90 @@
+use crate::{
+    analytics,
+    commands::map_cmd::find_map,
+    failed_searches,
+    mod_utils::format_count,
+    trend::{self, TrendRange, TrendSummary},
+    types::{ApiModioMap, Context, Error, SearchTarget, BOT_EMBED_COLOR},
+};
+use poise::serenity_prelude as serenity;
+use poise::{ChoiceParameter, CreateReply};
+use tracing::{error, info};
+
+const CHART_FILENAME: &str = "trend.png";
+
+fn signed_count(count: i64) -> String {
+    if count < 0 { format!("-{}", format_count(-count)) } else { format!("+{}", format_count(count)) }
+}
+
+fn rating_change(summary: &TrendSummary) -> String {
+    match (summary.rating_from, summary.rating_to) {
+        (Some(from), Some(to)) => format!("{:.0}% → {:.0}%", from, to),
+        (None, Some(to)) => format!("Unrated → {:.0}%", to),
+        _ => "Unrated".to_string(),
+    }
+}
+
+pub fn build_trend_embed(map: &ApiModioMap, range: TrendRange, summary: &TrendSummary, requested_by: &str) -> serenity::CreateEmbed {
+    serenity::CreateEmbed::default()
+        .title(format!("📈 {} · {}", map.name, range.name()))
+        .url(&map.profile_url)
+        .description(format!("From <t:{}:D> to <t:{}:D>", summary.from, summary.to))
+        .color(BOT_EMBED_COLOR)
+        .field("Downloads", signed_count(summary.downloads_gained), true)
+        .field("Subscribers", signed_count(summary.subscribers_gained), true)
+        .field("Rating", rating_change(summary), true)
+        .image(format!("attachment://{}", CHART_FILENAME))
+        .footer(serenity::CreateEmbedFooter::new(format!("Requested by {}", requested_by)))
+}
+
+/// Chart a map's downloads and subscribers over time.
+#[poise::command(slash_command, prefix_command)]
+pub async fn trend(
+    ctx: Context<'_>,
+    #[description = "Map name (use autocomplete)"]
+    #[autocomplete = "crate::commands::map_cmd::map_name_autocomplete"]
+    map: String,
+    #[description = "How far back to look (default: last 30 days)"] range: Option<TrendRange>,
+) -> Result<(), Error> {
+    let range = range.unwrap_or_default();
+    info!(user = %ctx.author().name, map = %map, range = ?range, "Map trend command received");
+
+    let entry = match find_map(ctx.data().maps.as_ref(), &map).await {
+        Ok(Some(entry)) => {
+            analytics::resolved_map(ctx, entry.id);
+            entry
+        }
+        Ok(None) => {
+            failed_searches::record(ctx, SearchTarget::Maps, &map).await;
+            let reply = CreateReply::default()
+                .content(format!("❌ Map not found matching: '{}'. Use the autocomplete suggestions.", map))
+                .ephemeral(true);
+            ctx.send(reply).await?;
+            return Ok(());
+        }
+        Err(e) => {
+            error!("Map Trend: Failed to query the map database: {}", e);
+            ctx.say("Sorry, I couldn't connect to the map database right now. Please try again later.").await?;
+            return Ok(());
+        }
+    };
+
+    let since = serenity::Timestamp::now().unix_timestamp() - range.secs();
+    let history = ctx.data().stats.history(entry.id, since).await?;
+    let Some(summary) = trend::summarize(&history) else {
+        let reply = CreateReply::default()
+            .content(format!("Not enough history for **{}** yet. Stats are snapshotted on the `stats_snapshot` schedule; try again later.", entry.name))
+            .ephemeral(true);
+        ctx.send(reply).await?;
+        return Ok(());
+    };
+
+    ctx.defer().await?;
+    let title = entry.name.clone();
+    let chart = tokio::task::spawn_blocking(move || trend::render_trend_png(&title, &history)).await??;
+    let reply = CreateReply::default()
+        .embed(build_trend_embed(&entry, range, &summary, &ctx.author().name))
+        .attachment(serenity::CreateAttachment::bytes(chart, CHART_FILENAME));
+    ctx.send(reply).await?;
+    Ok(())
+}
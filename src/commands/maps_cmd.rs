@@ -0,0 +1,135 @@
+use crate::{
+    pagination::{self, PAGE_SIZE},
+    repository,
+    stats::{self, TrendingWindow},
+    tags::{self, TagMatch},
+    types::{Context, Error},
+};
+use poise::serenity_prelude as serenity;
+use poise::{ChoiceParameter, CreateReply};
+use tracing::{info, warn};
+
+const AUTOCOMPLETE_LIMIT: usize = 25;
+
+/// How many maps `/maps trending` ranks.
+const TRENDING_LIMIT: usize = 50;
+
+async fn tag_autocomplete(ctx: Context<'_>, partial: &str) -> Vec<serenity::AutocompleteChoice> {
+    let index = ctx.data().tags.read().await;
+    index
+        .suggestions(partial, AUTOCOMPLETE_LIMIT)
+        .into_iter()
+        .map(|tag| {
+            let plural = if tag.map_ids.len() == 1 { "" } else { "s" };
+            serenity::AutocompleteChoice::new(format!("{} ({} map{})", tag.name, tag.map_ids.len(), plural), tag.name.clone())
+        })
+        .collect()
+}
+
+/// Browse maps by tag or popularity, draw random ones, compare two or chart a map's downloads.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands(
+        "tag",
+        "trending",
+        "super::map_random_cmd::random",
+        "super::map_random_cmd::roulette",
+        "super::map_compare_cmd::compare",
+        "super::map_trend_cmd::trend"
+    ),
+    subcommand_required
+)]
+pub async fn maps(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// List maps with one or more tags.
+#[poise::command(slash_command, prefix_command)]
+pub async fn tag(
+    ctx: Context<'_>,
+    #[description = "Tag (use autocomplete)"]
+    #[autocomplete = "tag_autocomplete"]
+    tag: String,
+    #[description = "Second tag"]
+    #[autocomplete = "tag_autocomplete"]
+    tag2: Option<String>,
+    #[description = "Third tag"]
+    #[autocomplete = "tag_autocomplete"]
+    tag3: Option<String>,
+    #[description = "Combine the tags with AND (default) or OR"]
+    #[rename = "match"]
+    mode: Option<TagMatch>,
+) -> Result<(), Error> {
+    let mode = mode.unwrap_or_default();
+    info!(user = %ctx.author().name, tag = %tag, tag2 = ?tag2, tag3 = ?tag3, mode = ?mode, "Maps tag command received");
+
+    let queries: Vec<String> = [Some(tag), tag2, tag3].into_iter().flatten().collect();
+    let resolved = {
+        let index = ctx.data().tags.read().await;
+        index.resolve(&queries).map(|names| {
+            let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+            let ids = index.matching_ids(&name_refs, mode);
+            (names, ids)
+        })
+    };
+    let (names, ids) = match resolved {
+        Ok(found) => found,
+        Err(unknown) => {
+            warn!(query = %unknown, "Tag not found in index");
+            let reply = CreateReply::default()
+                .content(format!("❌ No maps are tagged '{}'. Use the autocomplete suggestions.", unknown.trim()))
+                .ephemeral(true);
+            ctx.send(reply).await?;
+            return Ok(());
+        }
+    };
+    let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+    let description = tags::describe_tags(&name_refs, mode);
+
+    let mut maps = repository::load_maps(ctx.data().maps.as_ref(), &ids).await?;
+    if maps.is_empty() {
+        let reply = CreateReply::default().content(format!("No maps are tagged {}.", description)).ephemeral(true);
+        ctx.send(reply).await?;
+        return Ok(());
+    }
+    maps.sort_by_key(|m| m.name.to_lowercase());
+
+    let lines: Vec<String> = maps.iter().map(tags::tagged_map_line).collect();
+    let title = format!("🏷️ Maps tagged {}", description);
+    let footer = format!("{} maps", lines.len());
+    let total_pages = pagination::page_count(lines.len(), PAGE_SIZE);
+    pagination::paginate(ctx, total_pages, |page| pagination::build_list_page_embed(&lines, page, &title, &footer)).await
+}
+
+/// Maps ranked by downloads gained recently.
+#[poise::command(slash_command, prefix_command)]
+pub async fn trending(
+    ctx: Context<'_>,
+    #[description = "Time window (default: last 7 days)"] window: Option<TrendingWindow>,
+) -> Result<(), Error> {
+    let window = window.unwrap_or_default();
+    info!(user = %ctx.author().name, window = ?window, "Maps trending command received");
+
+    let since = serenity::Timestamp::now().unix_timestamp() - window.secs();
+    let ids: Vec<i32> = ctx.data().map_list.read().await.iter().map(|m| m.id).collect();
+    let baselines = stats::window_baselines(ctx.data(), &ids, since).await?;
+    let mut gains = stats::download_gains(&*ctx.data().map_list.read().await, &baselines);
+    if gains.is_empty() {
+        let reply = CreateReply::default()
+            .content(format!(
+                "No download gains recorded for the {} yet. Stats are snapshotted on the `stats_snapshot` schedule.",
+                window.name().to_lowercase()
+            ))
+            .ephemeral(true);
+        ctx.send(reply).await?;
+        return Ok(());
+    }
+    gains.truncate(TRENDING_LIMIT);
+
+    let lines: Vec<String> = gains.iter().enumerate().map(|(i, gain)| stats::trending_line(i + 1, gain)).collect();
+    let title = format!("🔥 Trending Maps · {}", window.name());
+    let footer = format!("Ranked by downloads gained · Top {}", lines.len());
+    let total_pages = pagination::page_count(lines.len(), PAGE_SIZE);
+    pagination::paginate(ctx, total_pages, |page| pagination::build_list_page_embed(&lines, page, &title, &footer)).await
+}
@@ -0,0 +1,85 @@
+use crate::types::{Context, Error};
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+use tracing::info;
+
+async fn reply_ephemeral(ctx: Context<'_>, content: impl Into<String>) -> Result<(), Error> {
+    ctx.send(CreateReply::default().content(content).ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Reply to map links and [[map or mod name]] in regular messages, per channel.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    subcommands("enable", "disable", "status"),
+    subcommand_required,
+    required_permissions = "MANAGE_CHANNELS",
+    default_member_permissions = "MANAGE_CHANNELS"
+)]
+pub async fn mentions(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Starts answering map links and [[names]] in a channel.
+#[poise::command(slash_command, prefix_command, guild_only, required_permissions = "MANAGE_CHANNELS")]
+pub async fn enable(
+    ctx: Context<'_>,
+    #[description = "Channel to listen in (defaults to this one)"]
+    #[channel_types("Text", "News", "PublicThread", "PrivateThread")]
+    channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return Ok(()) };
+    let channel_id = channel.map_or(ctx.channel_id(), |c| c.id);
+    let newly_enabled = ctx.data().mention_channels.enable(guild_id.get(), channel_id.get()).await?;
+    ctx.data().mention_channel_ids.write().await.insert(channel_id.get());
+    info!(user = %ctx.author().name, guild_id = %guild_id, channel_id = %channel_id, "Mentions enabled");
+
+    if newly_enabled {
+        reply_ephemeral(
+            ctx,
+            format!("🔗 I'll now reply in <#{}> to mod.io map links and `[[map or mod name]]` with a short summary.", channel_id),
+        )
+        .await
+    } else {
+        reply_ephemeral(ctx, format!("Mentions are already on in <#{}>.", channel_id)).await
+    }
+}
+
+/// Stops answering map links and [[names]] in a channel.
+#[poise::command(slash_command, prefix_command, guild_only, required_permissions = "MANAGE_CHANNELS")]
+pub async fn disable(
+    ctx: Context<'_>,
+    #[description = "Channel to stop listening in (defaults to this one)"]
+    #[channel_types("Text", "News", "PublicThread", "PrivateThread")]
+    channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return Ok(()) };
+    let channel_id = channel.map_or(ctx.channel_id(), |c| c.id);
+    ctx.data().mention_channel_ids.write().await.remove(&channel_id.get());
+    if !ctx.data().mention_channels.disable(channel_id.get()).await? {
+        return reply_ephemeral(ctx, format!("Mentions aren't on in <#{}>.", channel_id)).await;
+    }
+    info!(user = %ctx.author().name, guild_id = %guild_id, channel_id = %channel_id, "Mentions disabled");
+    reply_ephemeral(ctx, format!("Mentions turned off in <#{}>.", channel_id)).await
+}
+
+/// Lists the channels in this server where mentions are answered.
+#[poise::command(slash_command, prefix_command, guild_only, required_permissions = "MANAGE_CHANNELS")]
+pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return Ok(()) };
+    let channels: Vec<String> = ctx
+        .data()
+        .mention_channels
+        .all()
+        .await?
+        .into_iter()
+        .filter(|(guild, _)| *guild == guild_id.get())
+        .map(|(_, channel_id)| format!("<#{}>", channel_id))
+        .collect();
+    if channels.is_empty() {
+        return reply_ephemeral(ctx, "Mentions aren't on in any channel here. Turn them on with `/mentions enable`.").await;
+    }
+    reply_ephemeral(ctx, format!("Answering map links and `[[names]]` in {}.", channels.join(", "))).await
+}
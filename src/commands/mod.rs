@@ -1,5 +1,18 @@
 pub mod ping_cmd;
 pub mod age_cmd;
 pub mod map_cmd;
+pub mod map_random_cmd;
+pub mod map_compare_cmd;
+pub mod map_trend_cmd;
+pub mod maps_cmd;
+pub mod script_cmd;
+pub mod search_cmd;
 pub mod modlist_cmd;
-pub mod mod_cmd;
\ No newline at end of file
+pub mod mod_cmd;
+pub mod admin_cmd;
+pub mod favorites_cmd;
+pub mod watch_cmd;
+pub mod motw_cmd;
+pub mod author_cmd;
+pub mod digest_cmd;
+pub mod mentions_cmd;
\ No newline at end of file
@@ -1,40 +1,32 @@
 use crate::{
-    types::{Context, Error, ModVersionBranch, ModEntry, BOT_EMBED_COLOR},
+    analytics,
+    components,
+    failed_searches,
+    types::{Context, Error, FavoriteItem, ModVersionBranch, ModEntry, SearchTarget, BOT_EMBED_COLOR},
     mod_utils,
 };
 use poise::{
     serenity_prelude::{self as serenity, CreateEmbedFooter},
     CreateReply,
 };
-use std::str::FromStr;
+use std::{collections::{HashMap, HashSet}, str::FromStr};
 use tracing::{info, warn};
 
-async fn mod_title_autocomplete(
-    ctx: Context<'_>,
-    partial: &str,
-) -> Vec<String> {
-    let mod_cache_guard = ctx.data().mod_cache.read().await;
+/// Builds `"Title - Branch"` autocomplete choices from the slug-keyed mod cache.
+pub fn mod_title_suggestions(mod_cache: &HashMap<String, Vec<ModEntry>>, partial: &str) -> Vec<String> {
     let mut suggestions = Vec::new();
     let partial_lowercase = partial.to_lowercase();
 
-    for (slug, mods) in mod_cache_guard.iter() {
-        let version_enum = match slug.as_str() {
-            "1228" => ModVersionBranch::Alpha,
-            "12104" => ModVersionBranch::BetaPublic,
-            _ => continue,
-        };
+    for (slug, mods) in mod_cache.iter() {
+        let Some(version_enum) = mod_utils::branch_for_slug(slug) else { continue };
         let branch_name = version_enum.to_string();
 
         for entry in mods {
             if entry.title.to_lowercase().contains(&partial_lowercase) {
-                let display_title = if entry.title.len() > 75 {
-                    format!("{}...", &entry.title[..72])
-                } else {
-                    entry.title.clone()
-                };
+                let display_title = mod_utils::truncate_with_ellipsis(&entry.title, 75);
                 let suggestion = format!("{} - {}", display_title, branch_name);
                 // Ensure suggestion is not longer than Discord's limit for choice names (100 chars)
-                suggestions.push(suggestion[..suggestion.len().min(100)].to_string());
+                suggestions.push(mod_utils::truncate_with_ellipsis(&suggestion, 100));
                 if suggestions.len() >= 25 { break; }
             }
         }
@@ -43,9 +35,60 @@ async fn mod_title_autocomplete(
     suggestions
 }
 
+async fn mod_title_autocomplete(
+    ctx: Context<'_>,
+    partial: &str,
+) -> Vec<String> {
+    let mod_cache_guard = ctx.data().mod_cache.read().await;
+    mod_title_suggestions(&mod_cache_guard, partial)
+}
+
+/// Splits a `"Mod Title - Branch"` search into its title and branch.
+/// The error is a user-facing explanation of what was wrong with the input.
+pub fn parse_mod_search(search: &str) -> Result<(&str, ModVersionBranch), String> {
+    let Some(separator_index) = search.rfind(" - ") else {
+        return Err("Please use autocomplete or format search as `Mod Title - Branch` (e.g., `SomeMod - Beta/Public`).".to_string());
+    };
+    let (title_part, branch_part) = search.split_at(separator_index);
+    let branch_name = branch_part[3..].trim(); // Skip " - "
+
+    ModVersionBranch::from_str(branch_name)
+        .map(|branch| (title_part.trim(), branch))
+        .map_err(|_| format!("Invalid branch name '{}' found. Use autocomplete or 'Alpha', 'Beta', 'Public', 'Beta/Public'.", branch_name))
+}
+
+/// Returns every mod whose title matches `title`, ignoring ASCII case.
+pub fn find_mod_matches<'a>(mods: &'a [ModEntry], title: &str) -> Vec<&'a ModEntry> {
+    mods.iter().filter(|m| m.title.eq_ignore_ascii_case(title)).collect()
+}
+
+pub fn build_mod_embed(entry: &ModEntry, version: ModVersionBranch, requested_by: &str, dead_links: &HashSet<String>) -> serenity::CreateEmbed {
+    serenity::CreateEmbed::default()
+        .title(&entry.title)
+        .description(mod_utils::format_mod_entry(entry, dead_links))
+        .color(BOT_EMBED_COLOR)
+        .footer(CreateEmbedFooter::new(format!("Version: {} | Requested by {}", version, requested_by)))
+        .timestamp(serenity::Timestamp::now())
+}
+
+pub fn build_multiple_matches_embed(matches: &[&ModEntry], title: &str, version: ModVersionBranch, requested_by: &str) -> serenity::CreateEmbed {
+    let list_limit = 5;
+    let description = format!(
+        "Found {} mods matching '{}' exactly (unusual). Please check.\n\n**Matches:**\n{}",
+        matches.len(), title,
+        matches.iter().take(list_limit).map(|m| format!("- {}", m.title)).collect::<Vec<_>>().join("\n")
+    );
+    serenity::CreateEmbed::default()
+        .title("Multiple Exact Matches Found?")
+        .description(description)
+        .color(BOT_EMBED_COLOR)
+        .footer(CreateEmbedFooter::new(format!("Version: {} | Requested by {}", version, requested_by)))
+        .timestamp(serenity::Timestamp::now())
+}
+
 /// Search for a Skater XL mod by name.
 #[poise::command(slash_command, prefix_command, rename = "mod")]
-pub async fn modsearch( 
+pub async fn modsearch(
     ctx: Context<'_>,
     #[description = "Mod Title (use autocomplete for version)"]
     #[autocomplete = "mod_title_autocomplete"]
@@ -53,29 +96,13 @@ pub async fn modsearch(
 ) -> Result<(), Error> {
     info!(user = %ctx.author().name, search_term = %search, "Mod command received");
 
-    let (target_title, target_branch_name) =
-        if let Some(separator_index) = search.rfind(" - ") {
-            let (title_part, branch_part) = search.split_at(separator_index);
-            (title_part.trim(), branch_part[3..].trim()) // Skip " - "
-        } else {
-             warn!(user = %ctx.author().name, search_term = %search, "Search term missing ' - Branch' suffix");
-             let reply = CreateReply::default()
-                .content("Please use autocomplete or format search as `Mod Title - Branch` (e.g., `SomeMod - Beta/Public`).")
-                .ephemeral(true);
-             ctx.send(reply).await?;
-             return Ok(());
-        };
-
-    let version_enum = match ModVersionBranch::from_str(target_branch_name) {
-         Ok(v) => v,
-         Err(_) => {
-             warn!(user = %ctx.author().name, search_term = %search, parsed_branch = %target_branch_name, "Invalid branch name parsed from search query");
-             let reply = CreateReply::default()
-                .content(format!("Invalid branch name '{}' found. Use autocomplete or 'Alpha', 'Beta', 'Public', 'Beta/Public'.", target_branch_name))
-                .ephemeral(true);
-             ctx.send(reply).await?;
-             return Ok(());
-         }
+    let (target_title, version_enum) = match parse_mod_search(&search) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            warn!(user = %ctx.author().name, search_term = %search, "Could not parse mod search term");
+            ctx.send(CreateReply::default().content(message).ephemeral(true)).await?;
+            return Ok(());
+        }
     };
 
     let version_slug = mod_utils::resolve_version_slug(version_enum);
@@ -83,13 +110,13 @@ pub async fn modsearch(
 
     let reply = if let Some(mods) = mod_cache_guard.get(version_slug) {
         info!(fetched_count = mods.len(), %target_title, version = %version_enum, "Using cached mods, proceeding to filter.");
-        let matches: Vec<&ModEntry> = mods.iter().filter(|m| m.title.eq_ignore_ascii_case(target_title)).collect();
+        let matches = find_mod_matches(mods, target_title);
         info!(matched_count = matches.len(), %target_title, version = %version_enum, "Filtering complete.");
-        if matches.is_empty() && !mods.is_empty() { warn!(query=%target_title, version=%version_enum, "Filter yielded no results"); }
 
         match matches.len() {
             0 => {
                 warn!(query=%target_title, version = %version_enum, "Mod not found in cache");
+                failed_searches::record(ctx, SearchTarget::Mods, target_title).await;
                 CreateReply::default()
                     .content(format!("❌ No mod found matching '{}' for version {}.", target_title, version_enum))
                     .ephemeral(true)
@@ -97,30 +124,16 @@ pub async fn modsearch(
             1 => {
                 let entry = matches[0];
                 info!(mod_title = %entry.title, version = %version_enum, "Found single mod match");
-                let description = mod_utils::format_mod_entry(entry);
-                let embed = serenity::CreateEmbed::default()
-                    .title(&entry.title)
-                    .description(description)
-                    .color(BOT_EMBED_COLOR)
-                    .footer(CreateEmbedFooter::new(format!("Version: {} | Requested by {}", version_enum, ctx.author().name)))
-                    .timestamp(serenity::Timestamp::now());
-                CreateReply::default().embed(embed)
+                analytics::resolved_mod(ctx, &entry.title);
+                let item = FavoriteItem::Mod { title: entry.title.clone(), branch: version_enum };
+                let dead_links = ctx.data().dead_links.read().await;
+                CreateReply::default()
+                    .embed(build_mod_embed(entry, version_enum, &ctx.author().name, &dead_links))
+                    .components(components::item_action_rows(&item))
              }
             _ => {
                 info!(count = matches.len(), query=%target_title, version = %version_enum, "Multiple exact matches found?");
-                let list_limit = 5;
-                let description = format!(
-                    "Found {} mods matching '{}' exactly (unusual). Please check.\n\n**Matches:**\n{}",
-                    matches.len(), target_title,
-                    matches.iter().take(list_limit).map(|m| format!("- {}", m.title)).collect::<Vec<_>>().join("\n")
-                );
-                let embed = serenity::CreateEmbed::default()
-                    .title("Multiple Exact Matches Found?")
-                    .description(description)
-                    .color(BOT_EMBED_COLOR)
-                    .footer(CreateEmbedFooter::new(format!("Version: {} | Requested by {}", version_enum, ctx.author().name)))
-                    .timestamp(serenity::Timestamp::now());
-                CreateReply::default().embed(embed)
+                CreateReply::default().embed(build_multiple_matches_embed(&matches, target_title, version_enum, &ctx.author().name))
              }
         }
     } else {
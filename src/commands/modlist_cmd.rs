@@ -1,24 +1,119 @@
-use crate::types::{Context, Error, BOT_EMBED_COLOR};
+use crate::{
+    mod_utils,
+    pagination::{self, PAGE_SIZE},
+    types::{Context, Error, ModEntry, ModVersionBranch, BOT_EMBED_COLOR},
+};
 use poise::serenity_prelude as serenity;
-use poise::CreateReply;
+use poise::{ChoiceParameter, CreateReply};
+use std::cmp::Ordering;
+use tracing::info;
 
-/// Provides a link to the Skater XL working mod list.
-#[poise::command(slash_command, prefix_command)]
-pub async fn modlist(ctx: Context<'_>) -> Result<(), Error> {
-    let mod_list_url = "https://skatebit.app/mods"; // Updated URL
+pub const MOD_LIST_URL: &str = "https://skatebit.app/mods"; // Updated URL
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ChoiceParameter)]
+pub enum ModSort {
+    #[name = "Title"]
+    Title,
+    #[name = "Author"]
+    Author,
+}
+
+/// Sorts case-insensitively. Mods without an author are listed last when sorting by author.
+pub fn sort_mods(mods: &[ModEntry], sort: ModSort) -> Vec<&ModEntry> {
+    let mut sorted: Vec<&ModEntry> = mods.iter().collect();
+    let by_title = |a: &&ModEntry, b: &&ModEntry| a.title.to_lowercase().cmp(&b.title.to_lowercase());
+    match sort {
+        ModSort::Title => sorted.sort_by(by_title),
+        ModSort::Author => sorted.sort_by(|a, b| {
+            match (&a.author, &b.author) {
+                (Some(x), Some(y)) => x.to_lowercase().cmp(&y.to_lowercase()),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+            .then_with(|| by_title(a, b))
+        }),
+    }
+    sorted
+}
+
+fn format_mod_line(entry: &ModEntry) -> String {
+    let author = entry.author.as_deref().unwrap_or("Unknown");
+    let version = entry.working_version.as_deref().map(|v| format!(" · v{}", v)).unwrap_or_default();
+    format!("**{}** — {}{}", entry.title, author, version)
+}
+
+pub fn build_mod_page_embed(
+    mods: &[&ModEntry],
+    page: usize,
+    branch: ModVersionBranch,
+    sort: ModSort,
+    requested_by: &str,
+) -> serenity::CreateEmbed {
+    let total_pages = pagination::page_count(mods.len(), PAGE_SIZE);
+    let description = pagination::page_slice(mods, page, PAGE_SIZE)
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| format!("`{}.` {}", page * PAGE_SIZE + i + 1, format_mod_line(entry)))
+        .collect::<Vec<_>>()
+        .join("\n");
 
-    let embed = serenity::CreateEmbed::default()
+    serenity::CreateEmbed::default()
+        .title(format!("📜 Skater XL Script Mods — {}", branch))
+        .url(MOD_LIST_URL)
+        .description(description)
+        .color(BOT_EMBED_COLOR)
+        .footer(serenity::CreateEmbedFooter::new(format!(
+            "Page {}/{} · {} mods · Sorted by {} · Requested by {}",
+            page + 1, total_pages, mods.len(), sort.name(), requested_by
+        )))
+}
+
+pub fn build_link_embed(requested_by: &str) -> serenity::CreateEmbed {
+    serenity::CreateEmbed::default()
         .title("🔗 Skater XL Mod List")
         .description(format!(
             "You can find the full, community-curated list of Skater XL script mods here:\n\n**[Click here to view the Mod List]({})**", // Made link text bold and a clear call to action
-            mod_list_url 
+            MOD_LIST_URL
         ))
         .color(BOT_EMBED_COLOR)
         .timestamp(serenity::Timestamp::now())
-        .footer(serenity::CreateEmbedFooter::new(format!("Requested by {}", ctx.author().name)));
+        .footer(serenity::CreateEmbedFooter::new(format!("Requested by {}", requested_by)))
+}
+
+/// Links the Skater XL working mod list, or browses it here when a branch is chosen.
+#[poise::command(slash_command, prefix_command)]
+pub async fn modlist(
+    ctx: Context<'_>,
+    #[description = "Browse the cached mod list for this branch inside Discord"]
+    branch: Option<ModVersionBranch>,
+    #[description = "Sort order when browsing (default: Title)"]
+    sort: Option<ModSort>,
+) -> Result<(), Error> {
+    let requested_by = ctx.author().name.clone();
+    let Some(branch) = branch else {
+        ctx.send(CreateReply::default().embed(build_link_embed(&requested_by))).await?;
+        return Ok(());
+    };
+    let sort = sort.unwrap_or(ModSort::Title);
+
+    let mods = ctx
+        .data()
+        .mod_cache
+        .read()
+        .await
+        .get(mod_utils::resolve_version_slug(branch))
+        .cloned()
+        .unwrap_or_default();
 
-    let reply = CreateReply::default().embed(embed);
-    ctx.send(reply).await?;
+    if mods.is_empty() {
+        info!(%branch, "Mod list cache empty, falling back to the link.");
+        ctx.send(CreateReply::default().embed(build_link_embed(&requested_by))).await?;
+        return Ok(());
+    }
 
-    Ok(())
+    info!(user = %requested_by, %branch, count = mods.len(), ?sort, "Browsing mod list");
+    let sorted = sort_mods(&mods, sort);
+    let total_pages = pagination::page_count(sorted.len(), PAGE_SIZE);
+    pagination::paginate(ctx, total_pages, |page| build_mod_page_embed(&sorted, page, branch, sort, &requested_by)).await
 }
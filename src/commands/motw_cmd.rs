@@ -0,0 +1,135 @@
+use crate::{
+    commands::map_cmd::find_map,
+    config, motw,
+    pagination::{self, PAGE_SIZE},
+    types::{ApiModioMap, Context, Error, MotwPoll},
+};
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+use tracing::{info, warn};
+
+async fn reply_ephemeral(ctx: Context<'_>, content: impl Into<String>) -> Result<(), Error> {
+    ctx.send(CreateReply::default().content(content).ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Weekly community vote for the best map.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    subcommands("open", "close", "status"),
+    subcommand_required,
+    required_permissions = "MANAGE_GUILD",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn motw(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Opens this week's poll in this channel with 2–5 nominated maps.
+#[poise::command(slash_command, prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn open(
+    ctx: Context<'_>,
+    #[description = "Nominee 1"]
+    #[autocomplete = "crate::commands::map_cmd::map_name_autocomplete"]
+    map1: String,
+    #[description = "Nominee 2"]
+    #[autocomplete = "crate::commands::map_cmd::map_name_autocomplete"]
+    map2: String,
+    #[description = "Nominee 3"]
+    #[autocomplete = "crate::commands::map_cmd::map_name_autocomplete"]
+    map3: Option<String>,
+    #[description = "Nominee 4"]
+    #[autocomplete = "crate::commands::map_cmd::map_name_autocomplete"]
+    map4: Option<String>,
+    #[description = "Nominee 5"]
+    #[autocomplete = "crate::commands::map_cmd::map_name_autocomplete"]
+    map5: Option<String>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return Ok(()) };
+    if ctx.data().motw.get_poll(guild_id.get()).await?.is_some() {
+        return reply_ephemeral(ctx, "A map of the week poll is already open here. Close it first with `/motw close`.").await;
+    }
+
+    let searches: Vec<String> = [Some(map1), Some(map2), map3, map4, map5].into_iter().flatten().collect();
+    let mut nominees: Vec<ApiModioMap> = Vec::with_capacity(searches.len());
+    for search in &searches {
+        match find_map(ctx.data().maps.as_ref(), search).await? {
+            Some(map) if nominees.iter().any(|n| n.id == map.id) => {
+                return reply_ephemeral(ctx, format!("**{}** is nominated twice.", map.name)).await;
+            }
+            Some(map) => nominees.push(map),
+            None => return reply_ephemeral(ctx, format!("❌ Map not found matching: '{}'. Use the autocomplete suggestions.", search)).await,
+        }
+    }
+
+    let now = serenity::Timestamp::now().unix_timestamp();
+    let closes_at = config::next_cron_run(&ctx.data().config.schedule.motw_close, now);
+    // The poll is keyed by its message, so the message goes out first and only gets its vote buttons once the poll is saved.
+    let embed = motw::build_poll_embed(&nominees, closes_at);
+    let handle = ctx.send(CreateReply::default().embed(embed.clone())).await?;
+    let message = handle.message().await?;
+
+    let poll = MotwPoll {
+        guild_id: guild_id.get(),
+        channel_id: ctx.channel_id().get(),
+        message_id: message.id.get(),
+        nominees: nominees.iter().map(|m| m.id).collect(),
+        opened_at: now,
+    };
+    if !ctx.data().motw.open_poll(&poll).await? {
+        warn!(guild_id = %guild_id, "Another map of the week poll was opened concurrently, discarding this one");
+        handle.delete(ctx).await?;
+        return reply_ephemeral(ctx, "A map of the week poll is already open here.").await;
+    }
+    handle.edit(ctx, CreateReply::default().embed(embed).components(motw::poll_buttons(&nominees))).await?;
+    info!(user = %ctx.author().name, guild_id = %guild_id, nominees = ?poll.nominees, "Map of the week poll opened");
+    Ok(())
+}
+
+/// Closes the open poll now and announces the winner.
+#[poise::command(slash_command, prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn close(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return Ok(()) };
+    let now = serenity::Timestamp::now().unix_timestamp();
+    let Some(closed) = motw::finish_poll(ctx.data(), guild_id.get(), now).await? else {
+        return reply_ephemeral(ctx, "There is no open map of the week poll.").await;
+    };
+    motw::announce(ctx.http(), &closed).await?;
+    reply_ephemeral(ctx, "Poll closed.").await
+}
+
+/// Shows the current vote counts to you.
+#[poise::command(slash_command, prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return Ok(()) };
+    let Some(poll) = ctx.data().motw.get_poll(guild_id.get()).await? else {
+        return reply_ephemeral(ctx, "There is no open map of the week poll.").await;
+    };
+    let votes = ctx.data().motw.votes(&poll).await?;
+    let maps = ctx.data().maps.get_many(&poll.nominees).await?;
+    let lines: Vec<String> = poll
+        .nominees
+        .iter()
+        .zip(maps)
+        .zip(motw::tally(&poll.nominees, &votes))
+        .map(|((id, map), count)| format!("{} — **{}**", map.map(|m| m.name).unwrap_or_else(|| format!("Map #{}", id)), count))
+        .collect();
+    reply_ephemeral(ctx, format!("Poll opened <t:{}:R> · {} voters\n{}", poll.opened_at, votes.len(), lines.join("\n"))).await
+}
+
+/// Past map of the week winners in this server.
+#[poise::command(slash_command, prefix_command, guild_only)]
+pub async fn halloffame(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return Ok(()) };
+    let winners = ctx.data().motw.winners(guild_id.get()).await?;
+    if winners.is_empty() {
+        return reply_ephemeral(ctx, "No map of the week has been crowned here yet.").await;
+    }
+
+    let lines: Vec<String> = winners.iter().map(motw::hall_of_fame_line).collect();
+    let footer = format!("{} winners · Open a new poll with /motw open", lines.len());
+    let total_pages = pagination::page_count(lines.len(), PAGE_SIZE);
+    pagination::paginate(ctx, total_pages, |page| pagination::build_list_page_embed(&lines, page, "🏆 Hall of Fame", &footer)).await
+}
@@ -0,0 +1,51 @@
+use crate::{
+    commands::map_cmd::{build_modio_embed, find_tagged, title_suggestions},
+    components,
+    types::{ApiModioMap, Context, Error, FavoriteItem, SCRIPT_MOD_TAG},
+};
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+use tracing::{error, info, warn};
+
+pub async fn script_name_autocomplete(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    title_suggestions(ctx.data().maps.as_ref(), SCRIPT_MOD_TAG, partial).await
+}
+
+pub fn build_script_embed(entry: &ApiModioMap, requested_by: &str) -> serenity::CreateEmbed {
+    build_modio_embed(entry, "Download Script", requested_by)
+}
+
+/// Search for a script mod hosted on mod.io by name.
+#[poise::command(slash_command, prefix_command)]
+pub async fn script(
+    ctx: Context<'_>,
+    #[description = "Script name or ID (use autocomplete for best results)"]
+    #[autocomplete = "script_name_autocomplete"]
+    search: String,
+) -> Result<(), Error> {
+    info!(user = %ctx.author().name, query = %search, "Script command received");
+
+    let found = match find_tagged(ctx.data().maps.as_ref(), SCRIPT_MOD_TAG, &search).await {
+        Ok(found) => found,
+        Err(e) => {
+            error!("Script Command: Failed to query the mod database: {}", e);
+            ctx.say("Sorry, I couldn't connect to the mod database right now. Please try again later.").await?;
+            return Ok(());
+        }
+    };
+
+    let reply_message = if let Some(entry) = found {
+        info!(script_name = %entry.name, script_id = entry.id, "Script found and processed");
+        CreateReply::default()
+            .embed(build_script_embed(&entry, &ctx.author().name))
+            .components(components::item_action_rows(&FavoriteItem::Map { id: entry.id }))
+    } else {
+        warn!(query = %search, "Final: Script not found or ambiguous");
+        CreateReply::default()
+            .content(format!("❌ Script not found matching: '{}'.\nTip: Use the autocomplete suggestions, or try `/mod` for the community mod list.", search))
+            .ephemeral(true)
+    };
+
+    ctx.send(reply_message).await?;
+    Ok(())
+}
@@ -0,0 +1,261 @@
+use crate::{
+    analytics,
+    commands::{
+        map_cmd::build_map_embed,
+        mod_cmd::{build_mod_embed, find_mod_matches},
+        script_cmd::build_script_embed,
+    },
+    components,
+    mod_utils,
+    repository::MapRepository,
+    types::{Context, Data, Error, FavoriteItem, ModEntry, ModVersionBranch, BOT_EMBED_COLOR, MAP_TAG, SCRIPT_MOD_TAG},
+};
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+use std::{collections::HashMap, time::Duration};
+use tracing::{error, info, warn};
+
+/// Discord allows at most 25 options in a select menu.
+pub const MAX_RESULTS: usize = 25;
+
+/// How long the result picker stays active.
+const PICKER_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Where a search result comes from. The order is the tie-break when results rank equally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SearchKind {
+    Map,
+    Script,
+    Mod,
+}
+
+/// One ranked result of `/search`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit {
+    pub kind: SearchKind,
+    pub title: String,
+    /// Identifies the entry to open: a mod.io ID for maps and scripts, title and branch for community mods.
+    pub item: FavoriteItem,
+    /// Lower is better, see [`match_score`].
+    pub score: u8,
+}
+
+impl SearchHit {
+    pub fn badge(&self) -> String {
+        match (&self.kind, &self.item) {
+            (SearchKind::Map, _) => "🗺️ Map".to_string(),
+            (SearchKind::Script, _) => "📜 Script".to_string(),
+            (SearchKind::Mod, FavoriteItem::Mod { branch, .. }) => format!("🧩 Mod · {}", branch),
+            (SearchKind::Mod, _) => "🧩 Mod".to_string(),
+        }
+    }
+}
+
+/// How well `title` matches `query`, ignoring case: 0 for an exact match, 1 for a prefix,
+/// 2 when a later word starts with the query, 3 for any other substring. `None` if it doesn't match.
+pub fn match_score(title: &str, query: &str) -> Option<u8> {
+    let title = title.trim().to_lowercase();
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+    if title == query {
+        Some(0)
+    } else if title.starts_with(&query) {
+        Some(1)
+    } else if title.split(|c: char| !c.is_alphanumeric()).any(|word| word.starts_with(&query)) {
+        Some(2)
+    } else if title.contains(&query) {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+/// Best matches first; equally good matches are ordered maps, scripts, mods, then by title and branch.
+pub fn rank_hits(mut hits: Vec<SearchHit>) -> Vec<SearchHit> {
+    hits.sort_by(|a, b| {
+        a.score
+            .cmp(&b.score)
+            .then_with(|| a.kind.cmp(&b.kind))
+            .then_with(|| a.title.to_lowercase().cmp(&b.title.to_lowercase()))
+            .then_with(|| a.item.key().cmp(&b.item.key()))
+    });
+    hits.truncate(MAX_RESULTS);
+    hits
+}
+
+/// Matches against the cached community mod list, one hit per branch a mod appears in.
+pub fn mod_cache_hits(mod_cache: &HashMap<String, Vec<ModEntry>>, query: &str) -> Vec<SearchHit> {
+    let mut hits = Vec::new();
+    for (slug, mods) in mod_cache {
+        let Some(branch) = mod_utils::branch_for_slug(slug) else { continue };
+        for entry in mods {
+            if let Some(score) = match_score(&entry.title, query) {
+                hits.push(SearchHit {
+                    kind: SearchKind::Mod,
+                    title: entry.title.clone(),
+                    item: FavoriteItem::Mod { title: entry.title.clone(), branch },
+                    score,
+                });
+            }
+        }
+    }
+    hits
+}
+
+/// Matches from one mod.io title index. The index only supports prefix lookups, so these are prefix matches.
+pub async fn modio_hits(maps: &dyn MapRepository, tag: &str, kind: SearchKind, query: &str) -> Result<Vec<SearchHit>, Error> {
+    let entries = maps.search_titles(tag, query, MAX_RESULTS).await?;
+    let ids: Vec<i32> = entries.iter().map(|entry| entry.id).collect();
+    let found = maps.get_many(&ids).await?;
+    Ok(entries
+        .into_iter()
+        .zip(found)
+        .filter_map(|(entry, found)| {
+            // Stale index members have nothing to open.
+            let title = found?.name;
+            let score = match_score(&title, query).unwrap_or(1);
+            Some(SearchHit { kind, title, item: FavoriteItem::Map { id: entry.id }, score })
+        })
+        .collect())
+}
+
+/// Searches maps, mod.io scripts and the community mod list. A source that fails is logged and skipped.
+pub async fn search_all(data: &Data, query: &str) -> Vec<SearchHit> {
+    let mut hits = Vec::new();
+    for (tag, kind) in [(MAP_TAG, SearchKind::Map), (SCRIPT_MOD_TAG, SearchKind::Script)] {
+        match modio_hits(data.maps.as_ref(), tag, kind, query).await {
+            Ok(found) => hits.extend(found),
+            Err(e) => error!("Search: {} title lookup failed: {}", tag, e),
+        }
+    }
+    let mod_cache_guard = data.mod_cache.read().await;
+    hits.extend(mod_cache_hits(&mod_cache_guard, query));
+    rank_hits(hits)
+}
+
+pub fn build_results_embed(query: &str, hits: &[SearchHit]) -> serenity::CreateEmbed {
+    let lines: Vec<String> = hits
+        .iter()
+        .enumerate()
+        .map(|(i, hit)| format!("**{}.** {} — {}", i + 1, hit.badge(), mod_utils::truncate_with_ellipsis(&hit.title, 80)))
+        .collect();
+    serenity::CreateEmbed::default()
+        .title(format!("🔎 Results for '{}'", mod_utils::truncate_with_ellipsis(query.trim(), 100)))
+        .description(lines.join("\n"))
+        .color(BOT_EMBED_COLOR)
+        .footer(serenity::CreateEmbedFooter::new("Pick a result below to open it"))
+}
+
+fn result_picker(id_prefix: &str, hits: &[SearchHit]) -> Vec<serenity::CreateActionRow> {
+    let options = hits
+        .iter()
+        .enumerate()
+        .map(|(i, hit)| {
+            let label = mod_utils::truncate_with_ellipsis(&format!("{}. {}", i + 1, hit.title), 100);
+            serenity::CreateSelectMenuOption::new(label, i.to_string()).description(hit.badge())
+        })
+        .collect();
+    vec![serenity::CreateActionRow::SelectMenu(
+        serenity::CreateSelectMenu::new(id_prefix, serenity::CreateSelectMenuKind::String { options }).placeholder("Open a result..."),
+    )]
+}
+
+fn find_cached_mod<'a>(mod_cache: &'a HashMap<String, Vec<ModEntry>>, title: &str, branch: ModVersionBranch) -> Option<&'a ModEntry> {
+    let mods = mod_cache.get(mod_utils::resolve_version_slug(branch))?;
+    find_mod_matches(mods, title).into_iter().next()
+}
+
+/// The detail embed and action buttons for a result, as its own command would show them.
+/// `None` if the entry disappeared since the search ran.
+pub async fn open_hit(
+    data: &Data,
+    hit: &SearchHit,
+    requested_by: &str,
+) -> Result<Option<(serenity::CreateEmbed, Vec<serenity::CreateActionRow>)>, Error> {
+    match &hit.item {
+        FavoriteItem::Map { id } => {
+            let Some(entry) = data.maps.get_by_id(*id).await? else { return Ok(None) };
+            Ok(Some(match hit.kind {
+                SearchKind::Script => (build_script_embed(&entry, requested_by), components::item_action_rows(&hit.item)),
+                _ => (build_map_embed(&entry, requested_by), components::map_action_rows(&entry)),
+            }))
+        }
+        FavoriteItem::Mod { title, branch } => {
+            let mod_cache = data.mod_cache.read().await;
+            let dead_links = data.dead_links.read().await;
+            Ok(find_cached_mod(&mod_cache, title, *branch)
+                .map(|entry| (build_mod_embed(entry, *branch, requested_by, &dead_links), components::item_action_rows(&hit.item))))
+        }
+    }
+}
+
+/// Search maps, mod.io scripts and community mods at once.
+#[poise::command(slash_command, prefix_command)]
+pub async fn search(
+    ctx: Context<'_>,
+    #[description = "What to look for"]
+    #[min_length = 2]
+    query: String,
+) -> Result<(), Error> {
+    info!(user = %ctx.author().name, query = %query, "Search command received");
+    let hits = search_all(ctx.data(), &query).await;
+    if hits.is_empty() {
+        analytics::failed_search(ctx, &query);
+        let reply = CreateReply::default()
+            .content(format!("❌ Nothing found matching '{}' in maps, scripts or mods.", query.trim()))
+            .ephemeral(true);
+        ctx.send(reply).await?;
+        return Ok(());
+    }
+
+    let id_prefix = format!("search:{}", ctx.id());
+    let handle = ctx
+        .send(CreateReply::default().embed(build_results_embed(&query, &hits)).components(result_picker(&id_prefix, &hits)))
+        .await?;
+
+    while let Some(press) = serenity::ComponentInteractionCollector::new(ctx)
+        .filter({
+            let filter_id = id_prefix.clone();
+            move |press| press.data.custom_id == filter_id
+        })
+        .timeout(PICKER_TIMEOUT)
+        .await
+    {
+        let choice = match &press.data.kind {
+            serenity::ComponentInteractionDataKind::StringSelect { values } => values.first().and_then(|v| v.parse::<usize>().ok()),
+            _ => None,
+        };
+        let Some(hit) = choice.and_then(|i| hits.get(i)) else { continue };
+        let by_author = press.user.id == ctx.author().id;
+        let response = match open_hit(ctx.data(), hit, &press.user.name).await? {
+            // The author's pick replaces the list; anyone else gets a private copy.
+            Some((embed, rows)) if by_author => {
+                serenity::CreateInteractionResponse::UpdateMessage(serenity::CreateInteractionResponseMessage::new().embed(embed).components(rows))
+            }
+            Some((embed, rows)) => serenity::CreateInteractionResponse::Message(
+                serenity::CreateInteractionResponseMessage::new().embed(embed).components(rows).ephemeral(true),
+            ),
+            None => {
+                warn!(item = %hit.item.key(), "Search: Picked result is no longer available");
+                serenity::CreateInteractionResponse::Message(
+                    serenity::CreateInteractionResponseMessage::new().content(format!("**{}** is no longer available.", hit.title)).ephemeral(true),
+                )
+            }
+        };
+        let opened = matches!(response, serenity::CreateInteractionResponse::UpdateMessage(_));
+        press.create_response(ctx.serenity_context(), response).await?;
+        if opened {
+            analytics::resolved_item(ctx, &hit.item);
+            info!(user = %press.user.name, item = %hit.item.key(), "Search result opened");
+            return Ok(());
+        }
+    }
+
+    // Drop the picker once it times out so a stale menu doesn't linger.
+    if let Err(e) = handle.edit(ctx, CreateReply::default().embed(build_results_embed(&query, &hits)).components(Vec::new())).await {
+        warn!(error = %e, "Failed to remove search picker after timeout");
+    }
+    Ok(())
+}
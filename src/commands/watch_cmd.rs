@@ -0,0 +1,63 @@
+use crate::{
+    commands::favorites_cmd::{describe_favorites, item_choices},
+    pagination::{self, PAGE_SIZE},
+    types::{Context, Error, FavoriteItem},
+};
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+use tracing::{error, info};
+
+async fn watch_autocomplete(ctx: Context<'_>, partial: &str) -> Vec<serenity::AutocompleteChoice> {
+    match ctx.data().watches.list(ctx.author().id.get()).await {
+        Ok(items) => item_choices(ctx.data().maps.as_ref(), &items, partial).await,
+        Err(e) => {
+            error!("Autocomplete: Failed to load watch list: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Maps and mods you get a DM about when they're updated.
+#[poise::command(slash_command, prefix_command, subcommands("list", "remove"), subcommand_required)]
+pub async fn watch(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Lists the maps and mods you're watching.
+#[poise::command(slash_command, prefix_command)]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    let items = ctx.data().watches.list(ctx.author().id.get()).await?;
+    info!(user = %ctx.author().name, count = items.len(), "Watch list requested");
+
+    if items.is_empty() {
+        let reply = CreateReply::default()
+            .content("You aren't watching anything yet. Press 👀 **Watch** under a `/map` or `/mod` result to get a DM when it's updated.")
+            .ephemeral(true);
+        ctx.send(reply).await?;
+        return Ok(());
+    }
+
+    let lines = describe_favorites(ctx.data().maps.as_ref(), &items).await;
+    let title = format!("👀 {}'s Watch List", ctx.author().name);
+    let footer = format!("{} watched · Updates are sent by DM", lines.len());
+    let total_pages = pagination::page_count(lines.len(), PAGE_SIZE);
+    pagination::paginate(ctx, total_pages, |page| pagination::build_list_page_embed(&lines, page, &title, &footer)).await
+}
+
+/// Stops watching a map or mod.
+#[poise::command(slash_command, prefix_command)]
+pub async fn remove(
+    ctx: Context<'_>,
+    #[description = "Watched item to remove"]
+    #[autocomplete = "watch_autocomplete"]
+    item: String,
+) -> Result<(), Error> {
+    let Some(item) = FavoriteItem::from_key(&item) else {
+        ctx.send(CreateReply::default().content("Please pick an item from the autocomplete list.").ephemeral(true)).await?;
+        return Ok(());
+    };
+    let removed = ctx.data().watches.remove(ctx.author().id.get(), &item).await?;
+    let content = if removed { "You'll no longer get updates about that." } else { "You weren't watching that." };
+    ctx.send(CreateReply::default().content(content).ephemeral(true)).await?;
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use crate::{
+    mod_utils,
+    types::{ApiModioMap, FavoriteItem},
+};
+use poise::serenity_prelude as serenity;
+
+/// Discord rejects custom IDs longer than this.
+pub const MAX_CUSTOM_ID_LEN: usize = 100;
+
+/// Buttons that must keep working after the command that sent them has finished (and across restarts).
+/// Their custom IDs are handled in [`crate::events`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComponentAction {
+    ToggleFavorite(FavoriteItem),
+    ToggleWatch(FavoriteItem),
+    MotwVote { map_id: i32 },
+    /// Opens the `/author` profile of a map's submitter.
+    AuthorProfile { author_id: i32 },
+}
+
+impl ComponentAction {
+    pub fn custom_id(&self) -> String {
+        match self {
+            Self::ToggleFavorite(item) => format!("fav:{}", item.key()),
+            Self::ToggleWatch(item) => format!("watch:{}", item.key()),
+            Self::MotwVote { map_id } => format!("motw:{}", map_id),
+            Self::AuthorProfile { author_id } => format!("author:{}", author_id),
+        }
+    }
+
+    pub fn parse(custom_id: &str) -> Option<Self> {
+        let (kind, rest) = custom_id.split_once(':')?;
+        match kind {
+            "fav" => FavoriteItem::from_key(rest).map(Self::ToggleFavorite),
+            "watch" => FavoriteItem::from_key(rest).map(Self::ToggleWatch),
+            "motw" => rest.parse().ok().map(|map_id| Self::MotwVote { map_id }),
+            "author" => rest.parse().ok().map(|author_id| Self::AuthorProfile { author_id }),
+            _ => None,
+        }
+    }
+}
+
+/// Action buttons shown under a map or mod embed. Empty when the item's ID would not fit in a custom ID.
+pub fn item_action_rows(item: &FavoriteItem) -> Vec<serenity::CreateActionRow> {
+    let favorite = ComponentAction::ToggleFavorite(item.clone()).custom_id();
+    let watch = ComponentAction::ToggleWatch(item.clone()).custom_id();
+    if favorite.len() > MAX_CUSTOM_ID_LEN || watch.len() > MAX_CUSTOM_ID_LEN {
+        return Vec::new();
+    }
+    vec![serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(favorite).label("Favorite").emoji('⭐').style(serenity::ButtonStyle::Secondary),
+        serenity::CreateButton::new(watch).label("Watch").emoji('👀').style(serenity::ButtonStyle::Secondary),
+    ])]
+}
+
+/// [`item_action_rows`] for a map, plus a button to browse the rest of its author's maps.
+pub fn map_action_rows(map: &ApiModioMap) -> Vec<serenity::CreateActionRow> {
+    let author = ComponentAction::AuthorProfile { author_id: map.submitted_by.id };
+    let label = mod_utils::truncate_with_ellipsis(&format!("More by {}", map.submitted_by.username), 80);
+    let author_button = serenity::CreateButton::new(author.custom_id()).label(label).emoji('🛹').style(serenity::ButtonStyle::Secondary);
+
+    let mut rows = item_action_rows(&FavoriteItem::Map { id: map.id });
+    match rows.first_mut() {
+        Some(serenity::CreateActionRow::Buttons(buttons)) => buttons.push(author_button),
+        _ => rows.push(serenity::CreateActionRow::Buttons(vec![author_button])),
+    }
+    rows
+}
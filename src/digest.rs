@@ -0,0 +1,270 @@
+use crate::{
+    config, mod_utils,
+    stats::{self, DownloadGain},
+    tags,
+    types::{ApiModioMap, Data, DigestSubscription, Error, FavoriteItem, ModEntry, ModVersionBranch, StatsSnapshot, BOT_EMBED_COLOR, MAP_TAG},
+    watch,
+};
+use poise::serenity_prelude as serenity;
+use std::collections::{BTreeMap, HashMap};
+use tracing::{error, info};
+
+/// Posting schedule for guilds that don't pick one: Sundays at 17:00 UTC.
+pub const DEFAULT_DIGEST_CRON: &str = "0 0 17 * * Sun";
+
+/// Digests are meant to summarize, so a guild schedule may not post more than once a day.
+pub const MIN_DIGEST_INTERVAL_SECS: i64 = 86_400;
+
+/// How far back the first digest of a guild looks for new and updated maps.
+const FIRST_DIGEST_WINDOW_SECS: i64 = 7 * 86_400;
+
+const MAX_GAINERS: usize = 5;
+const MAX_LINES_PER_FIELD: usize = 10;
+
+/// Discord caps embed field values at 1024 characters.
+const MAX_FIELD_LEN: usize = 1024;
+
+/// Checks a guild's cron expression, including that it doesn't fire more than once a day.
+pub fn validate_digest_cron(expr: &str, now: i64) -> Result<(), String> {
+    config::validate_cron(expr)?;
+    let mut previous = config::next_cron_run(expr, now).ok_or("the schedule never runs")?;
+    for _ in 0..10 {
+        let Some(next) = config::next_cron_run(expr, previous) else { break };
+        if next - previous < MIN_DIGEST_INTERVAL_SECS {
+            return Err("digests can be posted at most once a day".to_string());
+        }
+        previous = next;
+    }
+    Ok(())
+}
+
+/// Whether the guild's schedule fired since its last digest was due (or since it subscribed).
+pub fn is_due(subscription: &DigestSubscription, now: i64) -> bool {
+    let after = subscription.last_attempt_at.or(subscription.last_posted_at).unwrap_or(subscription.subscribed_at);
+    config::next_cron_run(&subscription.cron, after).is_some_and(|next| next <= now)
+}
+
+/// Start of the period the next digest covers.
+pub fn period_start(subscription: &DigestSubscription, now: i64) -> i64 {
+    subscription.last_posted_at.unwrap_or(now - FIRST_DIGEST_WINDOW_SECS)
+}
+
+/// One change to the community mod list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModChange {
+    Added { title: String, version: String },
+    Removed { title: String },
+    VersionChanged { title: String, from: String, to: String },
+}
+
+fn mod_key(entry: &ModEntry, branch: ModVersionBranch) -> String {
+    FavoriteItem::Mod { title: entry.title.clone(), branch }.key()
+}
+
+/// Working version of every cached community mod, keyed like [`FavoriteItem::key`].
+pub fn mod_snapshot(mod_cache: &HashMap<String, Vec<ModEntry>>) -> BTreeMap<String, String> {
+    mod_cache
+        .iter()
+        .filter_map(|(slug, mods)| Some((mod_utils::branch_for_slug(slug)?, mods)))
+        .flat_map(|(branch, mods)| mods.iter().map(move |entry| (mod_key(entry, branch), watch::mod_fingerprint(entry))))
+        .collect()
+}
+
+/// The next digest's baseline: the current mod list, keeping the previous entries of branches missing from the cache.
+pub fn merge_mod_snapshot(seen: &BTreeMap<String, String>, mod_cache: &HashMap<String, Vec<ModEntry>>) -> BTreeMap<String, String> {
+    let mut merged: BTreeMap<String, String> = seen
+        .iter()
+        .filter(|(key, _)| match FavoriteItem::from_key(key) {
+            Some(FavoriteItem::Mod { branch, .. }) => !mod_cache.contains_key(mod_utils::resolve_version_slug(branch)),
+            _ => false,
+        })
+        .map(|(key, version)| (key.clone(), version.clone()))
+        .collect();
+    merged.extend(mod_snapshot(mod_cache));
+    merged
+}
+
+/// Mod list changes per branch since `seen` was taken. Branches missing from the cache are skipped rather than reported as emptied.
+pub fn mod_changes(seen: &BTreeMap<String, String>, mod_cache: &HashMap<String, Vec<ModEntry>>) -> Vec<(ModVersionBranch, Vec<ModChange>)> {
+    let or_unknown = |v: &str| if v.is_empty() { "unknown".to_string() } else { v.to_string() };
+    mod_utils::VERSION_SLUGS
+        .iter()
+        .filter_map(|slug| Some((mod_utils::branch_for_slug(slug)?, mod_cache.get(*slug)?)))
+        .map(|(branch, mods)| {
+            let mut changes = Vec::new();
+            let mut current = BTreeMap::new();
+            for entry in mods {
+                let key = mod_key(entry, branch);
+                let version = watch::mod_fingerprint(entry);
+                match seen.get(&key) {
+                    None => changes.push(ModChange::Added { title: entry.title.clone(), version: or_unknown(&version) }),
+                    Some(previous) if *previous != version => changes.push(ModChange::VersionChanged {
+                        title: entry.title.clone(),
+                        from: or_unknown(previous),
+                        to: or_unknown(&version),
+                    }),
+                    Some(_) => {}
+                }
+                current.insert(key, ());
+            }
+            for key in seen.keys().filter(|key| !current.contains_key(*key)) {
+                if let Some(FavoriteItem::Mod { title, branch: seen_branch }) = FavoriteItem::from_key(key) {
+                    if seen_branch == branch {
+                        changes.push(ModChange::Removed { title });
+                    }
+                }
+            }
+            (branch, changes)
+        })
+        .filter(|(_, changes)| !changes.is_empty())
+        .collect()
+}
+
+/// Everything a digest reports for one period.
+#[derive(Debug, Clone)]
+pub struct DigestReport {
+    pub since: i64,
+    pub until: i64,
+    /// Maps first published in the period, newest first.
+    pub added: Vec<ApiModioMap>,
+    /// Older maps updated in the period, most recent first.
+    pub updated: Vec<ApiModioMap>,
+    pub gainers: Vec<DownloadGain>,
+    pub mod_changes: Vec<(ModVersionBranch, Vec<ModChange>)>,
+}
+
+impl DigestReport {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.gainers.is_empty() && self.mod_changes.is_empty()
+    }
+}
+
+pub fn build_report(
+    maps: &[ApiModioMap],
+    baselines: &HashMap<i32, StatsSnapshot>,
+    mod_cache: &HashMap<String, Vec<ModEntry>>,
+    mods_seen: &BTreeMap<String, String>,
+    since: i64,
+    until: i64,
+) -> DigestReport {
+    let maps: Vec<&ApiModioMap> = maps.iter().filter(|map| map.has_tag(MAP_TAG)).collect();
+    let mut added: Vec<ApiModioMap> = maps.iter().filter(|map| map.date_added >= since).map(|&map| map.clone()).collect();
+    added.sort_by_key(|map| std::cmp::Reverse(map.date_added));
+    let mut updated: Vec<ApiModioMap> =
+        maps.iter().filter(|map| map.date_added < since && map.date_updated >= since).map(|&map| map.clone()).collect();
+    updated.sort_by_key(|map| std::cmp::Reverse(map.date_updated));
+    let mut gainers = stats::download_gains(maps, baselines);
+    gainers.truncate(MAX_GAINERS);
+
+    DigestReport { since, until, added, updated, gainers, mod_changes: mod_changes(mods_seen, mod_cache) }
+}
+
+/// Gathers the guild's next digest from the cached map list, stats snapshots and mod cache.
+pub async fn compile_digest(data: &Data, subscription: &DigestSubscription, now: i64) -> Result<DigestReport, Error> {
+    let since = period_start(subscription, now);
+    let ids: Vec<i32> = data.map_list.read().await.iter().map(|map| map.id).collect();
+    let baselines = stats::window_baselines(data, &ids, since).await?;
+    let maps = data.map_list.read().await;
+    let mod_cache = data.mod_cache.read().await;
+    Ok(build_report(&maps, &baselines, &mod_cache, &subscription.mods_seen, since, now))
+}
+
+/// Joins list lines into a field value, ending with "…and N more" when they don't all fit.
+fn capped_lines(lines: &[String]) -> String {
+    let mut value = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        if i == MAX_LINES_PER_FIELD || value.len() + line.len() > MAX_FIELD_LEN - 20 {
+            value.push_str(&format!("…and {} more", lines.len() - i));
+            break;
+        }
+        value.push_str(line);
+        value.push('\n');
+    }
+    value.trim_end().to_string()
+}
+
+fn describe_mod_change(change: &ModChange) -> String {
+    match change {
+        ModChange::Added { title, version } => format!("➕ **{}** ({})", title, version),
+        ModChange::Removed { title } => format!("➖ ~~{}~~", title),
+        ModChange::VersionChanged { title, from, to } => format!("🔁 **{}** {} → {}", title, from, to),
+    }
+}
+
+pub fn build_digest_embed(report: &DigestReport) -> serenity::CreateEmbed {
+    let mut description = format!("What changed since <t:{}:D>.", report.since);
+    if report.is_empty() {
+        description.push_str("\nA quiet one: no new or updated maps, no download gains and no mod list changes.");
+    }
+    let mut embed = serenity::CreateEmbed::default()
+        .title("📰 Skatebit Digest")
+        .description(description)
+        .color(BOT_EMBED_COLOR)
+        .timestamp(serenity::Timestamp::from_unix_timestamp(report.until).unwrap_or_else(|_| serenity::Timestamp::now()));
+
+    if !report.added.is_empty() {
+        let lines: Vec<String> = report.added.iter().map(tags::tagged_map_line).collect();
+        embed = embed.field(format!("🆕 New maps ({})", lines.len()), capped_lines(&lines), false);
+    }
+    if !report.updated.is_empty() {
+        let lines: Vec<String> = report.updated.iter().map(tags::tagged_map_line).collect();
+        embed = embed.field(format!("🔄 Updated maps ({})", lines.len()), capped_lines(&lines), false);
+    }
+    if !report.gainers.is_empty() {
+        let lines: Vec<String> = report
+            .gainers
+            .iter()
+            .enumerate()
+            .map(|(i, gain)| {
+                format!(
+                    "**{}.** [{}]({}) +{}",
+                    i + 1,
+                    mod_utils::truncate_with_ellipsis(&gain.name, 60),
+                    gain.profile_url,
+                    mod_utils::format_count(gain.gained)
+                )
+            })
+            .collect();
+        embed = embed.field("📈 Biggest download gains", capped_lines(&lines), false);
+    }
+    for (branch, changes) in &report.mod_changes {
+        let lines: Vec<String> = changes.iter().map(describe_mod_change).collect();
+        embed = embed.field(format!("🧩 Mod list · {} ({})", branch, changes.len()), capped_lines(&lines), false);
+    }
+    embed
+}
+
+/// Posts the guild's digest and moves its period and mod baseline forward.
+pub async fn post_digest(data: &Data, http: &serenity::Http, subscription: &DigestSubscription, now: i64) -> Result<(), Error> {
+    let report = compile_digest(data, subscription, now).await?;
+    let channel = serenity::ChannelId::new(subscription.channel_id);
+    channel.send_message(http, serenity::CreateMessage::new().embed(build_digest_embed(&report))).await?;
+
+    let mods_seen = merge_mod_snapshot(&subscription.mods_seen, &*data.mod_cache.read().await);
+    data.digests
+        .save(&DigestSubscription { last_posted_at: Some(now), last_attempt_at: Some(now), mods_seen, ..subscription.clone() })
+        .await?;
+    info!(guild_id = subscription.guild_id, "Digest posted");
+    Ok(())
+}
+
+/// Scheduled job: posts the digest of every guild whose schedule fired since its last one.
+pub async fn run_due_digests(data: &Data, http: &serenity::Http) {
+    let subscriptions = match data.digests.all().await {
+        Ok(subscriptions) => subscriptions,
+        Err(e) => {
+            error!("Digest: Failed to load subscriptions: {}", e);
+            return;
+        }
+    };
+    let now = serenity::Timestamp::now().unix_timestamp();
+    for subscription in subscriptions.iter().filter(|s| is_due(s, now)) {
+        if let Err(e) = post_digest(data, http, subscription, now).await {
+            error!(guild_id = subscription.guild_id, "Digest: Failed to post: {}", e);
+            let skipped = DigestSubscription { last_attempt_at: Some(now), ..subscription.clone() };
+            if let Err(e) = data.digests.save(&skipped).await {
+                error!(guild_id = subscription.guild_id, "Digest: Failed to record the skipped run: {}", e);
+            }
+        }
+    }
+}
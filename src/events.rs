@@ -0,0 +1,120 @@
+use crate::{
+    authors,
+    components::ComponentAction,
+    mentions,
+    types::{Data, Error},
+    watch,
+};
+use poise::serenity_prelude as serenity;
+use tracing::{error, info, warn};
+
+/// Framework-wide event hook for everything that isn't a command invocation.
+pub async fn event_handler(
+    ctx: &serenity::Context,
+    event: &serenity::FullEvent,
+    _framework: poise::FrameworkContext<'_, Data, Error>,
+    data: &Data,
+) -> Result<(), Error> {
+    match event {
+        serenity::FullEvent::InteractionCreate { interaction: serenity::Interaction::Component(component) } => {
+            if let Some(action) = ComponentAction::parse(&component.data.custom_id) {
+                // Answer store errors instead of leaving the button spinning until Discord reports a failed interaction.
+                if let Err(e) = handle_component_action(ctx, component, action, data).await {
+                    error!(error = ?e, custom_id = %component.data.custom_id, "Error handling button press");
+                    let content = "Oops, an internal error occurred. Please try again in a moment.".to_string();
+                    if let Err(e) = respond_ephemeral(ctx, component, content).await {
+                        error!(error = ?e, "Failed to send error message to Discord");
+                    }
+                }
+            }
+        }
+        serenity::FullEvent::Message { new_message } => mentions::handle_message(ctx, new_message, data).await?,
+        _ => {}
+    }
+    Ok(())
+}
+
+async fn respond_ephemeral(ctx: &serenity::Context, component: &serenity::ComponentInteraction, content: String) -> Result<(), Error> {
+    component
+        .create_response(
+            ctx,
+            serenity::CreateInteractionResponse::Message(
+                serenity::CreateInteractionResponseMessage::new().content(content).ephemeral(true),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+async fn handle_component_action(
+    ctx: &serenity::Context,
+    component: &serenity::ComponentInteraction,
+    action: ComponentAction,
+    data: &Data,
+) -> Result<(), Error> {
+    let user_id = component.user.id.get();
+    let item_name = component
+        .message
+        .embeds
+        .first()
+        .and_then(|embed| embed.title.clone())
+        .unwrap_or_else(|| "this item".to_string());
+
+    match action {
+        ComponentAction::ToggleFavorite(item) => {
+            let now_favorite = data.favorites.toggle(user_id, &item).await?;
+            info!(user = %component.user.name, item = %item.key(), now_favorite, "Favorite toggled");
+            let content = if now_favorite {
+                format!("⭐ Added **{}** to your favorites. See them with `/favorites list`.", item_name)
+            } else {
+                format!("Removed **{}** from your favorites.", item_name)
+            };
+            respond_ephemeral(ctx, component, content).await
+        }
+        ComponentAction::ToggleWatch(item) => {
+            let now_watching = data.watches.toggle(user_id, &item).await?;
+            info!(user = %component.user.name, item = %item.key(), now_watching, "Watch toggled");
+            if now_watching {
+                if let Err(e) = watch::record_baseline(data, &item).await {
+                    warn!(item = %item.key(), "Failed to record watch baseline: {}", e);
+                }
+            }
+            let content = if now_watching {
+                format!("👀 Watching **{}**. I'll DM you when it's updated — make sure your DMs are open. See your list with `/watch list`.", item_name)
+            } else {
+                format!("Stopped watching **{}**.", item_name)
+            };
+            respond_ephemeral(ctx, component, content).await
+        }
+        ComponentAction::MotwVote { map_id } => {
+            let Some(guild_id) = component.guild_id else { return Ok(()) };
+            let poll = data.motw.get_poll(guild_id.get()).await?;
+            let Some(poll) = poll.filter(|p| p.message_id == component.message.id.get() && p.nominees.contains(&map_id)) else {
+                return respond_ephemeral(ctx, component, "This poll has already closed.".to_string()).await;
+            };
+            data.motw.vote(&poll, user_id, map_id).await?;
+            info!(user = %component.user.name, guild_id = %guild_id, map_id, "Map of the week vote cast");
+            let map_name = data.maps.get_by_id(map_id).await?.map(|m| m.name).unwrap_or_else(|| format!("Map #{}", map_id));
+            let content = format!("🗳️ Your vote for **{}** is in. You can change it until voting closes.", map_name);
+            respond_ephemeral(ctx, component, content).await
+        }
+        ComponentAction::AuthorProfile { author_id } => {
+            let Some(author) = data.authors.read().await.get(author_id).cloned() else {
+                let content = "I don't know that author yet. Try again after the next index refresh.".to_string();
+                return respond_ephemeral(ctx, component, content).await;
+            };
+            let mut maps: Vec<_> = data.maps.get_many(&author.map_ids).await?.into_iter().flatten().collect();
+            authors::sort_author_maps(&mut maps);
+            let embed = authors::build_author_embed(&author, &maps, 0);
+            component
+                .create_response(
+                    ctx,
+                    serenity::CreateInteractionResponse::Message(
+                        serenity::CreateInteractionResponseMessage::new().embed(embed).ephemeral(true),
+                    ),
+                )
+                .await?;
+            Ok(())
+        }
+    }
+}
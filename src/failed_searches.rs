@@ -0,0 +1,120 @@
+use crate::{
+    analytics,
+    mod_utils::{self, format_count},
+    types::{Context, Data, Error, FailedSearch, SearchTarget, BOT_EMBED_COLOR},
+};
+use poise::serenity_prelude as serenity;
+use poise::ChoiceParameter;
+use std::collections::HashSet;
+use tracing::warn;
+
+/// Queries listed by `/admin searches`.
+pub const REPORT_LIMIT: usize = 15;
+
+/// Close matches shown per query.
+pub const MAX_CANDIDATES: usize = 3;
+
+/// Titles less alike than this (see [`similarity`]) aren't offered as candidates.
+pub const MIN_SIMILARITY: f64 = 0.5;
+
+/// Long pastes are cut down before they are counted.
+const MAX_QUERY_LEN: usize = 100;
+
+/// The form failed queries are counted under: lowercase, punctuation dropped, whitespace collapsed,
+/// so `"Brooklyn-Banks!"` and `"brooklyn banks"` add up.
+pub fn normalize_query(query: &str) -> String {
+    let cleaned: String = query.chars().map(|c| if c.is_alphanumeric() { c } else { ' ' }).collect();
+    let normalized = cleaned.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    normalized.chars().take(MAX_QUERY_LEN).collect()
+}
+
+/// Levenshtein distance, counted in characters.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = Vec::with_capacity(b.len() + 1);
+        current.push(i + 1);
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// How alike a normalized query and a title are, from 0 to 1 (equal once normalized).
+/// A query found whole inside the title, like `"xxl"` in `"XXL Mod"`, scores 0.8 however short it is.
+pub fn similarity(query: &str, title: &str) -> f64 {
+    let title = normalize_query(title);
+    if query.is_empty() || title.is_empty() {
+        return 0.0;
+    }
+    if query == title {
+        return 1.0;
+    }
+    let longest = query.chars().count().max(title.chars().count());
+    let by_edits = 1.0 - edit_distance(query, &title) as f64 / longest as f64;
+    let contained = title.contains(query) || query.contains(title.as_str());
+    if contained { by_edits.max(0.8) } else { by_edits }
+}
+
+/// Up to `limit` titles most like `query`, best first. Titles that only differ in case or punctuation are listed once.
+pub fn nearest_candidates<'a>(query: &str, titles: impl IntoIterator<Item = &'a str>, limit: usize) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut scored: Vec<(f64, &str)> = titles
+        .into_iter()
+        .filter(|title| seen.insert(normalize_query(title)))
+        .map(|title| (similarity(query, title), title))
+        .filter(|(score, _)| *score >= MIN_SIMILARITY)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.to_lowercase().cmp(&b.1.to_lowercase())));
+    scored.into_iter().take(limit).map(|(_, title)| title.to_string()).collect()
+}
+
+/// Titles failed queries are matched against: every map name, or every community mod title across branches.
+pub async fn candidate_titles(data: &Data, target: SearchTarget) -> Result<Vec<String>, Error> {
+    Ok(match target {
+        SearchTarget::Maps => data.map_list.read().await.iter().map(|map| map.name.clone()).collect(),
+        SearchTarget::Mods => data.mod_cache.read().await.values().flatten().map(|entry| entry.title.clone()).collect(),
+    })
+}
+
+/// Counts a `/map` or `/mod` search that found nothing, for `/admin searches` and `/admin stats`. Storage errors are only logged.
+pub async fn record(ctx: Context<'_>, target: SearchTarget, query: &str) {
+    analytics::failed_search(ctx, query);
+    let query = normalize_query(query);
+    if query.is_empty() {
+        return;
+    }
+    if let Err(e) = ctx.data().failed_searches.record(target, &query).await {
+        warn!(query = %query, target = ?target, "Failed searches: Failed to record query: {}", e);
+    }
+}
+
+/// One line of `/admin searches`: rank, query, count and the closest titles.
+pub fn failed_search_line(rank: usize, search: &FailedSearch, candidates: &[String]) -> String {
+    let matches = if candidates.is_empty() {
+        "no close match".to_string()
+    } else {
+        format!("→ {}", candidates.iter().map(|title| mod_utils::truncate_with_ellipsis(title, 40)).collect::<Vec<_>>().join(", "))
+    };
+    format!("**{}.** `{}` · {}× · {}", rank, mod_utils::truncate_with_ellipsis(&search.query, 50), format_count(search.count), matches)
+}
+
+/// The `/admin searches` report, with each query's closest titles from `titles`.
+pub fn build_failed_searches_embed(target: SearchTarget, searches: &[FailedSearch], titles: &[String]) -> serenity::CreateEmbed {
+    let embed = serenity::CreateEmbed::default().title(format!("🔍 Failed Searches · {}", target.name())).color(BOT_EMBED_COLOR);
+    if searches.is_empty() {
+        return embed.description(format!("No failed {} searches recorded yet.", target.key()));
+    }
+    let lines: Vec<String> = searches
+        .iter()
+        .enumerate()
+        .map(|(i, search)| failed_search_line(i + 1, search, &nearest_candidates(&search.query, titles.iter().map(String::as_str), MAX_CANDIDATES)))
+        .collect();
+    embed
+        .description(lines.join("\n"))
+        .footer(serenity::CreateEmbedFooter::new("No close match usually means it's missing; a close match may deserve an alias."))
+}
@@ -2,9 +2,28 @@ pub mod commands;
 pub mod types;
 pub mod mod_utils;
 pub mod scheduler;
+pub mod repository;
+pub mod config;
+pub mod pagination;
+pub mod components;
+pub mod events;
+pub mod watch;
+pub mod motw;
+pub mod authors;
+pub mod tags;
+pub mod link_health;
+pub mod stats;
+pub mod digest;
+pub mod trend;
+pub mod presence;
+pub mod mentions;
+pub mod rate_limit;
+pub mod analytics;
+pub mod failed_searches;
+pub mod map_cache;
 
 use poise::serenity_prelude as serenity;
-use std::{collections::HashMap, env, sync::Arc};
+use std::sync::Arc;
 use dotenvy::dotenv;
 use types::{Data, Error as AppError};
 use anyhow::{Context as AnyhowContext, Result as AnyhowResult};
@@ -18,19 +37,23 @@ pub async fn run() -> AnyhowResult<()> {
         }
     }
 
-    let token = env::var("DISCORD_TOKEN")
-        .context("DISCORD_TOKEN environment variable not set")?;
-    
-    let redis_url = env::var("REDIS_URL")
-        .unwrap_or_else(|_| {
-            warn!("REDIS_URL not set, defaulting to redis://127.0.0.1:6379");
-            "redis://127.0.0.1:6379".to_string()
-        });
+    let config = config::Config::load().context("Failed to load bot configuration")?;
+    info!(
+        mods_api = %config.api.mods_base_url,
+        redis_namespace = ?config.redis.namespace,
+        dev_guild = ?config.discord.dev_guild,
+        owners = config.discord.owners.len(),
+        "Configuration loaded and validated."
+    );
+    let token = config.discord.token.expose().to_string();
+    let owners = config.discord.owners.iter().map(|id| serenity::UserId::new(*id)).collect();
+    let dev_guild = config.discord.dev_guild.map(serenity::GuildId::new);
+    let features = config.features.clone();
 
     let intents = serenity::GatewayIntents::non_privileged()
         | serenity::GatewayIntents::MESSAGE_CONTENT;
 
-    let app_data = Arc::new(Data::new(&redis_url)
+    let app_data = Arc::new(Data::new(config)
         .context("Failed to initialize application data with Redis pool")?);
     
     let app_data_for_scheduler = app_data.clone();
@@ -41,15 +64,32 @@ pub async fn run() -> AnyhowResult<()> {
                 commands::ping_cmd::ping(),
                 commands::age_cmd::age(),
                 commands::map_cmd::map(),
+                commands::maps_cmd::maps(),
+                commands::script_cmd::script(),
+                commands::search_cmd::search(),
                 commands::modlist_cmd::modlist(),
                 commands::mod_cmd::modsearch(),
+                commands::admin_cmd::admin(),
+                commands::favorites_cmd::favorites(),
+                commands::watch_cmd::watch(),
+                commands::motw_cmd::motw(),
+                commands::motw_cmd::halloffame(),
+                commands::author_cmd::author(),
+                commands::digest_cmd::digest(),
+                commands::mentions_cmd::mentions(),
             ],
+            owners,
             prefix_options: poise::PrefixFrameworkOptions {
-                prefix: Some("~".into()),
+                prefix: features.prefix_commands.then(|| "~".into()),
                 case_insensitive_commands: true,
                 ..Default::default()
             },
+            command_check: Some(|ctx| Box::pin(rate_limit::check(ctx))),
+            pre_command: |ctx| Box::pin(async move { analytics::started(ctx) }),
+            post_command: |ctx| Box::pin(analytics::record(ctx, true)),
+            reply_callback: Some(analytics::on_reply),
             on_error: |error| Box::pin(on_error(error)),
+            event_handler: |ctx, event, framework, data| Box::pin(events::event_handler(ctx, event, framework, data)),
             ..Default::default()
         })
         .setup(move |ctx, ready, framework_ref| {
@@ -63,68 +103,79 @@ pub async fn run() -> AnyhowResult<()> {
                 info!("Initial Setup: Data will be fetched from Redis on demand by commands.");
 
                 info!("Initial Setup: Populating slug-based mod cache...");
-                let slugs_to_fetch = ["1228", "12104"];
-                let mut mod_cache_map_for_setup = HashMap::new();
-                let mut total_mods_loaded_setup = 0;
-                let mut versions_loaded_count_setup = 0;
-
-                for slug in slugs_to_fetch {
-                    match mod_utils::fetch_mods_for_version(&data_for_setup.http_client, slug).await {
-                        Ok(mods) => {
-                            info!(count = mods.len(), slug, "Initial Setup: Fetched slug-based mods for slug.");
-                            total_mods_loaded_setup += mods.len();
-                            if !mods.is_empty() { versions_loaded_count_setup += 1; }
-                            mod_cache_map_for_setup.insert(slug.to_string(), mods);
-                        }
-                        Err(e) => { 
-                            error!(error = ?e, slug, "Initial Setup: Failed slug-based mod fetch for slug.");
-                        }
-                    }
+                if mod_utils::refresh_mod_cache(&data_for_setup).await == 0 {
+                    warn!("Initial Setup: No slug-based mods loaded, mod cache might be empty or fetch failed.");
                 }
-                if total_mods_loaded_setup > 0 || versions_loaded_count_setup > 0 {
-                    *data_for_setup.mod_cache.write().await = mod_cache_map_for_setup;
-                    info!( total_mods = total_mods_loaded_setup, versions_attempted = slugs_to_fetch.len(), versions_loaded = versions_loaded_count_setup, "Initial Setup: Slug-based mod cache population complete.");
+
+                info!("Initial Setup: Building map indexes...");
+                if map_cache::refresh_map_indexes(&data_for_setup).await == 0 {
+                    warn!("Initial Setup: No maps loaded; /author and /maps tag suggestions and pasted map links stay empty until the next refresh.");
+                }
+
+                let dead_links = link_health::load_dead_links(&data_for_setup).await;
+                info!(dead_links, "Initial Setup: Loaded download link health from the last check.");
+
+                let mention_channels = mentions::load_channels(&data_for_setup).await;
+                info!(mention_channels, "Initial Setup: Loaded channels with mention replies.");
+
+                if data_for_setup.config.presence.enabled {
+                    tokio::spawn(presence::rotate_presence(ctx.clone(), (*data_for_setup).clone()));
                 } else {
-                    warn!("Initial Setup: No slug-based mods loaded, mod cache might be empty or fetch failed.");
+                    info!("Presence rotation disabled by config.");
                 }
                 
                 info!("Starting thorough command cleanup and registration...");
-                if !ready.guilds.is_empty() {
-                    info!("Attempting to clear old guild-specific commands from all {} servers...", ready.guilds.len());
-                    for guild_status in &ready.guilds {
-                        let guild_id = guild_status.id;
-                        info!(guild_id = %guild_id, "Attempting to clear commands for guild...");
-                        if let Err(e) = poise::builtins::register_in_guild(ctx, &[] as &[poise::Command<Data, AppError>], guild_id).await {
-                            warn!(error = %e, guild_id = %guild_id, "Failed to clear commands for guild.");
-                        } else {
-                            info!(guild_id = %guild_id, "Successfully cleared commands for guild.");
+                if features.clear_old_commands {
+                    if !ready.guilds.is_empty() {
+                        info!("Attempting to clear old guild-specific commands from all {} servers...", ready.guilds.len());
+                        for guild_status in &ready.guilds {
+                            let guild_id = guild_status.id;
+                            info!(guild_id = %guild_id, "Attempting to clear commands for guild...");
+                            if let Err(e) = poise::builtins::register_in_guild(ctx, &[] as &[poise::Command<Data, AppError>], guild_id).await {
+                                warn!(error = %e, guild_id = %guild_id, "Failed to clear commands for guild.");
+                            } else {
+                                info!(guild_id = %guild_id, "Successfully cleared commands for guild.");
+                            }
                         }
+                        info!("Finished attempting to clear guild-specific commands.");
+                    } else {
+                        info!("Bot is not in any guilds, skipping guild command cleanup.");
+                    }
+
+                    info!("Attempting to clear ALL old global application commands...");
+                    if let Err(e) = poise::builtins::register_globally(ctx, &[] as &[poise::Command<Data, AppError>]).await {
+                        warn!(error = %e, "Failed to clear all global commands.");
+                    } else {
+                        info!("Successfully cleared all old global commands.");
                     }
-                    info!("Finished attempting to clear guild-specific commands.");
                 } else {
-                    info!("Bot is not in any guilds, skipping guild command cleanup.");
+                    info!("Command cleanup disabled by config, keeping previously registered commands.");
                 }
 
-                info!("Attempting to clear ALL old global application commands...");
-                if let Err(e) = poise::builtins::register_globally(ctx, &[] as &[poise::Command<Data, AppError>]).await {
-                    warn!(error = %e, "Failed to clear all global commands.");
+                // Registered by hand rather than through poise's helpers, so the IDs Discord assigns come back for command mentions.
+                let builders = poise::builtins::create_application_commands(commands_to_register);
+                let registered = if let Some(guild_id) = dev_guild {
+                    info!(guild_id = %guild_id, "Registering current application commands in the dev guild...");
+                    let registered = guild_id.set_commands(ctx, builders).await
+                        .context("Failed to register current commands in the dev guild during setup")?;
+                    info!(guild_id = %guild_id, "Successfully registered current application commands in the dev guild.");
+                    registered
                 } else {
-                    info!("Successfully cleared all old global commands.");
+                    info!("Registering current application commands globally...");
+                    let registered = serenity::Command::set_global_commands(ctx, builders).await
+                        .context("Failed to register current commands globally during setup")?;
+                    info!("Successfully registered current application commands globally.");
+                    registered
+                };
+                if let Some(command) = registered.iter().find(|command| command.name == "author") {
+                    authors::set_author_command_id(command.id.get());
                 }
-            
-                info!("Registering current application commands globally...");
-                poise::builtins::register_globally(ctx, commands_to_register).await
-                    .context("Failed to register current commands globally during setup")?;
-                info!("Successfully registered current application commands globally.");
                 
                 Ok((*data_for_setup).clone())
             })
         })
         .build();
 
-    scheduler::initialize_and_start_scheduler(app_data_for_scheduler).await
-        .context("Failed to initialize and start the scheduler")?;
-
     info!("Building Serenity client...");
     let mut client = serenity::ClientBuilder::new(token, intents)
         .framework(framework)
@@ -132,6 +183,9 @@ pub async fn run() -> AnyhowResult<()> {
         .map_err(|e| { error!(error = %e, "Fatal: Error creating Discord client"); e })
         .context("Fatal error creating Discord client")?;
 
+    scheduler::initialize_and_start_scheduler(app_data_for_scheduler, client.http.clone()).await
+        .context("Failed to initialize and start the scheduler")?;
+
     info!("Starting Discord bot connection...");
     client.start_autosharded().await
         .map_err(|e| { error!(error = %e, "Fatal: Discord client runtime error"); e })
@@ -142,6 +196,16 @@ pub async fn run() -> AnyhowResult<()> {
 
 #[instrument(skip(error))]
 async fn on_error(error: poise::FrameworkError<'_, Data, AppError>) {
+    if let poise::FrameworkError::CommandCheckFailed { error: None, ctx, .. } = &error {
+        if rate_limit::was_limited(*ctx).await {
+            // The rate limiter already told the user to slow down.
+            return;
+        }
+    }
+    // Checks run before pre_command, so only invocations that got as far as parsing their arguments or running are recorded
+    if let Some(ctx) = error.ctx() {
+        analytics::record(ctx, false).await;
+    }
     match error {
         poise::FrameworkError::Setup { error, .. } => {
             error!(error = ?error, "Poise Framework setup error");
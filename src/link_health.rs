@@ -0,0 +1,194 @@
+use crate::{
+    mod_utils,
+    types::{Data, Error, LinkStatus, ModEntry, ModVersionBranch, BOT_EMBED_COLOR},
+};
+use poise::serenity_prelude as serenity;
+use reqwest::{Client, Method, StatusCode};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    time::Duration,
+};
+use tracing::{error, info, warn};
+
+/// Pause between two link checks, so a full run never hammers a host.
+pub const LINK_CHECK_DELAY: Duration = Duration::from_millis(1500);
+
+/// Per-request limit; a host that doesn't answer in time is reported as unreachable but not dead.
+const LINK_CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Discord caps embed field values at 1024 characters.
+const MAX_FIELD_LEN: usize = 1024;
+
+/// Client used for link checks: short timeout, and a user agent so file hosts don't reject us as a bot without one.
+pub fn link_check_client() -> Result<Client, Error> {
+    Ok(Client::builder()
+        .timeout(LINK_CHECK_TIMEOUT)
+        .user_agent(concat!("skatebit-bot/", env!("CARGO_PKG_VERSION"), " (link check)"))
+        .build()?)
+}
+
+/// Statuses that mean the file is gone rather than temporarily unavailable.
+pub fn is_dead_status(status: u16) -> bool {
+    matches!(status, 404 | 410)
+}
+
+/// Every distinct download link in the mod cache, sorted.
+pub fn collect_links(mod_cache: &HashMap<String, Vec<ModEntry>>) -> Vec<String> {
+    let links: BTreeSet<String> = mod_cache
+        .values()
+        .flatten()
+        .flat_map(|entry| entry.download_links.iter().flatten())
+        .map(|link| link.url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .collect();
+    links.into_iter().collect()
+}
+
+/// Checks one link with a HEAD request, retrying with GET for hosts that don't support HEAD.
+/// Redirects are followed, so a link counts as alive if where it ends up is. Only a 404 or 410 marks it dead;
+/// when the host can't be reached at all the result is unknown and the link isn't flagged.
+pub async fn check_link(client: &Client, url: &str, now: i64) -> LinkStatus {
+    let mut result = client.request(Method::HEAD, url).send().await;
+    if matches!(&result, Ok(response) if matches!(response.status(), StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED)) {
+        result = client.get(url).send().await;
+    }
+
+    match result {
+        Ok(response) => {
+            let status = response.status().as_u16();
+            LinkStatus { url: url.to_string(), status: Some(status), error: None, dead: is_dead_status(status), checked_at: now }
+        }
+        Err(e) => {
+            // A refused connection or failed lookup is as likely a blip on our side or the host's as a file that's gone.
+            let error = if e.is_timeout() { "timed out".to_string() } else { e.without_url().to_string() };
+            LinkStatus { url: url.to_string(), status: None, error: Some(error), dead: false, checked_at: now }
+        }
+    }
+}
+
+/// Summary of one full check run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinkCheckSummary {
+    pub checked: usize,
+    pub dead: usize,
+}
+
+/// Checks every link in the mod cache, `delay` apart, and stores the results.
+/// Results for links that left the mod list are dropped, and `data.dead_links` is replaced at the end.
+pub async fn run_link_check(data: &Data, client: &Client, delay: Duration) -> LinkCheckSummary {
+    let links = collect_links(&*data.mod_cache.read().await);
+    let mut dead_links = HashSet::new();
+    let mut summary = LinkCheckSummary::default();
+
+    for (i, url) in links.iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(delay).await;
+        }
+        let status = check_link(client, url, serenity::Timestamp::now().unix_timestamp()).await;
+        if status.dead {
+            warn!(url = %url, status = ?status.status, error = ?status.error, "Link check: Download link is dead");
+            dead_links.insert(url.clone());
+        } else if let Some(error) = &status.error {
+            info!(url = %url, error = %error, "Link check: Download link unreachable, leaving it unflagged");
+        }
+        if let Err(e) = data.link_health.record(&status).await {
+            error!(url = %url, "Link check: Failed to record result: {}", e);
+        }
+        summary.checked += 1;
+    }
+    summary.dead = dead_links.len();
+
+    match data.link_health.all().await {
+        Ok(stored) => {
+            let listed: HashSet<&String> = links.iter().collect();
+            let stale: Vec<String> = stored.into_keys().filter(|url| !listed.contains(url)).collect();
+            if let Err(e) = data.link_health.forget(&stale).await {
+                error!("Link check: Failed to drop results for removed links: {}", e);
+            }
+        }
+        Err(e) => error!("Link check: Failed to load stored results: {}", e),
+    }
+
+    *data.dead_links.write().await = dead_links;
+    info!(checked = summary.checked, dead = summary.dead, "Link check complete.");
+    summary
+}
+
+/// Loads the dead links found by earlier runs into `data.dead_links`, so a restart doesn't forget them.
+pub async fn load_dead_links(data: &Data) -> usize {
+    match data.link_health.all().await {
+        Ok(stored) => {
+            let dead: HashSet<String> = stored.into_values().filter(|status| status.dead).map(|status| status.url).collect();
+            let count = dead.len();
+            *data.dead_links.write().await = dead;
+            count
+        }
+        Err(e) => {
+            error!("Failed to load link health results: {}", e);
+            0
+        }
+    }
+}
+
+/// A dead download link and the mod listing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+    pub mod_title: String,
+    pub label: String,
+    pub status: LinkStatus,
+}
+
+/// Dead links per branch, in mod list order. Branches without dead links are left out.
+pub fn broken_links_report(
+    mod_cache: &HashMap<String, Vec<ModEntry>>,
+    statuses: &HashMap<String, LinkStatus>,
+) -> Vec<(ModVersionBranch, Vec<BrokenLink>)> {
+    mod_utils::VERSION_SLUGS
+        .iter()
+        .filter_map(|slug| Some((mod_utils::branch_for_slug(slug)?, mod_cache.get(*slug)?)))
+        .map(|(branch, mods)| {
+            let broken: Vec<BrokenLink> = mods
+                .iter()
+                .flat_map(|entry| entry.download_links.iter().flatten().map(move |link| (entry, link)))
+                .filter_map(|(entry, link)| {
+                    let status = statuses.get(link.url.trim()).filter(|status| status.dead)?;
+                    Some(BrokenLink { mod_title: entry.title.clone(), label: link.label.clone(), status: status.clone() })
+                })
+                .collect();
+            (branch, broken)
+        })
+        .filter(|(_, broken)| !broken.is_empty())
+        .collect()
+}
+
+/// Why a link counts as dead: the HTTP status, since only a 404 or 410 marks a link dead.
+pub fn describe_failure(status: &LinkStatus) -> String {
+    status.status.map_or_else(|| "unknown".to_string(), |code| code.to_string())
+}
+
+pub fn build_link_report_embed(report: &[(ModVersionBranch, Vec<BrokenLink>)], checked_links: usize) -> serenity::CreateEmbed {
+    let mut embed = serenity::CreateEmbed::default().title("🔗 Broken Download Links").color(BOT_EMBED_COLOR);
+    if report.is_empty() {
+        embed = embed.description(format!("All {} checked links are working.", checked_links));
+    }
+    for (branch, broken) in report {
+        let mut value = String::new();
+        for (i, link) in broken.iter().enumerate() {
+            let line = format!(
+                "**{}** — [{}]({}) ({}, <t:{}:R>)\n",
+                link.mod_title,
+                link.label,
+                link.status.url,
+                describe_failure(&link.status),
+                link.status.checked_at
+            );
+            if value.len() + line.len() > MAX_FIELD_LEN - 20 {
+                value.push_str(&format!("…and {} more", broken.len() - i));
+                break;
+            }
+            value.push_str(&line);
+        }
+        embed = embed.field(format!("{} ({})", branch, broken.len()), value.trim_end(), false);
+    }
+    embed.footer(serenity::CreateEmbedFooter::new(format!("{} links checked", checked_links)))
+}
@@ -0,0 +1,28 @@
+use crate::{
+    authors, mentions, repository, tags,
+    types::{ApiModioMap, Data},
+};
+use tracing::{error, info};
+
+/// Loads every map once and rebuilds everything derived from the whole store. Keeps the old indexes if the store can't be read.
+/// Returns how many maps were loaded.
+pub async fn refresh_map_indexes(data: &Data) -> usize {
+    match repository::load_all_maps(data.maps.as_ref()).await {
+        Ok(maps) => rebuild_map_indexes(data, maps).await,
+        Err(e) => {
+            error!("Failed to load maps for the map indexes: {}", e);
+            0
+        }
+    }
+}
+
+/// Rebuilds the author, tag and map link indexes from `maps` and keeps them as `data.map_list`. Returns how many maps there were.
+pub async fn rebuild_map_indexes(data: &Data, maps: Vec<ApiModioMap>) -> usize {
+    authors::rebuild_author_index(data, &maps).await;
+    tags::rebuild_tag_index(data, &maps).await;
+    mentions::rebuild_slug_index(data, &maps).await;
+    let count = maps.len();
+    *data.map_list.write().await = maps;
+    info!(maps = count, "Map indexes rebuilt.");
+    count
+}
@@ -0,0 +1,323 @@
+use crate::{
+    commands::{map_cmd::build_map_embed, mod_cmd::find_mod_matches},
+    components,
+    mod_utils::{self, format_count},
+    repository::{normalize_title, MapRepository},
+    types::{ApiModioMap, Data, Error, FavoriteItem, ModEntry, ModVersionBranch, BOT_EMBED_COLOR, MAP_TAG},
+};
+use poise::serenity_prelude as serenity;
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, error, info, warn};
+
+/// What precedes a map's `name_id` in its mod.io page URL.
+pub const MAP_URL_PREFIX: &str = "mod.io/g/skaterxl/m/";
+
+/// At most this many maps or mods are embedded per message; the rest are ignored.
+pub const MAX_MENTIONS_PER_MESSAGE: usize = 3;
+
+/// How long the same map or mod isn't embedded again in a channel.
+pub const ITEM_COOLDOWN_SECS: i64 = 10 * 60;
+
+/// How long after a reply further mentions by the same user are ignored.
+pub const USER_COOLDOWN_SECS: i64 = 20;
+
+/// Longer `[[...]]` contents are treated as regular text, not a name.
+const MAX_NAME_LEN: usize = 100;
+const SUMMARY_PREVIEW_LEN: usize = 200;
+const SEARCH_LIMIT: usize = 5;
+
+/// Cooldowns are pruned once this many have piled up.
+const COOLDOWN_PRUNE_THRESHOLD: usize = 1_000;
+
+/// A map or mod referenced in a regular message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mention {
+    /// The `name_id` of a pasted mod.io map link, lowercased.
+    MapLink(String),
+    /// A name written as `[[name]]`.
+    Name(String),
+}
+
+/// Map links (unless wrapped in `<>`) and `[[name]]` references in `content`, in order of appearance, without duplicates and capped at [`MAX_MENTIONS_PER_MESSAGE`].
+pub fn find_mentions(content: &str) -> Vec<Mention> {
+    let mut found: Vec<(usize, Mention)> = Vec::new();
+
+    // ASCII lowercasing keeps byte offsets intact.
+    let lowered = content.to_ascii_lowercase();
+    for (start, _) in lowered.match_indices(MAP_URL_PREFIX) {
+        // `<https://...>` is how Discord users opt out of link previews; leave those alone.
+        // The whitespace may be multibyte, like a non-breaking space, so step over the whole char.
+        let token_start = lowered[..start].char_indices().rev().find(|(_, c)| c.is_whitespace()).map_or(0, |(i, c)| i + c.len_utf8());
+        if lowered[token_start..].starts_with('<') {
+            continue;
+        }
+        let name_id: String =
+            lowered[start + MAP_URL_PREFIX.len()..].chars().take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_')).collect();
+        if !name_id.is_empty() {
+            found.push((start, Mention::MapLink(name_id)));
+        }
+    }
+
+    // Pair every "]]" with the closest "[[" before it, so "[[a [[b]]" still finds "b".
+    let mut rest = 0;
+    while let Some(close) = content[rest..].find("]]").map(|i| rest + i) {
+        if let Some(open) = content[rest..close].rfind("[[").map(|i| rest + i) {
+            let name = content[open + 2..close].trim();
+            if !name.is_empty() && !name.contains('\n') && name.chars().count() <= MAX_NAME_LEN {
+                found.push((open, Mention::Name(name.to_string())));
+            }
+        }
+        rest = close + 2;
+    }
+
+    found.sort_by_key(|(position, _)| *position);
+    let mut mentions: Vec<Mention> = Vec::new();
+    for (_, mention) in found {
+        let duplicate = mentions.iter().any(|seen| match (seen, &mention) {
+            (Mention::Name(a), Mention::Name(b)) => a.to_lowercase() == b.to_lowercase(),
+            (a, b) => a == b,
+        });
+        if !duplicate {
+            mentions.push(mention);
+        }
+    }
+    mentions.truncate(MAX_MENTIONS_PER_MESSAGE);
+    mentions
+}
+
+/// Lowercased `name_id` to ID for every `Map`-tagged entry.
+pub fn slug_index<'a>(maps: impl IntoIterator<Item = &'a ApiModioMap>) -> HashMap<String, i32> {
+    maps.into_iter().filter(|map| map.has_tag(MAP_TAG) && !map.name_id.is_empty()).map(|map| (map.name_id.to_lowercase(), map.id)).collect()
+}
+
+/// Rebuilds `data.map_slugs` from `maps`, see [`crate::map_cache`]. Returns how many maps were indexed.
+pub async fn rebuild_slug_index(data: &Data, maps: &[ApiModioMap]) -> usize {
+    let index = slug_index(maps);
+    let count = index.len();
+    *data.map_slugs.write().await = index;
+    info!(maps = count, "Map link index refreshed.");
+    count
+}
+
+/// Loads the channels with mention replies turned on into `data.mention_channel_ids`.
+pub async fn load_channels(data: &Data) -> usize {
+    match data.mention_channels.all().await {
+        Ok(channels) => {
+            let ids: HashSet<u64> = channels.into_iter().map(|(_, channel_id)| channel_id).collect();
+            let count = ids.len();
+            *data.mention_channel_ids.write().await = ids;
+            count
+        }
+        Err(e) => {
+            error!("Failed to load mention channels: {}", e);
+            0
+        }
+    }
+}
+
+/// The map whose title is `name`, or the only map whose title starts with it.
+pub async fn resolve_map_name(maps: &dyn MapRepository, name: &str) -> Result<Option<ApiModioMap>, Error> {
+    let wanted = normalize_title(name);
+    let entries = maps.search_titles(MAP_TAG, &wanted, SEARCH_LIMIT).await?;
+    let id = match (entries.iter().find(|entry| entry.title == wanted), entries.as_slice()) {
+        (Some(exact), _) => exact.id,
+        (None, [only]) => only.id,
+        _ => return Ok(None),
+    };
+    Ok(maps.get_by_id(id).await?.filter(|map| map.has_tag(MAP_TAG)))
+}
+
+/// The community mod titled `name`, preferring the Beta/Public list over Alpha.
+pub fn resolve_mod_name(mod_cache: &HashMap<String, Vec<ModEntry>>, name: &str) -> Option<(ModEntry, ModVersionBranch)> {
+    [ModVersionBranch::BetaPublic, ModVersionBranch::Alpha].into_iter().find_map(|branch| {
+        let mods = mod_cache.get(mod_utils::resolve_version_slug(branch))?;
+        find_mod_matches(mods, name.trim()).first().map(|entry| ((*entry).clone(), branch))
+    })
+}
+
+/// What a mention resolved to.
+#[derive(Debug, Clone)]
+pub enum MentionTarget {
+    Map(Box<ApiModioMap>),
+    Mod { entry: ModEntry, branch: ModVersionBranch },
+}
+
+impl MentionTarget {
+    pub fn item(&self) -> FavoriteItem {
+        match self {
+            Self::Map(map) => FavoriteItem::Map { id: map.id },
+            Self::Mod { entry, branch } => FavoriteItem::Mod { title: entry.title.clone(), branch: *branch },
+        }
+    }
+}
+
+/// Links resolve through the map link index, names against map titles first and then the mod list.
+pub async fn resolve_mention(data: &Data, mention: &Mention) -> Result<Option<MentionTarget>, Error> {
+    match mention {
+        Mention::MapLink(name_id) => {
+            let Some(id) = data.map_slugs.read().await.get(name_id).copied() else { return Ok(None) };
+            Ok(data.maps.get_by_id(id).await?.filter(|map| map.has_tag(MAP_TAG)).map(|map| MentionTarget::Map(Box::new(map))))
+        }
+        Mention::Name(name) => {
+            if let Some(map) = resolve_map_name(data.maps.as_ref(), name).await? {
+                return Ok(Some(MentionTarget::Map(Box::new(map))));
+            }
+            Ok(resolve_mod_name(&*data.mod_cache.read().await, name).map(|(entry, branch)| MentionTarget::Mod { entry, branch }))
+        }
+    }
+}
+
+pub fn build_compact_map_embed(map: &ApiModioMap) -> serenity::CreateEmbed {
+    let stats = format!(
+        "by **{}** · ⬇️ {} · 👥 {} · {}",
+        map.submitted_by.username,
+        format_count(i64::from(map.stats.downloads_total)),
+        format_count(i64::from(map.stats.subscribers_total)),
+        map.stats.ratings_display_text
+    );
+    let summary = mod_utils::truncate_with_ellipsis(map.summary.trim(), SUMMARY_PREVIEW_LEN);
+    serenity::CreateEmbed::default()
+        .title(&map.name)
+        .url(&map.profile_url)
+        .description(if summary.is_empty() { stats } else { format!("{}\n{}", stats, summary) })
+        .color(BOT_EMBED_COLOR)
+        .thumbnail(map.logo.thumb_320x180.as_deref().unwrap_or(map.logo.original.as_str()))
+        .footer(serenity::CreateEmbedFooter::new(format!("Map · ID: {} · /map for details", map.id)))
+}
+
+pub fn build_compact_mod_embed(entry: &ModEntry, branch: ModVersionBranch, dead_links: &HashSet<String>) -> serenity::CreateEmbed {
+    let mut description = format!(
+        "by **{}** · {} · game {}",
+        entry.author.as_deref().unwrap_or("Unknown"),
+        entry.working_version.as_deref().unwrap_or("N/A"),
+        entry.game_version.as_deref().unwrap_or("N/A")
+    );
+    if let Some(links) = entry.download_links.as_ref().filter(|links| !links.is_empty()) {
+        let links: Vec<String> = links.iter().map(|l| mod_utils::format_download_link(&l.label, &l.url, dead_links)).collect();
+        description.push('\n');
+        description.push_str(&links.join(" | "));
+    }
+    serenity::CreateEmbed::default()
+        .title(&entry.title)
+        .description(description)
+        .color(BOT_EMBED_COLOR)
+        .footer(serenity::CreateEmbedFooter::new(format!("Mod · {} · /mod for details", branch)))
+}
+
+/// Expiry times per key, for the reply cooldowns.
+#[derive(Debug, Default)]
+pub struct Cooldowns {
+    until: HashMap<String, i64>,
+}
+
+impl Cooldowns {
+    pub fn is_active(&self, key: &str, now: i64) -> bool {
+        self.until.get(key).is_some_and(|until| *until > now)
+    }
+
+    pub fn start(&mut self, key: &str, now: i64, secs: i64) {
+        if self.until.len() >= COOLDOWN_PRUNE_THRESHOLD {
+            self.until.retain(|_, until| *until > now);
+        }
+        self.until.insert(key.to_string(), now + secs);
+    }
+}
+
+fn user_cooldown_key(user_id: serenity::UserId) -> String {
+    format!("user:{}", user_id)
+}
+
+fn item_cooldown_key(channel_id: serenity::ChannelId, target: &MentionTarget) -> String {
+    format!("channel:{}:{}", channel_id, target.item().key())
+}
+
+/// What the bot answers a message with.
+pub struct MentionReply {
+    pub embeds: Vec<serenity::CreateEmbed>,
+    /// Maps shown with the full embed because their link was pasted.
+    pub unfurled: Vec<ApiModioMap>,
+    /// Per-channel cooldowns to start once the reply is sent.
+    pub item_keys: Vec<String>,
+}
+
+/// Resolves what a message in `channel_id` mentions, skipping items still on cooldown there. `None` when there is nothing to answer.
+/// Pasted map links are unfurled with the full map embed in every channel when `features.unfurl_links` is on;
+/// `[[names]]` (and links, with unfurling off) get compact embeds in enabled channels only.
+pub async fn prepare_reply(data: &Data, channel_id: serenity::ChannelId, author_name: &str, content: &str, now: i64) -> Option<MentionReply> {
+    let listening = data.mention_channel_ids.read().await.contains(&channel_id.get());
+    let unfurl_links = data.config.features.unfurl_links;
+    if !listening && !unfurl_links {
+        return None;
+    }
+    let mentions: Vec<Mention> =
+        find_mentions(content).into_iter().filter(|mention| listening || matches!(mention, Mention::MapLink(_))).collect();
+
+    let mut reply = MentionReply { embeds: Vec::new(), unfurled: Vec::new(), item_keys: Vec::new() };
+    for mention in &mentions {
+        let target = match resolve_mention(data, mention).await {
+            Ok(Some(target)) => target,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!(mention = ?mention, "Mentions: Failed to resolve: {}", e);
+                continue;
+            }
+        };
+        let key = item_cooldown_key(channel_id, &target);
+        if reply.item_keys.contains(&key) || data.mention_cooldowns.lock().unwrap().is_active(&key, now) {
+            continue;
+        }
+        reply.embeds.push(match (&target, mention) {
+            (MentionTarget::Map(map), Mention::MapLink(_)) if unfurl_links => {
+                reply.unfurled.push((**map).clone());
+                build_map_embed(map, author_name)
+            }
+            (MentionTarget::Map(map), _) => build_compact_map_embed(map),
+            (MentionTarget::Mod { entry, branch }, _) => build_compact_mod_embed(entry, *branch, &*data.dead_links.read().await),
+        });
+        reply.item_keys.push(key);
+    }
+    (!reply.embeds.is_empty()).then_some(reply)
+}
+
+/// Answers the map links and `[[names]]` in a guild message, see [`prepare_reply`].
+pub async fn handle_message(ctx: &serenity::Context, message: &serenity::Message, data: &Data) -> Result<(), Error> {
+    if message.author.bot || message.guild_id.is_none() {
+        return Ok(());
+    }
+    let now = message.timestamp.unix_timestamp();
+    let user_key = user_cooldown_key(message.author.id);
+    if data.mention_cooldowns.lock().unwrap().is_active(&user_key, now) {
+        return Ok(());
+    }
+    let Some(reply) = prepare_reply(data, message.channel_id, &message.author.name, &message.content, now).await else {
+        return Ok(());
+    };
+
+    {
+        let mut cooldowns = data.mention_cooldowns.lock().unwrap();
+        cooldowns.start(&user_key, now, USER_COOLDOWN_SECS);
+        for key in &reply.item_keys {
+            cooldowns.start(key, now, ITEM_COOLDOWN_SECS);
+        }
+    }
+    let components = match (reply.embeds.len(), reply.unfurled.as_slice()) {
+        (1, [map]) => components::map_action_rows(map),
+        _ => Vec::new(),
+    };
+    let response = serenity::CreateMessage::new()
+        .embeds(reply.embeds)
+        .components(components)
+        .reference_message(message)
+        .allowed_mentions(serenity::CreateAllowedMentions::new());
+    message.channel_id.send_message(ctx, response).await?;
+    info!(user = %message.author.name, channel_id = %message.channel_id, items = ?reply.item_keys, "Mentions: Replied with embeds");
+
+    if !reply.unfurled.is_empty() {
+        // Our embed replaces Discord's own link preview. Hiding it needs Manage Messages, which the bot may not have.
+        let suppress = serenity::EditMessage::new().suppress_embeds(true);
+        if let Err(e) = message.channel_id.edit_message(ctx, message.id, suppress).await {
+            debug!(channel_id = %message.channel_id, "Mentions: Could not hide the link preview: {}", e);
+        }
+    }
+    Ok(())
+}
@@ -1,8 +1,15 @@
-use crate::types::{ModEntry, Error as AppError, ModVersionBranch};
+use crate::types::{Data, ModEntry, Error as AppError, ModVersionBranch};
 use reqwest::Client;
+use std::collections::{HashMap, HashSet};
 use tracing::{info, warn, error};
 use anyhow::anyhow;
 
+/// Default skatebit-api host serving `/api/mods/{slug}`.
+pub const DEFAULT_MODS_API_BASE_URL: &str = "https://skatebit-api.vercel.app";
+
+/// Version slugs kept in `Data.mod_cache`, one per `ModVersionBranch`.
+pub const VERSION_SLUGS: [&str; 2] = ["1228", "12104"];
+
 pub fn resolve_version_slug(branch: ModVersionBranch) -> &'static str {
     match branch {
         ModVersionBranch::Alpha => "1228",
@@ -10,11 +17,78 @@ pub fn resolve_version_slug(branch: ModVersionBranch) -> &'static str {
     }
 }
 
+pub fn branch_for_slug(version_slug: &str) -> Option<ModVersionBranch> {
+    match version_slug {
+        "1228" => Some(ModVersionBranch::Alpha),
+        "12104" => Some(ModVersionBranch::BetaPublic),
+        _ => None,
+    }
+}
+
+/// Shortens `text` to at most `max_chars` characters, ending with "..." when cut.
+pub fn truncate_with_ellipsis(text: &str, max_chars: usize) -> String {
+    if text.chars().count() > max_chars {
+        let kept: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+        format!("{}...", kept)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Formats a count with thousands separators, e.g. `12,345`.
+pub fn format_count(count: i64) -> String {
+    let digits = count.unsigned_abs().to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if count < 0 { format!("-{}", grouped) } else { grouped }
+}
+
+/// Re-fetches every version slug through `data.mods` and swaps the result into `data.mod_cache`.
+/// The cache is left untouched when nothing could be loaded. Returns the number of mods loaded.
+pub async fn refresh_mod_cache(data: &Data) -> usize {
+    let mut new_mod_cache_map = HashMap::new();
+    let mut total_mods_loaded = 0;
+    let mut versions_loaded_count = 0;
+
+    for slug in VERSION_SLUGS {
+        match data.mods.fetch_mods(slug).await {
+            Ok(mods) => {
+                info!(count = mods.len(), slug, "Fetched slug-based mods for slug.");
+                total_mods_loaded += mods.len();
+                if !mods.is_empty() { versions_loaded_count += 1; }
+                new_mod_cache_map.insert(slug.to_string(), mods);
+            }
+            Err(e) => {
+                error!(error = ?e, slug, "Failed to fetch slug-based mods for slug.");
+            }
+        }
+    }
+
+    if total_mods_loaded > 0 || versions_loaded_count > 0 {
+        *data.mod_cache.write().await = new_mod_cache_map;
+        info!(
+            total_mods = total_mods_loaded,
+            versions_attempted = VERSION_SLUGS.len(),
+            versions_loaded = versions_loaded_count,
+            "Slug-based mod cache refresh complete."
+        );
+    } else {
+        warn!("No slug-based mods loaded, slug-based mod cache not updated.");
+    }
+    total_mods_loaded
+}
+
 pub async fn fetch_mods_for_version(
     http_client: &Client,
+    base_url: &str,
     version_slug: &str
 ) -> Result<Vec<ModEntry>, AppError> {
-    let url = format!("https://skatebit-api.vercel.app/api/mods/{}", version_slug);
+    let url = format!("{}/api/mods/{}", base_url.trim_end_matches('/'), version_slug);
     info!(url = %url, version = %version_slug, "Fetching mods...");
 
     let response = http_client.get(&url).send().await
@@ -34,7 +108,16 @@ pub async fn fetch_mods_for_version(
     Ok(mods)
 }
 
-pub fn format_mod_entry(mod_entry: &ModEntry) -> String {
+pub fn format_download_link(label: &str, url: &str, dead_links: &HashSet<String>) -> String {
+    if dead_links.contains(url.trim()) {
+        format!("~~[{}]({})~~ ⚠️ dead link", label, url)
+    } else {
+        format!("[{}]({})", label, url)
+    }
+}
+
+/// Renders a mod's details. Links in `dead_links` are struck through and flagged.
+pub fn format_mod_entry(mod_entry: &ModEntry, dead_links: &HashSet<String>) -> String {
     let author = mod_entry.author.as_deref().unwrap_or("Unknown");
     let version = mod_entry.working_version.as_deref().unwrap_or("N/A");
     let game_version = mod_entry.game_version.as_deref().unwrap_or("N/A");
@@ -42,7 +125,7 @@ pub fn format_mod_entry(mod_entry: &ModEntry) -> String {
     let features = mod_entry.features.as_ref().map(|f| if f.is_empty() { "N/A".to_string() } else { f.join(", ") }).unwrap_or_else(|| "N/A".to_string());
     let note = mod_entry.note.as_deref().unwrap_or("");
     let note_line = if note.is_empty() { String::new() } else { format!("**Note:** {}\n", note) };
-    let downloads = mod_entry.download_links.as_ref().filter(|links| !links.is_empty()).map(|links| { links.iter().map(|l| format!("[{}]({})", l.label, l.url)).collect::<Vec<_>>().join(" | ") }).map(|s| format!("**Links:** {}", s)).unwrap_or_else(|| "".to_string());
+    let downloads = mod_entry.download_links.as_ref().filter(|links| !links.is_empty()).map(|links| { links.iter().map(|l| format_download_link(&l.label, &l.url, dead_links)).collect::<Vec<_>>().join(" | ") }).map(|s| format!("**Links:** {}", s)).unwrap_or_default();
 
     format!(
         "**Author:** {}\n**Mod Version:** {}\n**Game Version:** {}\n**Keybind:** {}\n**Features:** {}\n{}{}",
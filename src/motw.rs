@@ -0,0 +1,166 @@
+use crate::{
+    commands::map_cmd::build_map_embed,
+    components::{self, ComponentAction},
+    mod_utils,
+    types::{ApiModioMap, Data, Error, HallOfFameEntry, MotwPoll, BOT_EMBED_COLOR},
+};
+use poise::serenity_prelude as serenity;
+use std::collections::HashMap;
+use tracing::{error, info, warn};
+
+/// A poll that has just been closed, with everything needed to announce the result.
+#[derive(Debug, Clone)]
+pub struct ClosedPoll {
+    pub poll: MotwPoll,
+    /// Nominee entries in ballot order; `None` if a map vanished from the store meanwhile.
+    pub maps: Vec<Option<ApiModioMap>>,
+    pub counts: Vec<usize>,
+    pub winner: Option<HallOfFameEntry>,
+}
+
+/// Votes per nominee, in ballot order. Votes for maps that aren't on the ballot are ignored.
+pub fn tally(nominees: &[i32], votes: &HashMap<u64, i32>) -> Vec<usize> {
+    nominees.iter().map(|id| votes.values().filter(|voted| *voted == id).count()).collect()
+}
+
+/// Index of the most voted nominee. Ties go to the earlier nomination; no votes means no winner.
+pub fn winning_index(counts: &[usize]) -> Option<usize> {
+    let best = *counts.iter().max()?;
+    (best > 0).then(|| counts.iter().position(|&count| count == best)).flatten()
+}
+
+fn nominee_name(map: Option<&ApiModioMap>, id: i32) -> String {
+    map.map(|m| m.name.clone()).unwrap_or_else(|| format!("Map #{}", id))
+}
+
+pub fn build_poll_embed(nominees: &[ApiModioMap], closes_at: Option<i64>) -> serenity::CreateEmbed {
+    let mut lines: Vec<String> = nominees
+        .iter()
+        .enumerate()
+        .map(|(i, map)| format!("**{}.** [{}]({}) by {}", i + 1, map.name, map.profile_url, map.submitted_by.username))
+        .collect();
+    lines.push(String::new());
+    lines.push(match closes_at {
+        Some(closes_at) => format!("Vote with the buttons below. Voting closes <t:{}:F>.", closes_at),
+        None => "Vote with the buttons below.".to_string(),
+    });
+    serenity::CreateEmbed::default()
+        .title("🗳️ Map of the Week")
+        .description(lines.join("\n"))
+        .color(BOT_EMBED_COLOR)
+        .footer(serenity::CreateEmbedFooter::new("One vote per member · You can change your vote until the poll closes"))
+}
+
+pub fn poll_buttons(nominees: &[ApiModioMap]) -> Vec<serenity::CreateActionRow> {
+    let buttons = nominees
+        .iter()
+        .enumerate()
+        .map(|(i, map)| {
+            let label = mod_utils::truncate_with_ellipsis(&format!("{}. {}", i + 1, map.name), 80);
+            serenity::CreateButton::new(ComponentAction::MotwVote { map_id: map.id }.custom_id())
+                .label(label)
+                .style(serenity::ButtonStyle::Primary)
+        })
+        .collect();
+    vec![serenity::CreateActionRow::Buttons(buttons)]
+}
+
+pub fn build_results_embed(closed: &ClosedPoll) -> serenity::CreateEmbed {
+    let winner_id = closed.winner.as_ref().map(|w| w.map_id);
+    let mut lines: Vec<String> = closed
+        .poll
+        .nominees
+        .iter()
+        .zip(&closed.maps)
+        .zip(&closed.counts)
+        .enumerate()
+        .map(|(i, ((id, map), count))| {
+            let plural = if *count == 1 { "" } else { "s" };
+            let line = format!("**{}.** {} — **{} vote{}**", i + 1, nominee_name(map.as_ref(), *id), count, plural);
+            if winner_id == Some(*id) { format!("🏆 {}", line) } else { line }
+        })
+        .collect();
+    lines.push(String::new());
+    lines.push(match &closed.winner {
+        Some(winner) => format!("**{}** is the map of the week!", winner.map_name),
+        None => "Voting closed without any votes.".to_string(),
+    });
+    serenity::CreateEmbed::default()
+        .title("🗳️ Map of the Week — Results")
+        .description(lines.join("\n"))
+        .color(BOT_EMBED_COLOR)
+}
+
+/// One line of `/motw halloffame`: when the poll closed, the winner and its vote count.
+pub fn hall_of_fame_line(entry: &HallOfFameEntry) -> String {
+    let plural = if entry.votes == 1 { "" } else { "s" };
+    format!("<t:{}:D> — 🏆 **{}** ({} vote{})", entry.closed_at, entry.map_name, entry.votes, plural)
+}
+
+/// Closes the guild's poll, if any, and records the winner in the hall of fame.
+pub async fn finish_poll(data: &Data, guild_id: u64, now: i64) -> Result<Option<ClosedPoll>, Error> {
+    let Some((poll, votes)) = data.motw.close_poll(guild_id).await? else { return Ok(None) };
+    let maps = data.maps.get_many(&poll.nominees).await?;
+    let counts = tally(&poll.nominees, &votes);
+
+    let winner = winning_index(&counts).map(|i| HallOfFameEntry {
+        map_id: poll.nominees[i],
+        map_name: nominee_name(maps[i].as_ref(), poll.nominees[i]),
+        votes: counts[i],
+        closed_at: now,
+    });
+    if let Some(winner) = &winner {
+        data.motw.add_winner(guild_id, winner).await?;
+    }
+    info!(guild_id, winner = ?winner.as_ref().map(|w| &w.map_name), "Map of the week poll closed");
+    Ok(Some(ClosedPoll { poll, maps, counts, winner }))
+}
+
+/// Replaces the poll buttons with the results and posts the winner in the poll's channel.
+pub async fn announce(http: &serenity::Http, closed: &ClosedPoll) -> Result<(), Error> {
+    let channel = serenity::ChannelId::new(closed.poll.channel_id);
+    let results = serenity::EditMessage::new().embed(build_results_embed(closed)).components(Vec::new());
+    if let Err(e) = channel.edit_message(http, serenity::MessageId::new(closed.poll.message_id), results).await {
+        warn!(guild_id = closed.poll.guild_id, "Could not update the map of the week poll message: {}", e);
+    }
+
+    let Some(winner) = &closed.winner else {
+        channel.say(http, "🗳️ Map of the week voting closed without any votes.").await?;
+        return Ok(());
+    };
+    let winner_map = closed.maps.iter().flatten().find(|map| map.id == winner.map_id);
+    let mut message = serenity::CreateMessage::new().content(format!(
+        "🏆 **Map of the Week:** {} with {} vote{}!",
+        winner.map_name, winner.votes, if winner.votes == 1 { "" } else { "s" }
+    ));
+    if let Some(map) = winner_map {
+        message = message
+            .embed(build_map_embed(map, "Map of the Week vote"))
+            .components(components::map_action_rows(map));
+    }
+    channel.send_message(http, message).await?;
+    Ok(())
+}
+
+/// Scheduled job: closes and announces every open poll.
+pub async fn close_all_polls(data: &Data, http: &serenity::Http) {
+    let polls = match data.motw.open_polls().await {
+        Ok(polls) => polls,
+        Err(e) => {
+            error!("Map of the week: Failed to load open polls: {}", e);
+            return;
+        }
+    };
+    let now = serenity::Timestamp::now().unix_timestamp();
+    for poll in polls {
+        match finish_poll(data, poll.guild_id, now).await {
+            Ok(Some(closed)) => {
+                if let Err(e) = announce(http, &closed).await {
+                    error!(guild_id = poll.guild_id, "Map of the week: Failed to announce winner: {}", e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => error!(guild_id = poll.guild_id, "Map of the week: Failed to close poll: {}", e),
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::types::{Context, Error, BOT_EMBED_COLOR};
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+use std::time::Duration;
+use tracing::warn;
+
+/// Items per page for list embeds.
+pub const PAGE_SIZE: usize = 10;
+
+/// How long the navigation controls stay active after the last press.
+const PAGINATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Discord allows at most 25 options in a select menu.
+const MAX_JUMP_OPTIONS: usize = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageAction {
+    First,
+    Prev,
+    Next,
+    Last,
+    Jump(usize),
+}
+
+/// Number of pages needed for `item_count` items. An empty list still has one (empty) page.
+pub fn page_count(item_count: usize, per_page: usize) -> usize {
+    item_count.div_ceil(per_page).max(1)
+}
+
+pub fn page_slice<T>(items: &[T], page: usize, per_page: usize) -> &[T] {
+    let start = (page * per_page).min(items.len());
+    let end = (start + per_page).min(items.len());
+    &items[start..end]
+}
+
+/// One page of a plain list embed, `PAGE_SIZE` lines to a page, with the page number ahead of `footer`.
+pub fn build_list_page_embed(lines: &[String], page: usize, title: &str, footer: &str) -> serenity::CreateEmbed {
+    let total_pages = page_count(lines.len(), PAGE_SIZE);
+    serenity::CreateEmbed::default()
+        .title(title)
+        .description(page_slice(lines, page, PAGE_SIZE).join("\n"))
+        .color(BOT_EMBED_COLOR)
+        .footer(serenity::CreateEmbedFooter::new(format!("Page {}/{} · {}", page + 1, total_pages, footer)))
+}
+
+/// Applies a navigation action to the zero-based `page`, clamping to the valid range.
+pub fn apply_page_action(action: PageAction, page: usize, total_pages: usize) -> usize {
+    let last = total_pages.saturating_sub(1);
+    match action {
+        PageAction::First => 0,
+        PageAction::Prev => page.saturating_sub(1),
+        PageAction::Next => (page + 1).min(last),
+        PageAction::Last => last,
+        PageAction::Jump(target) => target.min(last),
+    }
+}
+
+/// Maps a component custom ID (and select values) back to a [`PageAction`], if it belongs to `id_prefix`.
+pub fn parse_page_action(id_prefix: &str, custom_id: &str, values: &[String]) -> Option<PageAction> {
+    match custom_id.strip_prefix(id_prefix)? {
+        ":first" => Some(PageAction::First),
+        ":prev" => Some(PageAction::Prev),
+        ":next" => Some(PageAction::Next),
+        ":last" => Some(PageAction::Last),
+        ":jump" => values.first()?.parse::<usize>().ok().map(PageAction::Jump),
+        _ => None,
+    }
+}
+
+/// Pages offered in the jump menu: all of them, or a window of 25 around the current page.
+fn jump_targets(page: usize, total_pages: usize) -> std::ops::Range<usize> {
+    if total_pages <= MAX_JUMP_OPTIONS {
+        return 0..total_pages;
+    }
+    let start = page.saturating_sub(MAX_JUMP_OPTIONS / 2).min(total_pages - MAX_JUMP_OPTIONS);
+    start..start + MAX_JUMP_OPTIONS
+}
+
+/// Navigation buttons plus a jump-to-page menu. Nothing is rendered for a single page.
+pub fn page_components(id_prefix: &str, page: usize, total_pages: usize) -> Vec<serenity::CreateActionRow> {
+    if total_pages <= 1 {
+        return Vec::new();
+    }
+    let at_start = page == 0;
+    let at_end = page + 1 >= total_pages;
+
+    let buttons = serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(format!("{}:first", id_prefix)).emoji('⏮').disabled(at_start),
+        serenity::CreateButton::new(format!("{}:prev", id_prefix)).emoji('◀').disabled(at_start),
+        serenity::CreateButton::new(format!("{}:next", id_prefix)).emoji('▶').disabled(at_end),
+        serenity::CreateButton::new(format!("{}:last", id_prefix)).emoji('⏭').disabled(at_end),
+    ]);
+
+    let options = jump_targets(page, total_pages)
+        .map(|target| {
+            serenity::CreateSelectMenuOption::new(format!("Page {}", target + 1), target.to_string())
+                .default_selection(target == page)
+        })
+        .collect();
+    let jump_menu = serenity::CreateActionRow::SelectMenu(
+        serenity::CreateSelectMenu::new(format!("{}:jump", id_prefix), serenity::CreateSelectMenuKind::String { options })
+            .placeholder("Jump to page..."),
+    );
+
+    vec![buttons, jump_menu]
+}
+
+/// Sends page 0 and keeps the message interactive until nobody has touched it for a while.
+/// Only the invoking user can turn pages; anyone else gets an ephemeral notice.
+/// `render` builds the embed for a zero-based page index.
+pub async fn paginate<F>(ctx: Context<'_>, total_pages: usize, render: F) -> Result<(), Error>
+where
+    F: Fn(usize) -> serenity::CreateEmbed,
+{
+    let id_prefix = format!("page:{}", ctx.id());
+    let mut page = 0;
+
+    let reply = CreateReply::default()
+        .embed(render(page))
+        .components(page_components(&id_prefix, page, total_pages));
+    let handle = ctx.send(reply).await?;
+
+    if total_pages <= 1 {
+        return Ok(());
+    }
+
+    while let Some(press) = serenity::ComponentInteractionCollector::new(ctx)
+        .filter({
+            let filter_prefix = id_prefix.clone();
+            move |press| press.data.custom_id.starts_with(&filter_prefix)
+        })
+        .timeout(PAGINATION_TIMEOUT)
+        .await
+    {
+        if press.user.id != ctx.author().id {
+            let notice = serenity::CreateInteractionResponseMessage::new()
+                .content("Only the person who ran this command can turn its pages. Run it yourself to browse.")
+                .ephemeral(true);
+            if let Err(e) = press.create_response(ctx.serenity_context(), serenity::CreateInteractionResponse::Message(notice)).await {
+                warn!(error = %e, "Failed to answer a page button pressed by someone else");
+            }
+            continue;
+        }
+        let values = match &press.data.kind {
+            serenity::ComponentInteractionDataKind::StringSelect { values } => values.clone(),
+            _ => Vec::new(),
+        };
+        let Some(action) = parse_page_action(&id_prefix, &press.data.custom_id, &values) else { continue };
+        page = apply_page_action(action, page, total_pages);
+
+        press
+            .create_response(
+                ctx.serenity_context(),
+                serenity::CreateInteractionResponse::UpdateMessage(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .embed(render(page))
+                        .components(page_components(&id_prefix, page, total_pages)),
+                ),
+            )
+            .await?;
+    }
+
+    // Drop the controls once the collector times out so stale buttons don't linger.
+    if let Err(e) = handle.edit(ctx, CreateReply::default().embed(render(page)).components(Vec::new())).await {
+        warn!(error = %e, "Failed to remove pagination controls after timeout");
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::{
+    mod_utils::{self, format_count},
+    types::{ApiModioMap, Data, ModEntry, MAP_TAG},
+};
+use poise::serenity_prelude as serenity;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Always in the rotation, so there is something to show even when Redis is unreachable.
+pub const SEARCH_HINT: &str = "🔎 /map to find a map";
+
+/// Leaves room under Discord's 128 character limit for custom statuses.
+const MAX_MAP_NAME_LEN: usize = 80;
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+/// Community mods across every version branch, counting a mod listed on several branches once.
+pub fn distinct_mod_count(mod_cache: &HashMap<String, Vec<ModEntry>>) -> usize {
+    mod_cache.values().flatten().map(|entry| entry.title.to_lowercase()).collect::<HashSet<_>>().len()
+}
+
+/// The most recently added of `maps`.
+pub fn latest_map(maps: &[ApiModioMap]) -> Option<&ApiModioMap> {
+    maps.iter().max_by_key(|map| map.date_added)
+}
+
+/// The statuses to cycle through. The map count is left out when the map store couldn't be read,
+/// the latest map until the map list has been cached, and mod lines while the mod cache is empty.
+pub fn status_lines(map_count: Option<usize>, latest: Option<&ApiModioMap>, mod_count: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(count) = map_count.filter(|count| *count > 0) {
+        lines.push(format!("🛹 {} map{} indexed", format_count(count as i64), plural(count)));
+    }
+    if let Some(latest) = latest {
+        lines.push(format!("🆕 Latest map: {}", mod_utils::truncate_with_ellipsis(&latest.name, MAX_MAP_NAME_LEN)));
+    }
+    if mod_count > 0 {
+        lines.push(format!("🧩 {} community mod{}", format_count(mod_count as i64), plural(mod_count)));
+    }
+    lines.push(SEARCH_HINT.to_string());
+    lines
+}
+
+/// Builds the statuses from the map store's ID index, the cached map list and the mod cache.
+pub async fn current_status_lines(data: &Data) -> Vec<String> {
+    let map_count = match data.maps.all_ids(MAP_TAG).await {
+        Ok(ids) => Some(ids.len()),
+        Err(e) => {
+            warn!("Presence: Failed to count maps, leaving them out of the rotation: {}", e);
+            None
+        }
+    };
+    let mod_count = distinct_mod_count(&*data.mod_cache.read().await);
+    status_lines(map_count, latest_map(&data.map_list.read().await), mod_count)
+}
+
+/// Cycles the bot's status every `rotation_interval_secs`, rebuilding the statuses at the start of each cycle. Runs until the bot stops.
+pub async fn rotate_presence(ctx: serenity::Context, data: Data) {
+    let interval = Duration::from_secs(data.config.presence.rotation_interval_secs);
+    info!(interval_secs = interval.as_secs(), "Presence rotation started.");
+    let mut ticker = tokio::time::interval(interval);
+    let mut lines = Vec::new();
+    let mut next = 0;
+    loop {
+        ticker.tick().await;
+        if next >= lines.len() {
+            lines = current_status_lines(&data).await;
+            next = 0;
+        }
+        ctx.set_activity(Some(serenity::ActivityData::custom(lines[next].clone())));
+        next += 1;
+    }
+}
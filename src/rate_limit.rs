@@ -0,0 +1,158 @@
+use crate::{
+    config::{RateLimitConfig, RateLimitRule},
+    types::{Context, Error},
+};
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+use std::collections::{HashMap, VecDeque};
+use tracing::info;
+
+/// Members with this permission are treated as moderators and never rate limited.
+pub const BYPASS_PERMISSION: serenity::Permissions = serenity::Permissions::MANAGE_MESSAGES;
+
+/// Idle buckets are dropped once this many have piled up.
+const BUCKET_PRUNE_THRESHOLD: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitScope {
+    User,
+    Channel,
+    Guild,
+    Command,
+}
+
+/// One limit that applies to an invocation, e.g. the invoking user's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bucket {
+    pub scope: LimitScope,
+    pub key: String,
+    pub rule: RateLimitRule,
+}
+
+/// The buckets an invocation of `command` counts against, per the configured scopes.
+pub fn buckets(config: &RateLimitConfig, command: &str, user_id: u64, channel_id: u64, guild_id: Option<u64>) -> Vec<Bucket> {
+    let mut buckets = vec![
+        Bucket { scope: LimitScope::User, key: format!("user:{}", user_id), rule: config.user },
+        Bucket { scope: LimitScope::Channel, key: format!("channel:{}", channel_id), rule: config.channel },
+    ];
+    if let Some(guild_id) = guild_id {
+        buckets.push(Bucket { scope: LimitScope::Guild, key: format!("guild:{}", guild_id), rule: config.guild });
+    }
+    if let Some(&rule) = config.commands.get(command) {
+        buckets.push(Bucket { scope: LimitScope::Command, key: format!("command:{}:user:{}", command, user_id), rule });
+    }
+    buckets.retain(|bucket| !bucket.rule.is_off());
+    buckets
+}
+
+/// Returned when a bucket is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimited {
+    pub scope: LimitScope,
+    pub retry_after_ms: i64,
+}
+
+impl RateLimited {
+    /// Whole seconds until the invocation would be allowed, rounded up.
+    pub fn retry_after_secs(&self) -> i64 {
+        (self.retry_after_ms + 999) / 1000
+    }
+}
+
+/// Sliding window counters, one per bucket key.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    hits: HashMap<String, VecDeque<i64>>,
+}
+
+impl RateLimiter {
+    /// Counts one use against every bucket, unless one of them is full. Then nothing is counted and the longest wait is returned.
+    pub fn try_acquire(&mut self, buckets: &[Bucket], now_ms: i64) -> Result<(), RateLimited> {
+        if self.hits.len() >= BUCKET_PRUNE_THRESHOLD {
+            // Windows are short, so anything idle for an hour is long expired.
+            self.hits.retain(|_, hits| hits.back().is_some_and(|last| now_ms - last < 3_600_000));
+        }
+
+        let mut limited: Option<RateLimited> = None;
+        for bucket in buckets {
+            let window_ms = bucket.rule.per_secs as i64 * 1000;
+            let hits = self.hits.entry(bucket.key.clone()).or_default();
+            while hits.front().is_some_and(|first| now_ms - first >= window_ms) {
+                hits.pop_front();
+            }
+            if hits.len() >= bucket.rule.max as usize {
+                let retry_after_ms = hits.front().map_or(0, |first| first + window_ms - now_ms);
+                if limited.is_none_or(|l| retry_after_ms > l.retry_after_ms) {
+                    limited = Some(RateLimited { scope: bucket.scope, retry_after_ms });
+                }
+            }
+        }
+        if let Some(limited) = limited {
+            return Err(limited);
+        }
+        for bucket in buckets {
+            self.hits.entry(bucket.key.clone()).or_default().push_back(now_ms);
+        }
+        Ok(())
+    }
+}
+
+pub fn limited_message(limited: &RateLimited) -> String {
+    let reason = match limited.scope {
+        LimitScope::User | LimitScope::Command => "",
+        LimitScope::Channel => " This channel is running a lot of commands right now.",
+        LimitScope::Guild => " This server is running a lot of commands right now.",
+    };
+    format!("🐢 Slow down, try again in {}s.{}", limited.retry_after_secs().max(1), reason)
+}
+
+/// Bot owners, and guild members with [`BYPASS_PERMISSION`] in the channel.
+async fn is_moderator(ctx: Context<'_>) -> bool {
+    if ctx.framework().options().owners.contains(&ctx.author().id) {
+        return true;
+    }
+    let Some(member) = ctx.author_member().await else { return false };
+    // Interactions carry the member's resolved permissions; prefix invocations fall back to the cache.
+    let permissions = member.permissions.or_else(|| {
+        let guild = ctx.guild()?;
+        let channel = guild.channels.get(&ctx.channel_id())?;
+        Some(guild.user_permissions_in(channel, &member))
+    });
+    permissions.is_some_and(|p| p.contains(BYPASS_PERMISSION) || p.administrator())
+}
+
+/// Marks an invocation as counted. Poise runs the framework-wide check for every parent of a subcommand too.
+struct RateLimitChecked {
+    /// The invocation was turned down and the user already told to slow down.
+    limited: bool,
+}
+
+/// Framework-wide pre-command check: lets the command run, or tells the user to slow down and stops it.
+/// Moderators skip the buckets entirely, so their commands don't use up a channel's or server's allowance.
+pub async fn check(ctx: Context<'_>) -> Result<bool, Error> {
+    let config = &ctx.data().config.rate_limit;
+    if !config.enabled || ctx.invocation_data::<RateLimitChecked>().await.is_some() {
+        return Ok(true);
+    }
+    ctx.set_invocation_data(RateLimitChecked { limited: false }).await;
+    if is_moderator(ctx).await {
+        return Ok(true);
+    }
+
+    let command = &ctx.command().qualified_name;
+    let buckets = buckets(config, command, ctx.author().id.get(), ctx.channel_id().get(), ctx.guild_id().map(|g| g.get()));
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let Err(limited) = ctx.data().rate_limiter.lock().unwrap().try_acquire(&buckets, now_ms) else {
+        return Ok(true);
+    };
+
+    info!(user = %ctx.author().name, command = %command, scope = ?limited.scope, retry_after_ms = limited.retry_after_ms, "Command rate limited");
+    ctx.set_invocation_data(RateLimitChecked { limited: true }).await;
+    ctx.send(CreateReply::default().content(limited_message(&limited)).ephemeral(true)).await?;
+    Ok(false)
+}
+
+/// Whether [`check`] turned this invocation down, in which case the user was already answered.
+pub async fn was_limited(ctx: Context<'_>) -> bool {
+    ctx.invocation_data::<RateLimitChecked>().await.is_some_and(|checked| checked.limited)
+}
@@ -0,0 +1,30 @@
+use super::ModRepository;
+use crate::{
+    mod_utils,
+    types::{Error, ModEntry},
+};
+use async_trait::async_trait;
+use reqwest::Client;
+
+/// Fetches the community mod list from skatebit-api.
+pub struct HttpModRepository {
+    http_client: Client,
+    base_url: String,
+}
+
+impl HttpModRepository {
+    pub fn new(http_client: Client) -> Self {
+        Self::with_base_url(http_client, mod_utils::DEFAULT_MODS_API_BASE_URL)
+    }
+
+    pub fn with_base_url(http_client: Client, base_url: &str) -> Self {
+        Self { http_client, base_url: base_url.to_string() }
+    }
+}
+
+#[async_trait]
+impl ModRepository for HttpModRepository {
+    async fn fetch_mods(&self, version_slug: &str) -> Result<Vec<ModEntry>, Error> {
+        mod_utils::fetch_mods_for_version(&self.http_client, &self.base_url, version_slug).await
+    }
+}
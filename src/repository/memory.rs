@@ -0,0 +1,508 @@
+use super::{
+    normalize_title, AnalyticsRepository, DigestRepository, FailedSearchRepository, FavoritesRepository, LinkHealthRepository, MapRepository, MentionChannelRepository, ModRepository,
+    MotwRepository, StatsRepository, TitleIndexEntry, WatchRepository,
+};
+use crate::types::{
+    ApiModioMap, Collection, CommandEvent, DigestSubscription, Error, FailedSearch, FavoriteItem, HallOfFameEntry, LinkStatus, ModEntry, MotwPoll, SearchTarget, StatsSnapshot, UsageTotals,
+};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::RwLock,
+};
+
+/// In-memory stand-in for [`super::RedisMapRepository`], used to exercise commands without Redis.
+#[derive(Default)]
+pub struct InMemoryMapRepository {
+    // One index per lowercased tag. Members are kept as `"{title}:{id}"` so ordering matches a Redis ZSET with equal scores.
+    titles: RwLock<HashMap<String, BTreeSet<String>>>,
+    maps: RwLock<HashMap<i32, ApiModioMap>>,
+}
+
+impl InMemoryMapRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_maps(maps: impl IntoIterator<Item = ApiModioMap>) -> Self {
+        let repo = Self::new();
+        for map in maps {
+            repo.insert(map);
+        }
+        repo
+    }
+
+    /// Stores the entry and indexes its title under each of its tags.
+    pub fn insert(&self, map: ApiModioMap) {
+        for tag in map.tags.iter().flatten() {
+            self.insert_title(&tag.name, &map.name, map.id);
+        }
+        self.maps.write().unwrap().insert(map.id, map);
+    }
+
+    /// Indexes a title without storing an entry, like a stale index member whose `mod:{id}` key expired.
+    pub fn insert_title(&self, tag: &str, title: &str, id: i32) {
+        self.titles
+            .write()
+            .unwrap()
+            .entry(tag.to_lowercase())
+            .or_default()
+            .insert(format!("{}:{}", normalize_title(title), id));
+    }
+}
+
+#[async_trait]
+impl MapRepository for InMemoryMapRepository {
+    async fn search_titles(&self, tag: &str, prefix: &str, limit: usize) -> Result<Vec<TitleIndexEntry>, Error> {
+        let prefix_normalized = normalize_title(prefix);
+        let indexes = self.titles.read().unwrap();
+        let Some(titles) = indexes.get(&tag.to_lowercase()) else { return Ok(Vec::new()) };
+        Ok(titles
+            .range(prefix_normalized.clone()..)
+            .take_while(|member| member.starts_with(&prefix_normalized))
+            .filter_map(|member| TitleIndexEntry::parse(member))
+            .take(limit)
+            .collect())
+    }
+
+    async fn all_ids(&self, tag: &str) -> Result<Vec<i32>, Error> {
+        let indexes = self.titles.read().unwrap();
+        Ok(indexes
+            .get(&tag.to_lowercase())
+            .into_iter()
+            .flatten()
+            .filter_map(|member| TitleIndexEntry::parse(member))
+            .map(|entry| entry.id)
+            .collect())
+    }
+
+    async fn get_by_id(&self, id: i32) -> Result<Option<ApiModioMap>, Error> {
+        Ok(self.maps.read().unwrap().get(&id).cloned())
+    }
+
+    async fn get_many(&self, ids: &[i32]) -> Result<Vec<Option<ApiModioMap>>, Error> {
+        let maps = self.maps.read().unwrap();
+        Ok(ids.iter().map(|id| maps.get(id).cloned()).collect())
+    }
+}
+
+/// In-memory stand-in for [`super::HttpModRepository`]. Unknown slugs fail like an upstream error would.
+#[derive(Default)]
+pub struct InMemoryModRepository {
+    mods: RwLock<HashMap<String, Vec<ModEntry>>>,
+}
+
+impl InMemoryModRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_version(self, version_slug: &str, mods: Vec<ModEntry>) -> Self {
+        self.mods.write().unwrap().insert(version_slug.to_string(), mods);
+        self
+    }
+}
+
+#[async_trait]
+impl ModRepository for InMemoryModRepository {
+    async fn fetch_mods(&self, version_slug: &str) -> Result<Vec<ModEntry>, Error> {
+        self.mods
+            .read()
+            .unwrap()
+            .get(version_slug)
+            .cloned()
+            .ok_or_else(|| anyhow!("No mods available for version {}", version_slug))
+    }
+}
+
+/// In-memory stand-in for [`super::RedisFavoritesRepository`].
+#[derive(Default)]
+pub struct InMemoryFavoritesRepository {
+    favorites: RwLock<HashMap<u64, BTreeMap<String, FavoriteItem>>>,
+    collections: RwLock<HashMap<String, Collection>>,
+}
+
+impl InMemoryFavoritesRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl FavoritesRepository for InMemoryFavoritesRepository {
+    async fn toggle(&self, user_id: u64, item: &FavoriteItem) -> Result<bool, Error> {
+        let mut favorites = self.favorites.write().unwrap();
+        let user_favorites = favorites.entry(user_id).or_default();
+        if user_favorites.remove(&item.key()).is_some() {
+            return Ok(false);
+        }
+        user_favorites.insert(item.key(), item.clone());
+        Ok(true)
+    }
+
+    async fn add_many(&self, user_id: u64, items: &[FavoriteItem]) -> Result<usize, Error> {
+        let mut favorites = self.favorites.write().unwrap();
+        let user_favorites = favorites.entry(user_id).or_default();
+        Ok(items.iter().filter(|item| user_favorites.insert(item.key(), (*item).clone()).is_none()).count())
+    }
+
+    async fn remove(&self, user_id: u64, item: &FavoriteItem) -> Result<bool, Error> {
+        let mut favorites = self.favorites.write().unwrap();
+        Ok(favorites.get_mut(&user_id).and_then(|f| f.remove(&item.key())).is_some())
+    }
+
+    async fn list(&self, user_id: u64) -> Result<Vec<FavoriteItem>, Error> {
+        let favorites = self.favorites.read().unwrap();
+        Ok(favorites.get(&user_id).map(|f| f.values().cloned().collect()).unwrap_or_default())
+    }
+
+    async fn create_collection(&self, collection: &Collection) -> Result<bool, Error> {
+        let mut collections = self.collections.write().unwrap();
+        if collections.contains_key(&collection.code) {
+            return Ok(false);
+        }
+        collections.insert(collection.code.clone(), collection.clone());
+        Ok(true)
+    }
+
+    async fn get_collection(&self, code: &str) -> Result<Option<Collection>, Error> {
+        Ok(self.collections.read().unwrap().get(code).cloned())
+    }
+
+    async fn list_collections(&self, user_id: u64) -> Result<Vec<Collection>, Error> {
+        let mut owned: Vec<Collection> = self
+            .collections
+            .read()
+            .unwrap()
+            .values()
+            .filter(|c| c.owner_id == user_id)
+            .cloned()
+            .collect();
+        owned.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()).then_with(|| a.code.cmp(&b.code)));
+        Ok(owned)
+    }
+}
+
+/// In-memory stand-in for [`super::RedisWatchRepository`].
+#[derive(Default)]
+pub struct InMemoryWatchRepository {
+    watches: RwLock<BTreeMap<String, (FavoriteItem, BTreeSet<u64>)>>,
+    last_seen: RwLock<HashMap<String, String>>,
+}
+
+impl InMemoryWatchRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl WatchRepository for InMemoryWatchRepository {
+    async fn toggle(&self, user_id: u64, item: &FavoriteItem) -> Result<bool, Error> {
+        if self.remove(user_id, item).await? {
+            return Ok(false);
+        }
+        let mut watches = self.watches.write().unwrap();
+        watches.entry(item.key()).or_insert_with(|| (item.clone(), BTreeSet::new())).1.insert(user_id);
+        Ok(true)
+    }
+
+    async fn remove(&self, user_id: u64, item: &FavoriteItem) -> Result<bool, Error> {
+        let mut watches = self.watches.write().unwrap();
+        let Some((_, watchers)) = watches.get_mut(&item.key()) else { return Ok(false) };
+        let removed = watchers.remove(&user_id);
+        if watchers.is_empty() {
+            watches.remove(&item.key());
+            self.last_seen.write().unwrap().remove(&item.key());
+        }
+        Ok(removed)
+    }
+
+    async fn list(&self, user_id: u64) -> Result<Vec<FavoriteItem>, Error> {
+        let watches = self.watches.read().unwrap();
+        Ok(watches.values().filter(|(_, w)| w.contains(&user_id)).map(|(item, _)| item.clone()).collect())
+    }
+
+    async fn watched_items(&self) -> Result<Vec<FavoriteItem>, Error> {
+        Ok(self.watches.read().unwrap().values().map(|(item, _)| item.clone()).collect())
+    }
+
+    async fn watchers(&self, item: &FavoriteItem) -> Result<Vec<u64>, Error> {
+        let watches = self.watches.read().unwrap();
+        Ok(watches.get(&item.key()).map(|(_, w)| w.iter().copied().collect()).unwrap_or_default())
+    }
+
+    async fn last_seen(&self, item: &FavoriteItem) -> Result<Option<String>, Error> {
+        Ok(self.last_seen.read().unwrap().get(&item.key()).cloned())
+    }
+
+    async fn set_last_seen(&self, item: &FavoriteItem, fingerprint: &str) -> Result<(), Error> {
+        self.last_seen.write().unwrap().insert(item.key(), fingerprint.to_string());
+        Ok(())
+    }
+}
+
+/// In-memory stand-in for [`super::RedisMotwRepository`].
+#[derive(Default)]
+pub struct InMemoryMotwRepository {
+    polls: RwLock<BTreeMap<u64, MotwPoll>>,
+    // Keyed by guild and poll message, like the Redis store.
+    votes: RwLock<HashMap<(u64, u64), HashMap<u64, i32>>>,
+    winners: RwLock<HashMap<u64, Vec<HallOfFameEntry>>>,
+}
+
+impl InMemoryMotwRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl MotwRepository for InMemoryMotwRepository {
+    async fn open_poll(&self, poll: &MotwPoll) -> Result<bool, Error> {
+        let mut polls = self.polls.write().unwrap();
+        if polls.contains_key(&poll.guild_id) {
+            return Ok(false);
+        }
+        polls.insert(poll.guild_id, poll.clone());
+        Ok(true)
+    }
+
+    async fn get_poll(&self, guild_id: u64) -> Result<Option<MotwPoll>, Error> {
+        Ok(self.polls.read().unwrap().get(&guild_id).cloned())
+    }
+
+    async fn open_polls(&self) -> Result<Vec<MotwPoll>, Error> {
+        Ok(self.polls.read().unwrap().values().cloned().collect())
+    }
+
+    async fn vote(&self, poll: &MotwPoll, user_id: u64, map_id: i32) -> Result<(), Error> {
+        self.votes.write().unwrap().entry((poll.guild_id, poll.message_id)).or_default().insert(user_id, map_id);
+        Ok(())
+    }
+
+    async fn votes(&self, poll: &MotwPoll) -> Result<HashMap<u64, i32>, Error> {
+        Ok(self.votes.read().unwrap().get(&(poll.guild_id, poll.message_id)).cloned().unwrap_or_default())
+    }
+
+    async fn close_poll(&self, guild_id: u64) -> Result<Option<(MotwPoll, HashMap<u64, i32>)>, Error> {
+        let Some(poll) = self.polls.write().unwrap().remove(&guild_id) else { return Ok(None) };
+        let votes = self.votes.write().unwrap().remove(&(guild_id, poll.message_id)).unwrap_or_default();
+        Ok(Some((poll, votes)))
+    }
+
+    async fn add_winner(&self, guild_id: u64, entry: &HallOfFameEntry) -> Result<(), Error> {
+        self.winners.write().unwrap().entry(guild_id).or_default().push(entry.clone());
+        Ok(())
+    }
+
+    async fn winners(&self, guild_id: u64) -> Result<Vec<HallOfFameEntry>, Error> {
+        let mut winners = self.winners.read().unwrap().get(&guild_id).cloned().unwrap_or_default();
+        winners.sort_by_key(|w| std::cmp::Reverse(w.closed_at));
+        Ok(winners)
+    }
+}
+
+/// In-memory stand-in for [`super::RedisLinkHealthRepository`].
+#[derive(Default)]
+pub struct InMemoryLinkHealthRepository {
+    statuses: RwLock<HashMap<String, LinkStatus>>,
+}
+
+impl InMemoryLinkHealthRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl LinkHealthRepository for InMemoryLinkHealthRepository {
+    async fn record(&self, status: &LinkStatus) -> Result<(), Error> {
+        self.statuses.write().unwrap().insert(status.url.clone(), status.clone());
+        Ok(())
+    }
+
+    async fn all(&self) -> Result<HashMap<String, LinkStatus>, Error> {
+        Ok(self.statuses.read().unwrap().clone())
+    }
+
+    async fn forget(&self, urls: &[String]) -> Result<(), Error> {
+        let mut statuses = self.statuses.write().unwrap();
+        for url in urls {
+            statuses.remove(url);
+        }
+        Ok(())
+    }
+}
+
+/// In-memory stand-in for [`super::RedisStatsRepository`].
+#[derive(Default)]
+pub struct InMemoryStatsRepository {
+    // Per map, keyed by `taken_at` so a second snapshot at the same time replaces the first.
+    snapshots: RwLock<HashMap<i32, BTreeMap<i64, StatsSnapshot>>>,
+}
+
+impl InMemoryStatsRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl StatsRepository for InMemoryStatsRepository {
+    async fn record(&self, snapshots: &[StatsSnapshot]) -> Result<(), Error> {
+        let mut stored = self.snapshots.write().unwrap();
+        for snapshot in snapshots {
+            stored.entry(snapshot.map_id).or_default().insert(snapshot.taken_at, snapshot.clone());
+        }
+        Ok(())
+    }
+
+    async fn history(&self, map_id: i32, since: i64) -> Result<Vec<StatsSnapshot>, Error> {
+        let stored = self.snapshots.read().unwrap();
+        Ok(stored.get(&map_id).map(|s| s.range(since..).map(|(_, snapshot)| snapshot.clone()).collect()).unwrap_or_default())
+    }
+
+    async fn baselines(&self, map_ids: &[i32], since: i64) -> Result<HashMap<i32, StatsSnapshot>, Error> {
+        let stored = self.snapshots.read().unwrap();
+        Ok(map_ids
+            .iter()
+            .filter_map(|id| Some((*id, stored.get(id)?.range(since..).next()?.1.clone())))
+            .collect())
+    }
+
+    async fn prune(&self, map_ids: &[i32], before: i64) -> Result<(), Error> {
+        let mut stored = self.snapshots.write().unwrap();
+        for id in map_ids {
+            if let Some(snapshots) = stored.get_mut(id) {
+                *snapshots = snapshots.split_off(&before);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// In-memory stand-in for [`super::RedisDigestRepository`].
+#[derive(Default)]
+pub struct InMemoryDigestRepository {
+    subscriptions: RwLock<BTreeMap<u64, DigestSubscription>>,
+}
+
+impl InMemoryDigestRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DigestRepository for InMemoryDigestRepository {
+    async fn save(&self, subscription: &DigestSubscription) -> Result<(), Error> {
+        self.subscriptions.write().unwrap().insert(subscription.guild_id, subscription.clone());
+        Ok(())
+    }
+
+    async fn get(&self, guild_id: u64) -> Result<Option<DigestSubscription>, Error> {
+        Ok(self.subscriptions.read().unwrap().get(&guild_id).cloned())
+    }
+
+    async fn remove(&self, guild_id: u64) -> Result<bool, Error> {
+        Ok(self.subscriptions.write().unwrap().remove(&guild_id).is_some())
+    }
+
+    async fn all(&self) -> Result<Vec<DigestSubscription>, Error> {
+        Ok(self.subscriptions.read().unwrap().values().cloned().collect())
+    }
+}
+
+/// In-memory stand-in for [`super::RedisMentionChannelRepository`].
+#[derive(Default)]
+pub struct InMemoryMentionChannelRepository {
+    guild_by_channel: RwLock<BTreeMap<u64, u64>>,
+}
+
+impl InMemoryMentionChannelRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl MentionChannelRepository for InMemoryMentionChannelRepository {
+    async fn enable(&self, guild_id: u64, channel_id: u64) -> Result<bool, Error> {
+        Ok(self.guild_by_channel.write().unwrap().insert(channel_id, guild_id).is_none())
+    }
+
+    async fn disable(&self, channel_id: u64) -> Result<bool, Error> {
+        Ok(self.guild_by_channel.write().unwrap().remove(&channel_id).is_some())
+    }
+
+    async fn all(&self) -> Result<Vec<(u64, u64)>, Error> {
+        let mut channels: Vec<(u64, u64)> = self.guild_by_channel.read().unwrap().iter().map(|(channel, guild)| (*guild, *channel)).collect();
+        channels.sort();
+        Ok(channels)
+    }
+}
+
+/// In-memory stand-in for [`super::RedisAnalyticsRepository`].
+#[derive(Default)]
+pub struct InMemoryAnalyticsRepository {
+    days: RwLock<BTreeMap<i64, UsageTotals>>,
+}
+
+impl InMemoryAnalyticsRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl AnalyticsRepository for InMemoryAnalyticsRepository {
+    async fn record(&self, event: &CommandEvent) -> Result<(), Error> {
+        self.days.write().unwrap().entry(crate::analytics::day_of(event.at)).or_default().add(event);
+        Ok(())
+    }
+
+    async fn totals(&self, days: &[i64]) -> Result<UsageTotals, Error> {
+        let stored = self.days.read().unwrap();
+        let mut totals = UsageTotals::default();
+        for day in days {
+            if let Some(day_totals) = stored.get(day) {
+                totals.merge(day_totals);
+            }
+        }
+        Ok(totals)
+    }
+}
+
+/// In-memory stand-in for [`super::RedisFailedSearchRepository`].
+#[derive(Default)]
+pub struct InMemoryFailedSearchRepository {
+    counts: RwLock<HashMap<SearchTarget, HashMap<String, i64>>>,
+}
+
+impl InMemoryFailedSearchRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl FailedSearchRepository for InMemoryFailedSearchRepository {
+    async fn record(&self, target: SearchTarget, query: &str) -> Result<(), Error> {
+        *self.counts.write().unwrap().entry(target).or_default().entry(query.to_string()).or_default() += 1;
+        Ok(())
+    }
+
+    async fn top(&self, target: SearchTarget, limit: usize) -> Result<Vec<FailedSearch>, Error> {
+        let counts = self.counts.read().unwrap();
+        let mut top: Vec<FailedSearch> = counts
+            .get(&target)
+            .map(|queries| queries.iter().map(|(query, count)| FailedSearch { query: query.clone(), count: *count }).collect())
+            .unwrap_or_default();
+        // Same order as a Redis ZREVRANGE: highest score first, ties in reverse lexical order.
+        top.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| b.query.cmp(&a.query)));
+        top.truncate(limit);
+        Ok(top)
+    }
+}
@@ -0,0 +1,240 @@
+pub mod http_mods;
+pub mod memory;
+pub mod redis_analytics;
+pub mod redis_digests;
+pub mod redis_failed_searches;
+pub mod redis_favorites;
+pub mod redis_link_health;
+pub mod redis_maps;
+pub mod redis_mentions;
+pub mod redis_motw;
+pub mod redis_stats;
+pub mod redis_watches;
+
+use crate::types::{
+    ApiModioMap, Collection, CommandEvent, DigestSubscription, Error, FailedSearch, FavoriteItem, HallOfFameEntry, LinkStatus, ModEntry, MotwPoll, SearchTarget, StatsSnapshot, UsageTotals, MAP_TAG,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+pub use http_mods::HttpModRepository;
+pub use memory::{
+    InMemoryAnalyticsRepository, InMemoryDigestRepository, InMemoryFailedSearchRepository, InMemoryFavoritesRepository, InMemoryLinkHealthRepository, InMemoryMapRepository, InMemoryMentionChannelRepository,
+    InMemoryModRepository, InMemoryMotwRepository, InMemoryStatsRepository, InMemoryWatchRepository,
+};
+pub use redis_analytics::RedisAnalyticsRepository;
+pub use redis_digests::RedisDigestRepository;
+pub use redis_failed_searches::RedisFailedSearchRepository;
+pub use redis_favorites::RedisFavoritesRepository;
+pub use redis_link_health::RedisLinkHealthRepository;
+pub use redis_maps::RedisMapRepository;
+pub use redis_mentions::RedisMentionChannelRepository;
+pub use redis_motw::RedisMotwRepository;
+pub use redis_stats::RedisStatsRepository;
+pub use redis_watches::RedisWatchRepository;
+
+/// One member of a title index, stored by the Go API as `"{normalized title}:{id}"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TitleIndexEntry {
+    pub title: String,
+    pub id: i32,
+}
+
+impl TitleIndexEntry {
+    pub fn parse(member: &str) -> Option<Self> {
+        let colon_idx = member.rfind(':')?;
+        let id = member[colon_idx + 1..].parse::<i32>().ok()?;
+        Some(Self { title: member[..colon_idx].to_string(), id })
+    }
+}
+
+/// Normalizes a title the same way the Go API does before writing it to a title index.
+pub fn normalize_title(title: &str) -> String {
+    title.to_lowercase().trim().to_string()
+}
+
+/// Read access to the mod.io entries (maps, scripts...) cached by the Go API.
+#[async_trait]
+pub trait MapRepository: Send + Sync {
+    /// Returns up to `limit` entries of `tag`'s title index starting with `prefix`, or the first `limit` entries when `prefix` is empty.
+    async fn search_titles(&self, tag: &str, prefix: &str, limit: usize) -> Result<Vec<TitleIndexEntry>, Error>;
+
+    /// Every ID in `tag`'s title index, in index order.
+    async fn all_ids(&self, tag: &str) -> Result<Vec<i32>, Error>;
+
+    /// Fetches a single mod.io entry. Missing or malformed entries are `None`.
+    async fn get_by_id(&self, id: i32) -> Result<Option<ApiModioMap>, Error>;
+
+    /// Fetches several mod.io entries at once, preserving the order of `ids`.
+    async fn get_many(&self, ids: &[i32]) -> Result<Vec<Option<ApiModioMap>>, Error>;
+}
+
+/// How many entries [`load_all_maps`] fetches per round trip.
+const LOAD_ALL_BATCH_SIZE: usize = 100;
+
+/// Every readable entry in the map title index, fetched in batches. Missing or malformed entries are skipped.
+pub async fn load_all_maps(maps: &dyn MapRepository) -> Result<Vec<ApiModioMap>, Error> {
+    let ids = maps.all_ids(MAP_TAG).await?;
+    load_maps(maps, &ids).await
+}
+
+/// The entries for `ids`, fetched in batches. Missing or malformed entries are skipped.
+pub async fn load_maps(maps: &dyn MapRepository, ids: &[i32]) -> Result<Vec<ApiModioMap>, Error> {
+    let mut loaded = Vec::with_capacity(ids.len());
+    for batch in ids.chunks(LOAD_ALL_BATCH_SIZE) {
+        loaded.extend(maps.get_many(batch).await?.into_iter().flatten());
+    }
+    Ok(loaded)
+}
+
+/// Source of the community-maintained script mod list, keyed by version slug.
+#[async_trait]
+pub trait ModRepository: Send + Sync {
+    async fn fetch_mods(&self, version_slug: &str) -> Result<Vec<ModEntry>, Error>;
+}
+
+/// Per-user starred maps and mods, plus shareable named collections of them.
+#[async_trait]
+pub trait FavoritesRepository: Send + Sync {
+    /// Adds the item if absent, removes it otherwise. Returns whether it is now a favorite.
+    async fn toggle(&self, user_id: u64, item: &FavoriteItem) -> Result<bool, Error>;
+
+    /// Adds several items at once. Returns how many were new.
+    async fn add_many(&self, user_id: u64, items: &[FavoriteItem]) -> Result<usize, Error>;
+
+    /// Returns whether the item was a favorite.
+    async fn remove(&self, user_id: u64, item: &FavoriteItem) -> Result<bool, Error>;
+
+    /// All favorites of a user, sorted by key.
+    async fn list(&self, user_id: u64) -> Result<Vec<FavoriteItem>, Error>;
+
+    /// Stores a collection unless its code is already taken. Returns `false` on a code collision.
+    async fn create_collection(&self, collection: &Collection) -> Result<bool, Error>;
+
+    async fn get_collection(&self, code: &str) -> Result<Option<Collection>, Error>;
+
+    /// Collections shared by a user, sorted by name.
+    async fn list_collections(&self, user_id: u64) -> Result<Vec<Collection>, Error>;
+}
+
+/// Per-user watch lists, plus the last seen state of every watched item so updates can be detected.
+#[async_trait]
+pub trait WatchRepository: Send + Sync {
+    /// Starts watching if not yet watched, stops otherwise. Returns whether the item is now watched.
+    async fn toggle(&self, user_id: u64, item: &FavoriteItem) -> Result<bool, Error>;
+
+    /// Returns whether the user was watching the item.
+    async fn remove(&self, user_id: u64, item: &FavoriteItem) -> Result<bool, Error>;
+
+    /// Everything a user watches, sorted by key.
+    async fn list(&self, user_id: u64) -> Result<Vec<FavoriteItem>, Error>;
+
+    /// Every item with at least one watcher, sorted by key.
+    async fn watched_items(&self) -> Result<Vec<FavoriteItem>, Error>;
+
+    async fn watchers(&self, item: &FavoriteItem) -> Result<Vec<u64>, Error>;
+
+    /// Fingerprint of the item's state when it was last checked.
+    async fn last_seen(&self, item: &FavoriteItem) -> Result<Option<String>, Error>;
+
+    async fn set_last_seen(&self, item: &FavoriteItem, fingerprint: &str) -> Result<(), Error>;
+}
+
+/// Map of the week polls (at most one open per guild), their votes, and each guild's hall of fame.
+#[async_trait]
+pub trait MotwRepository: Send + Sync {
+    /// Stores the poll unless the guild already has one open. Returns `false` in that case.
+    async fn open_poll(&self, poll: &MotwPoll) -> Result<bool, Error>;
+
+    async fn get_poll(&self, guild_id: u64) -> Result<Option<MotwPoll>, Error>;
+
+    async fn open_polls(&self) -> Result<Vec<MotwPoll>, Error>;
+
+    /// Records or replaces a member's vote in `poll`.
+    async fn vote(&self, poll: &MotwPoll, user_id: u64, map_id: i32) -> Result<(), Error>;
+
+    /// The voted map ID per voter in `poll`.
+    async fn votes(&self, poll: &MotwPoll) -> Result<HashMap<u64, i32>, Error>;
+
+    /// Removes the poll and its votes, returning both if a poll was open.
+    async fn close_poll(&self, guild_id: u64) -> Result<Option<(MotwPoll, HashMap<u64, i32>)>, Error>;
+
+    async fn add_winner(&self, guild_id: u64, entry: &HallOfFameEntry) -> Result<(), Error>;
+
+    /// Past winners, newest first.
+    async fn winners(&self, guild_id: u64) -> Result<Vec<HallOfFameEntry>, Error>;
+}
+
+/// Health check results for community mod download links, keyed by URL.
+#[async_trait]
+pub trait LinkHealthRepository: Send + Sync {
+    /// Stores or replaces the result for `status.url`.
+    async fn record(&self, status: &LinkStatus) -> Result<(), Error>;
+
+    async fn all(&self) -> Result<HashMap<String, LinkStatus>, Error>;
+
+    /// Drops results for links that are no longer listed anywhere.
+    async fn forget(&self, urls: &[String]) -> Result<(), Error>;
+}
+
+/// Periodic snapshots of map stats, kept per map in time order.
+#[async_trait]
+pub trait StatsRepository: Send + Sync {
+    async fn record(&self, snapshots: &[StatsSnapshot]) -> Result<(), Error>;
+
+    /// Snapshots of one map taken at or after `since`, oldest first.
+    async fn history(&self, map_id: i32, since: i64) -> Result<Vec<StatsSnapshot>, Error>;
+
+    /// The first snapshot taken at or after `since` for each of `map_ids`. Maps without one are left out.
+    async fn baselines(&self, map_ids: &[i32], since: i64) -> Result<HashMap<i32, StatsSnapshot>, Error>;
+
+    /// Drops snapshots of `map_ids` taken before `before`.
+    async fn prune(&self, map_ids: &[i32], before: i64) -> Result<(), Error>;
+}
+
+/// Activity digest subscriptions, at most one per guild.
+#[async_trait]
+pub trait DigestRepository: Send + Sync {
+    /// Stores or replaces the guild's subscription.
+    async fn save(&self, subscription: &DigestSubscription) -> Result<(), Error>;
+
+    async fn get(&self, guild_id: u64) -> Result<Option<DigestSubscription>, Error>;
+
+    /// Returns whether the guild was subscribed.
+    async fn remove(&self, guild_id: u64) -> Result<bool, Error>;
+
+    /// Every subscription, sorted by guild ID.
+    async fn all(&self) -> Result<Vec<DigestSubscription>, Error>;
+}
+
+/// Channels where the bot answers map and mod mentions in regular messages.
+#[async_trait]
+pub trait MentionChannelRepository: Send + Sync {
+    /// Returns whether the channel was newly enabled.
+    async fn enable(&self, guild_id: u64, channel_id: u64) -> Result<bool, Error>;
+
+    /// Returns whether the channel was enabled.
+    async fn disable(&self, channel_id: u64) -> Result<bool, Error>;
+
+    /// Every enabled channel as `(guild_id, channel_id)`, sorted.
+    async fn all(&self) -> Result<Vec<(u64, u64)>, Error>;
+}
+
+/// Command usage counters, bucketed per UTC day.
+#[async_trait]
+pub trait AnalyticsRepository: Send + Sync {
+    async fn record(&self, event: &CommandEvent) -> Result<(), Error>;
+
+    /// Counters summed over `days`, each counted in days since the Unix epoch.
+    async fn totals(&self, days: &[i64]) -> Result<UsageTotals, Error>;
+}
+
+/// All-time counts of searches that found nothing, per target.
+#[async_trait]
+pub trait FailedSearchRepository: Send + Sync {
+    /// Counts one more search for the already normalized `query`.
+    async fn record(&self, target: SearchTarget, query: &str) -> Result<(), Error>;
+
+    /// The `limit` most searched queries, most searched first.
+    async fn top(&self, target: SearchTarget, limit: usize) -> Result<Vec<FailedSearch>, Error>;
+}
@@ -0,0 +1,99 @@
+use super::AnalyticsRepository;
+use crate::{
+    analytics::{self, ANALYTICS_RETENTION_DAYS},
+    config::RedisConfig,
+    types::{CommandEvent, Error, UsageTotals},
+};
+use async_trait::async_trait;
+use deadpool_redis::{redis, Pool};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// Counter HASHes kept per day, e.g. `bot:analytics:19675:commands`.
+const COUNTERS: [&str; 7] = ["commands", "failures", "latency_ms", "guilds", "maps", "mods", "failed_searches"];
+
+/// Keeps one HASH of counters per kind and UTC day, expiring once no window reaches back that far.
+pub struct RedisAnalyticsRepository {
+    pool: Pool,
+    keys: RedisConfig,
+}
+
+impl RedisAnalyticsRepository {
+    pub fn new(pool: Pool, keys: RedisConfig) -> Self {
+        Self { pool, keys }
+    }
+
+    fn counter_key(&self, day: i64, counter: &str) -> String {
+        self.keys.bot_key(&format!("analytics:{}:{}", day, counter))
+    }
+}
+
+fn parse_map_counts(raw: &HashMap<String, i64>) -> HashMap<i32, i64> {
+    raw.iter()
+        .filter_map(|(field, count)| match field.parse() {
+            Ok(id) => Some((id, *count)),
+            Err(_) => {
+                warn!(field = %field, "Skipping unreadable map usage counter");
+                None
+            }
+        })
+        .collect()
+}
+
+#[async_trait]
+impl AnalyticsRepository for RedisAnalyticsRepository {
+    async fn record(&self, event: &CommandEvent) -> Result<(), Error> {
+        let day = analytics::day_of(event.at);
+        let mut increments: Vec<(&str, String, i64)> = vec![
+            ("commands", event.command.clone(), 1),
+            ("latency_ms", event.command.clone(), event.latency_ms as i64),
+            ("guilds", event.guild_key(), 1),
+        ];
+        if !event.success {
+            increments.push(("failures", event.command.clone(), 1));
+        }
+        increments.extend(event.map_ids.iter().map(|id| ("maps", id.to_string(), 1)));
+        increments.extend(event.mod_titles.iter().map(|title| ("mods", title.clone(), 1)));
+        if let Some(query) = &event.failed_query {
+            increments.push(("failed_searches", query.clone(), 1));
+        }
+
+        let mut redis_conn = self.pool.get().await?;
+        let mut pipe = redis::pipe();
+        for (counter, field, by) in &increments {
+            let key = self.counter_key(day, counter);
+            pipe.hincr(&key, field, *by).ignore();
+            pipe.expire(&key, ANALYTICS_RETENTION_DAYS * 86_400).ignore();
+        }
+        let () = pipe.query_async(&mut redis_conn).await?;
+        Ok(())
+    }
+
+    async fn totals(&self, days: &[i64]) -> Result<UsageTotals, Error> {
+        let mut totals = UsageTotals::default();
+        if days.is_empty() {
+            return Ok(totals);
+        }
+        let mut redis_conn = self.pool.get().await?;
+        let mut pipe = redis::pipe();
+        for day in days {
+            for counter in COUNTERS {
+                pipe.hgetall(self.counter_key(*day, counter));
+            }
+        }
+        let hashes: Vec<HashMap<String, i64>> = pipe.query_async(&mut redis_conn).await?;
+        for day_hashes in hashes.chunks(COUNTERS.len()) {
+            let [commands, failures, latency_ms, guilds, maps, mods, failed_searches] = day_hashes else { continue };
+            totals.merge(&UsageTotals {
+                commands: commands.clone(),
+                failures: failures.clone(),
+                latency_ms: latency_ms.clone(),
+                guilds: guilds.clone(),
+                maps: parse_map_counts(maps),
+                mods: mods.clone(),
+                failed_searches: failed_searches.clone(),
+            });
+        }
+        Ok(totals)
+    }
+}
@@ -0,0 +1,65 @@
+use super::DigestRepository;
+use crate::{
+    config::RedisConfig,
+    types::{DigestSubscription, Error},
+};
+use async_trait::async_trait;
+use deadpool_redis::{redis::AsyncCommands, Pool};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// Keeps digest subscriptions as JSON values in one Redis HASH keyed by guild ID.
+pub struct RedisDigestRepository {
+    pool: Pool,
+    keys: RedisConfig,
+}
+
+impl RedisDigestRepository {
+    pub fn new(pool: Pool, keys: RedisConfig) -> Self {
+        Self { pool, keys }
+    }
+
+    fn digests_key(&self) -> String {
+        self.keys.bot_key("digests")
+    }
+}
+
+fn parse_subscription(guild_id: &str, json: &str) -> Option<DigestSubscription> {
+    match serde_json::from_str::<DigestSubscription>(json) {
+        Ok(subscription) => Some(subscription),
+        Err(e) => {
+            warn!(guild_id = %guild_id, "Skipping unreadable digest subscription: {}", e);
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl DigestRepository for RedisDigestRepository {
+    async fn save(&self, subscription: &DigestSubscription) -> Result<(), Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let _: i64 = redis_conn.hset(self.digests_key(), subscription.guild_id, serde_json::to_string(subscription)?).await?;
+        Ok(())
+    }
+
+    async fn get(&self, guild_id: u64) -> Result<Option<DigestSubscription>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let json: Option<String> = redis_conn.hget(self.digests_key(), guild_id).await?;
+        Ok(json.and_then(|json| parse_subscription(&guild_id.to_string(), &json)))
+    }
+
+    async fn remove(&self, guild_id: u64) -> Result<bool, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let removed: i64 = redis_conn.hdel(self.digests_key(), guild_id).await?;
+        Ok(removed > 0)
+    }
+
+    async fn all(&self) -> Result<Vec<DigestSubscription>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let raw: HashMap<String, String> = redis_conn.hgetall(self.digests_key()).await?;
+        let mut subscriptions: Vec<DigestSubscription> =
+            raw.iter().filter_map(|(guild_id, json)| parse_subscription(guild_id, json)).collect();
+        subscriptions.sort_by_key(|s| s.guild_id);
+        Ok(subscriptions)
+    }
+}
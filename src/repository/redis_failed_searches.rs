@@ -0,0 +1,54 @@
+use super::FailedSearchRepository;
+use crate::{
+    config::RedisConfig,
+    types::{Error, FailedSearch, SearchTarget},
+};
+use async_trait::async_trait;
+use deadpool_redis::{
+    redis::{self, AsyncCommands},
+    Pool,
+};
+
+/// Only this many of the most searched queries are kept per target, so a flood of one-off typos can't grow the set forever.
+const MAX_TRACKED_QUERIES: isize = 1_000;
+
+/// Keeps failed queries as members of one ZSET per target, scored by how often they were searched.
+pub struct RedisFailedSearchRepository {
+    pool: Pool,
+    keys: RedisConfig,
+}
+
+impl RedisFailedSearchRepository {
+    pub fn new(pool: Pool, keys: RedisConfig) -> Self {
+        Self { pool, keys }
+    }
+
+    fn queries_key(&self, target: SearchTarget) -> String {
+        self.keys.bot_key(&format!("failed_searches:{}", target.key()))
+    }
+}
+
+#[async_trait]
+impl FailedSearchRepository for RedisFailedSearchRepository {
+    async fn record(&self, target: SearchTarget, query: &str) -> Result<(), Error> {
+        let key = self.queries_key(target);
+        let mut redis_conn = self.pool.get().await?;
+        let () = redis::pipe()
+            .zincr(&key, query, 1)
+            .ignore()
+            .zremrangebyrank(&key, 0, -(MAX_TRACKED_QUERIES + 1))
+            .ignore()
+            .query_async(&mut redis_conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn top(&self, target: SearchTarget, limit: usize) -> Result<Vec<FailedSearch>, Error> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let mut redis_conn = self.pool.get().await?;
+        let scored: Vec<(String, f64)> = redis_conn.zrevrange_withscores(self.queries_key(target), 0, limit as isize - 1).await?;
+        Ok(scored.into_iter().map(|(query, count)| FailedSearch { query, count: count as i64 }).collect())
+    }
+}
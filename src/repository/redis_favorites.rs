@@ -0,0 +1,118 @@
+use super::FavoritesRepository;
+use crate::{
+    config::RedisConfig,
+    types::{Collection, Error, FavoriteItem},
+};
+use async_trait::async_trait;
+use deadpool_redis::{
+    redis::{AsyncCommands, ExistenceCheck, SetOptions},
+    Pool,
+};
+use tracing::error;
+
+/// Stores favorites as one Redis SET per user and collections as JSON blobs keyed by code.
+pub struct RedisFavoritesRepository {
+    pool: Pool,
+    keys: RedisConfig,
+}
+
+impl RedisFavoritesRepository {
+    pub fn new(pool: Pool, keys: RedisConfig) -> Self {
+        Self { pool, keys }
+    }
+
+    fn favorites_key(&self, user_id: u64) -> String {
+        self.keys.bot_key(&format!("favorites:{}", user_id))
+    }
+
+    fn collection_key(&self, code: &str) -> String {
+        self.keys.bot_key(&format!("collection:{}", code))
+    }
+
+    fn user_collections_key(&self, user_id: u64) -> String {
+        self.keys.bot_key(&format!("collections:{}", user_id))
+    }
+}
+
+fn parse_collection(code: &str, json: &str) -> Option<Collection> {
+    match serde_json::from_str::<Collection>(json) {
+        Ok(collection) => Some(collection),
+        Err(e) => {
+            error!("Failed to deserialize collection '{}' from Redis: {}", code, e);
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl FavoritesRepository for RedisFavoritesRepository {
+    async fn toggle(&self, user_id: u64, item: &FavoriteItem) -> Result<bool, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let key = self.favorites_key(user_id);
+        let removed: i64 = redis_conn.srem(&key, item.key()).await?;
+        if removed > 0 {
+            return Ok(false);
+        }
+        let _: i64 = redis_conn.sadd(&key, item.key()).await?;
+        Ok(true)
+    }
+
+    async fn add_many(&self, user_id: u64, items: &[FavoriteItem]) -> Result<usize, Error> {
+        if items.is_empty() {
+            return Ok(0);
+        }
+        let mut redis_conn = self.pool.get().await?;
+        let members: Vec<String> = items.iter().map(FavoriteItem::key).collect();
+        let added: i64 = redis_conn.sadd(self.favorites_key(user_id), members).await?;
+        Ok(added as usize)
+    }
+
+    async fn remove(&self, user_id: u64, item: &FavoriteItem) -> Result<bool, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let removed: i64 = redis_conn.srem(self.favorites_key(user_id), item.key()).await?;
+        Ok(removed > 0)
+    }
+
+    async fn list(&self, user_id: u64) -> Result<Vec<FavoriteItem>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let mut members: Vec<String> = redis_conn.smembers(self.favorites_key(user_id)).await?;
+        members.sort();
+        Ok(members.iter().filter_map(|m| FavoriteItem::from_key(m)).collect())
+    }
+
+    async fn create_collection(&self, collection: &Collection) -> Result<bool, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let json = serde_json::to_string(collection)?;
+        let options = SetOptions::default().conditional_set(ExistenceCheck::NX);
+        let created: Option<String> = redis_conn.set_options(self.collection_key(&collection.code), json, options).await?;
+        if created.is_none() {
+            return Ok(false);
+        }
+        let _: i64 = redis_conn.sadd(self.user_collections_key(collection.owner_id), &collection.code).await?;
+        Ok(true)
+    }
+
+    async fn get_collection(&self, code: &str) -> Result<Option<Collection>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let json: Option<String> = redis_conn.get(self.collection_key(code)).await?;
+        Ok(json.and_then(|json| parse_collection(code, &json)))
+    }
+
+    async fn list_collections(&self, user_id: u64) -> Result<Vec<Collection>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let codes: Vec<String> = redis_conn.smembers(self.user_collections_key(user_id)).await?;
+        if codes.is_empty() {
+            return Ok(Vec::new());
+        }
+        let keys: Vec<String> = codes.iter().map(|code| self.collection_key(code)).collect();
+        let jsons: Vec<Option<String>> = redis_conn.mget(keys).await?;
+
+        let mut collections: Vec<Collection> = codes
+            .iter()
+            .zip(jsons)
+            .filter_map(|(code, json)| json.and_then(|json| parse_collection(code, &json)))
+            .collect();
+        collections.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()).then_with(|| a.code.cmp(&b.code)));
+        Ok(collections)
+    }
+}
@@ -0,0 +1,58 @@
+use super::LinkHealthRepository;
+use crate::{
+    config::RedisConfig,
+    types::{Error, LinkStatus},
+};
+use async_trait::async_trait;
+use deadpool_redis::{redis::AsyncCommands, Pool};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// Keeps link check results as JSON values in one Redis HASH keyed by URL.
+pub struct RedisLinkHealthRepository {
+    pool: Pool,
+    keys: RedisConfig,
+}
+
+impl RedisLinkHealthRepository {
+    pub fn new(pool: Pool, keys: RedisConfig) -> Self {
+        Self { pool, keys }
+    }
+
+    fn health_key(&self) -> String {
+        self.keys.bot_key("link_health")
+    }
+}
+
+#[async_trait]
+impl LinkHealthRepository for RedisLinkHealthRepository {
+    async fn record(&self, status: &LinkStatus) -> Result<(), Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let _: i64 = redis_conn.hset(self.health_key(), &status.url, serde_json::to_string(status)?).await?;
+        Ok(())
+    }
+
+    async fn all(&self) -> Result<HashMap<String, LinkStatus>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let raw: HashMap<String, String> = redis_conn.hgetall(self.health_key()).await?;
+        Ok(raw
+            .into_iter()
+            .filter_map(|(url, json)| match serde_json::from_str::<LinkStatus>(&json) {
+                Ok(status) => Some((url, status)),
+                Err(e) => {
+                    warn!(url = %url, "Skipping unreadable link health entry: {}", e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    async fn forget(&self, urls: &[String]) -> Result<(), Error> {
+        if urls.is_empty() {
+            return Ok(());
+        }
+        let mut redis_conn = self.pool.get().await?;
+        let _: i64 = redis_conn.hdel(self.health_key(), urls).await?;
+        Ok(())
+    }
+}
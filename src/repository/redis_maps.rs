@@ -0,0 +1,77 @@
+use super::{normalize_title, MapRepository, TitleIndexEntry};
+use crate::{
+    config::RedisConfig,
+    types::{ApiModioMap, Error},
+};
+use async_trait::async_trait;
+use deadpool_redis::{redis::AsyncCommands, Pool};
+use tracing::{error, info};
+
+fn parse_mod_json(id: i32, json: &str) -> Option<ApiModioMap> {
+    match serde_json::from_str::<ApiModioMap>(json) {
+        Ok(map) => Some(map),
+        Err(e) => {
+            error!("Failed to deserialize mod JSON from Redis for ID {}: {}", id, e);
+            None
+        }
+    }
+}
+
+/// Reads maps from the Redis instance populated by the Go API.
+pub struct RedisMapRepository {
+    pool: Pool,
+    keys: RedisConfig,
+}
+
+impl RedisMapRepository {
+    pub fn new(pool: Pool, keys: RedisConfig) -> Self {
+        Self { pool, keys }
+    }
+}
+
+#[async_trait]
+impl MapRepository for RedisMapRepository {
+    async fn search_titles(&self, tag: &str, prefix: &str, limit: usize) -> Result<Vec<TitleIndexEntry>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let titles_key = self.keys.title_index_key(tag);
+
+        let members: Vec<String> = if prefix.is_empty() {
+            info!("Partial is empty, fetching default title suggestions.");
+            redis_conn.zrange(&titles_key, 0, limit as isize - 1).await?
+        } else {
+            let prefix_normalized = normalize_title(prefix);
+            let min_lex = format!("[{}", prefix_normalized);
+            let max_lex = format!("[{}{}", prefix_normalized, std::char::from_u32(0xFF).unwrap_or('~'));
+            redis_conn.zrangebylex_limit(&titles_key, min_lex, max_lex, 0, limit as isize).await?
+        };
+
+        Ok(members.iter().filter_map(|m| TitleIndexEntry::parse(m)).collect())
+    }
+
+    async fn all_ids(&self, tag: &str) -> Result<Vec<i32>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let members: Vec<String> = redis_conn.zrange(self.keys.title_index_key(tag), 0, -1).await?;
+        Ok(members.iter().filter_map(|m| TitleIndexEntry::parse(m)).map(|entry| entry.id).collect())
+    }
+
+    async fn get_by_id(&self, id: i32) -> Result<Option<ApiModioMap>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let mod_json: Option<String> = redis_conn.get(self.keys.mod_key(id)).await?;
+        Ok(mod_json.and_then(|json| parse_mod_json(id, &json)))
+    }
+
+    async fn get_many(&self, ids: &[i32]) -> Result<Vec<Option<ApiModioMap>>, Error> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut redis_conn = self.pool.get().await?;
+        let keys: Vec<String> = ids.iter().map(|id| self.keys.mod_key(*id)).collect();
+        let mod_jsons: Vec<Option<String>> = redis_conn.mget(keys).await?;
+
+        Ok(ids
+            .iter()
+            .zip(mod_jsons)
+            .map(|(id, json)| json.and_then(|json| parse_mod_json(*id, &json)))
+            .collect())
+    }
+}
@@ -0,0 +1,54 @@
+use super::MentionChannelRepository;
+use crate::{config::RedisConfig, types::Error};
+use async_trait::async_trait;
+use deadpool_redis::{redis::AsyncCommands, Pool};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// Keeps mention channels in one Redis HASH mapping channel ID to guild ID.
+pub struct RedisMentionChannelRepository {
+    pool: Pool,
+    keys: RedisConfig,
+}
+
+impl RedisMentionChannelRepository {
+    pub fn new(pool: Pool, keys: RedisConfig) -> Self {
+        Self { pool, keys }
+    }
+
+    fn channels_key(&self) -> String {
+        self.keys.bot_key("mention_channels")
+    }
+}
+
+#[async_trait]
+impl MentionChannelRepository for RedisMentionChannelRepository {
+    async fn enable(&self, guild_id: u64, channel_id: u64) -> Result<bool, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let added: i64 = redis_conn.hset(self.channels_key(), channel_id, guild_id).await?;
+        Ok(added > 0)
+    }
+
+    async fn disable(&self, channel_id: u64) -> Result<bool, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let removed: i64 = redis_conn.hdel(self.channels_key(), channel_id).await?;
+        Ok(removed > 0)
+    }
+
+    async fn all(&self) -> Result<Vec<(u64, u64)>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let raw: HashMap<String, String> = redis_conn.hgetall(self.channels_key()).await?;
+        let mut channels: Vec<(u64, u64)> = raw
+            .iter()
+            .filter_map(|(channel, guild)| match (guild.parse(), channel.parse()) {
+                (Ok(guild), Ok(channel)) => Some((guild, channel)),
+                _ => {
+                    warn!(channel = %channel, guild = %guild, "Skipping unreadable mention channel");
+                    None
+                }
+            })
+            .collect();
+        channels.sort();
+        Ok(channels)
+    }
+}
@@ -0,0 +1,132 @@
+use super::MotwRepository;
+use crate::{
+    config::RedisConfig,
+    types::{Error, HallOfFameEntry, MotwPoll},
+};
+use async_trait::async_trait;
+use deadpool_redis::{
+    redis::{self, AsyncCommands, ExistenceCheck, SetOptions},
+    Pool,
+};
+use std::collections::HashMap;
+use tracing::error;
+
+/// Votes that arrive after their poll closed are left behind under the old poll's key; this cleans them up.
+const VOTES_TTL_SECS: i64 = 30 * 86_400;
+
+/// Stores each guild's open poll as JSON, votes as a HASH of voter to map ID per poll,
+/// and winners in a ZSET scored by the time the poll closed.
+pub struct RedisMotwRepository {
+    pool: Pool,
+    keys: RedisConfig,
+}
+
+impl RedisMotwRepository {
+    pub fn new(pool: Pool, keys: RedisConfig) -> Self {
+        Self { pool, keys }
+    }
+
+    fn poll_key(&self, guild_id: u64) -> String {
+        self.keys.bot_key(&format!("motw:poll:{}", guild_id))
+    }
+
+    fn open_polls_key(&self) -> String {
+        self.keys.bot_key("motw:open")
+    }
+
+    /// Keyed by the poll message too, so a vote that lands while the poll closes can't carry over into the next one.
+    fn votes_key(&self, poll: &MotwPoll) -> String {
+        self.keys.bot_key(&format!("motw:votes:{}:{}", poll.guild_id, poll.message_id))
+    }
+
+    fn winners_key(&self, guild_id: u64) -> String {
+        self.keys.bot_key(&format!("motw:winners:{}", guild_id))
+    }
+}
+
+fn parse_json<T: serde::de::DeserializeOwned>(what: &str, json: &str) -> Option<T> {
+    match serde_json::from_str::<T>(json) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            error!("Failed to deserialize {} from Redis: {}", what, e);
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl MotwRepository for RedisMotwRepository {
+    async fn open_poll(&self, poll: &MotwPoll) -> Result<bool, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let json = serde_json::to_string(poll)?;
+        let options = SetOptions::default().conditional_set(ExistenceCheck::NX);
+        let created: Option<String> = redis_conn.set_options(self.poll_key(poll.guild_id), json, options).await?;
+        if created.is_none() {
+            return Ok(false);
+        }
+        let _: i64 = redis_conn.sadd(self.open_polls_key(), poll.guild_id).await?;
+        Ok(true)
+    }
+
+    async fn get_poll(&self, guild_id: u64) -> Result<Option<MotwPoll>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let json: Option<String> = redis_conn.get(self.poll_key(guild_id)).await?;
+        Ok(json.and_then(|json| parse_json(&format!("poll for guild {}", guild_id), &json)))
+    }
+
+    async fn open_polls(&self) -> Result<Vec<MotwPoll>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let mut guild_ids: Vec<u64> = redis_conn.smembers(self.open_polls_key()).await?;
+        guild_ids.sort_unstable();
+        let mut polls = Vec::with_capacity(guild_ids.len());
+        for guild_id in guild_ids {
+            if let Some(poll) = self.get_poll(guild_id).await? {
+                polls.push(poll);
+            }
+        }
+        Ok(polls)
+    }
+
+    async fn vote(&self, poll: &MotwPoll, user_id: u64, map_id: i32) -> Result<(), Error> {
+        let key = self.votes_key(poll);
+        let mut redis_conn = self.pool.get().await?;
+        let () = redis::pipe().hset(&key, user_id, map_id).ignore().expire(&key, VOTES_TTL_SECS).ignore().query_async(&mut redis_conn).await?;
+        Ok(())
+    }
+
+    async fn votes(&self, poll: &MotwPoll) -> Result<HashMap<u64, i32>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        Ok(redis_conn.hgetall(self.votes_key(poll)).await?)
+    }
+
+    async fn close_poll(&self, guild_id: u64) -> Result<Option<(MotwPoll, HashMap<u64, i32>)>, Error> {
+        // One transaction, so when the scheduled close and `/motw close` race only one of them gets the poll.
+        let mut redis_conn = self.pool.get().await?;
+        let (json,): (Option<String>,) = redis::pipe()
+            .atomic()
+            .get(self.poll_key(guild_id))
+            .del(self.poll_key(guild_id))
+            .ignore()
+            .srem(self.open_polls_key(), guild_id)
+            .ignore()
+            .query_async(&mut redis_conn)
+            .await?;
+        let Some(poll) = json.and_then(|json| parse_json::<MotwPoll>(&format!("poll for guild {}", guild_id), &json)) else { return Ok(None) };
+        let (votes,): (HashMap<u64, i32>,) =
+            redis::pipe().atomic().hgetall(self.votes_key(&poll)).del(self.votes_key(&poll)).ignore().query_async(&mut redis_conn).await?;
+        Ok(Some((poll, votes)))
+    }
+
+    async fn add_winner(&self, guild_id: u64, entry: &HallOfFameEntry) -> Result<(), Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let json = serde_json::to_string(entry)?;
+        let _: i64 = redis_conn.zadd(self.winners_key(guild_id), json, entry.closed_at).await?;
+        Ok(())
+    }
+
+    async fn winners(&self, guild_id: u64) -> Result<Vec<HallOfFameEntry>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let members: Vec<String> = redis_conn.zrange(self.winners_key(guild_id), 0, -1).await?;
+        Ok(members.iter().rev().filter_map(|json| parse_json("hall of fame entry", json)).collect())
+    }
+}
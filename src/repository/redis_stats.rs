@@ -0,0 +1,94 @@
+use super::StatsRepository;
+use crate::{
+    config::RedisConfig,
+    stats::STATS_RETENTION_SECS,
+    types::{Error, StatsSnapshot},
+};
+use async_trait::async_trait;
+use deadpool_redis::{
+    redis::{self, AsyncCommands},
+    Pool,
+};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// Keeps each map's snapshots as JSON members of a ZSET scored by the time they were taken.
+/// Every write pushes the key's expiry out by the retention window, so the history of a map that left the store expires on its own.
+pub struct RedisStatsRepository {
+    pool: Pool,
+    keys: RedisConfig,
+}
+
+impl RedisStatsRepository {
+    pub fn new(pool: Pool, keys: RedisConfig) -> Self {
+        Self { pool, keys }
+    }
+
+    fn stats_key(&self, map_id: i32) -> String {
+        self.keys.bot_key(&format!("stats:{}", map_id))
+    }
+}
+
+fn parse_snapshot(map_id: i32, json: &str) -> Option<StatsSnapshot> {
+    match serde_json::from_str::<StatsSnapshot>(json) {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            warn!(map_id, "Skipping unreadable stats snapshot: {}", e);
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl StatsRepository for RedisStatsRepository {
+    async fn record(&self, snapshots: &[StatsSnapshot]) -> Result<(), Error> {
+        if snapshots.is_empty() {
+            return Ok(());
+        }
+        let mut redis_conn = self.pool.get().await?;
+        let mut pipe = redis::pipe();
+        for snapshot in snapshots {
+            let key = self.stats_key(snapshot.map_id);
+            pipe.zadd(&key, serde_json::to_string(snapshot)?, snapshot.taken_at).ignore();
+            pipe.expire(&key, STATS_RETENTION_SECS).ignore();
+        }
+        let () = pipe.query_async(&mut redis_conn).await?;
+        Ok(())
+    }
+
+    async fn history(&self, map_id: i32, since: i64) -> Result<Vec<StatsSnapshot>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let raw: Vec<String> = redis_conn.zrangebyscore(self.stats_key(map_id), since, "+inf").await?;
+        Ok(raw.iter().filter_map(|json| parse_snapshot(map_id, json)).collect())
+    }
+
+    async fn baselines(&self, map_ids: &[i32], since: i64) -> Result<HashMap<i32, StatsSnapshot>, Error> {
+        if map_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let mut redis_conn = self.pool.get().await?;
+        let mut pipe = redis::pipe();
+        for id in map_ids {
+            pipe.zrangebyscore_limit(self.stats_key(*id), since, "+inf", 0, 1);
+        }
+        let firsts: Vec<Vec<String>> = pipe.query_async(&mut redis_conn).await?;
+        Ok(map_ids
+            .iter()
+            .zip(firsts)
+            .filter_map(|(id, first)| Some((*id, parse_snapshot(*id, first.first()?)?)))
+            .collect())
+    }
+
+    async fn prune(&self, map_ids: &[i32], before: i64) -> Result<(), Error> {
+        if map_ids.is_empty() {
+            return Ok(());
+        }
+        let mut redis_conn = self.pool.get().await?;
+        let mut pipe = redis::pipe();
+        for id in map_ids {
+            pipe.zrembyscore(self.stats_key(*id), "-inf", format!("({}", before)).ignore();
+        }
+        let () = pipe.query_async(&mut redis_conn).await?;
+        Ok(())
+    }
+}
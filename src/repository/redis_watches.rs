@@ -0,0 +1,97 @@
+use super::WatchRepository;
+use crate::{
+    config::RedisConfig,
+    types::{Error, FavoriteItem},
+};
+use async_trait::async_trait;
+use deadpool_redis::{redis::AsyncCommands, Pool};
+
+/// Keeps each user's watch list and a reverse index of watchers per item as Redis SETs.
+pub struct RedisWatchRepository {
+    pool: Pool,
+    keys: RedisConfig,
+}
+
+impl RedisWatchRepository {
+    pub fn new(pool: Pool, keys: RedisConfig) -> Self {
+        Self { pool, keys }
+    }
+
+    fn user_key(&self, user_id: u64) -> String {
+        self.keys.bot_key(&format!("watch:{}", user_id))
+    }
+
+    fn watchers_key(&self, item: &FavoriteItem) -> String {
+        self.keys.bot_key(&format!("watchers:{}", item.key()))
+    }
+
+    fn watched_key(&self) -> String {
+        self.keys.bot_key("watched")
+    }
+
+    fn last_seen_key(&self) -> String {
+        self.keys.bot_key("watch_seen")
+    }
+}
+
+fn parse_items(mut members: Vec<String>) -> Vec<FavoriteItem> {
+    members.sort();
+    members.iter().filter_map(|m| FavoriteItem::from_key(m)).collect()
+}
+
+#[async_trait]
+impl WatchRepository for RedisWatchRepository {
+    async fn toggle(&self, user_id: u64, item: &FavoriteItem) -> Result<bool, Error> {
+        if self.remove(user_id, item).await? {
+            return Ok(false);
+        }
+        let mut redis_conn = self.pool.get().await?;
+        let _: i64 = redis_conn.sadd(self.user_key(user_id), item.key()).await?;
+        let _: i64 = redis_conn.sadd(self.watchers_key(item), user_id).await?;
+        let _: i64 = redis_conn.sadd(self.watched_key(), item.key()).await?;
+        Ok(true)
+    }
+
+    async fn remove(&self, user_id: u64, item: &FavoriteItem) -> Result<bool, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let removed: i64 = redis_conn.srem(self.user_key(user_id), item.key()).await?;
+        if removed == 0 {
+            return Ok(false);
+        }
+        let _: i64 = redis_conn.srem(self.watchers_key(item), user_id).await?;
+        let remaining: i64 = redis_conn.scard(self.watchers_key(item)).await?;
+        if remaining == 0 {
+            let _: i64 = redis_conn.srem(self.watched_key(), item.key()).await?;
+            let _: i64 = redis_conn.hdel(self.last_seen_key(), item.key()).await?;
+        }
+        Ok(true)
+    }
+
+    async fn list(&self, user_id: u64) -> Result<Vec<FavoriteItem>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        Ok(parse_items(redis_conn.smembers(self.user_key(user_id)).await?))
+    }
+
+    async fn watched_items(&self) -> Result<Vec<FavoriteItem>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        Ok(parse_items(redis_conn.smembers(self.watched_key()).await?))
+    }
+
+    async fn watchers(&self, item: &FavoriteItem) -> Result<Vec<u64>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let mut watchers: Vec<u64> = redis_conn.smembers(self.watchers_key(item)).await?;
+        watchers.sort_unstable();
+        Ok(watchers)
+    }
+
+    async fn last_seen(&self, item: &FavoriteItem) -> Result<Option<String>, Error> {
+        let mut redis_conn = self.pool.get().await?;
+        Ok(redis_conn.hget(self.last_seen_key(), item.key()).await?)
+    }
+
+    async fn set_last_seen(&self, item: &FavoriteItem, fingerprint: &str) -> Result<(), Error> {
+        let mut redis_conn = self.pool.get().await?;
+        let _: i64 = redis_conn.hset(self.last_seen_key(), item.key(), fingerprint).await?;
+        Ok(())
+    }
+}
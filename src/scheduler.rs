@@ -1,58 +1,125 @@
 use crate::{
+    digest,
+    link_health,
+    map_cache,
     types::Data,
     mod_utils,
+    motw,
+    stats,
+    watch,
 };
-use std::{collections::HashMap, sync::Arc}; // Removed env
+use poise::serenity_prelude as serenity;
+use std::sync::Arc;
 use tokio_cron_scheduler::{Job, JobScheduler};
-use tracing::{info, warn, error};
+use tracing::info;
 use anyhow::{Context as AnyhowContext, Result as AnyhowResult};
 
-pub async fn initialize_and_start_scheduler(app_data: Arc<Data>) -> AnyhowResult<()> {
+pub async fn initialize_and_start_scheduler(app_data: Arc<Data>, http: Arc<serenity::Http>) -> AnyhowResult<()> {
     let sched = JobScheduler::new().await
         .context("Failed to create new JobScheduler")?;
     
     let data_for_job = app_data.clone();
-    let job = Job::new_async("0 30 0,6,12,18 * * *", move |_uuid, _l| {
+    let http_for_job = http.clone();
+    let refresh_schedule = app_data.config.schedule.mod_cache_refresh.clone();
+    let job = Job::new_async(refresh_schedule.as_str(), move |_uuid, _l| {
         let job_data_clone = data_for_job.clone();
+        let job_http_clone = http_for_job.clone();
         Box::pin(async move {
             info!("Scheduled Task: Starting slug-based mod cache refresh...");
-            
-            let slugs_to_fetch = ["1228", "12104"];
-            let mut new_mod_cache_map = HashMap::new();
-            let mut total_mods_refreshed = 0;
-            let mut versions_refreshed_count = 0;
-
-            for slug in slugs_to_fetch {
-                match mod_utils::fetch_mods_for_version(&job_data_clone.http_client, slug).await {
-                    Ok(mods) => {
-                        info!(count = mods.len(), slug, "Scheduled Task: Fetched slug-based mods for slug.");
-                        total_mods_refreshed += mods.len();
-                        if !mods.is_empty() { versions_refreshed_count += 1; }
-                        new_mod_cache_map.insert(slug.to_string(), mods);
-                    }
-                    Err(e) => {
-                        error!(error = ?e, slug, "Scheduled Task: Failed to fetch slug-based mods for slug.");
-                    }
-                }
-            }
-
-            if total_mods_refreshed > 0 || versions_refreshed_count > 0 {
-                *job_data_clone.mod_cache.write().await = new_mod_cache_map;
-                info!(
-                    total_mods = total_mods_refreshed,
-                    versions_loaded = versions_refreshed_count,
-                    "Scheduled Task: Slug-based mod cache refresh complete."
-                );
-            } else {
-                warn!("Scheduled Task: No new slug-based mods loaded, slug-based mod cache not updated.");
+            if mod_utils::refresh_mod_cache(&job_data_clone).await > 0 {
+                watch::run_watch_check(&job_data_clone, &job_http_clone).await;
             }
             info!("Scheduled Task: Finished.");
         })
     })?;
 
     sched.add(job).await.context("Failed to add slug-based mod cache refresh job")?;
+
+    let data_for_watch_job = app_data.clone();
+    let watch_schedule = app_data.config.schedule.watch_check.clone();
+    let http_for_watch_job = http.clone();
+    let watch_job = Job::new_async(watch_schedule.as_str(), move |_uuid, _l| {
+        let job_data_clone = data_for_watch_job.clone();
+        let job_http_clone = http_for_watch_job.clone();
+        Box::pin(async move {
+            info!("Scheduled Task: Checking watched maps and mods for updates...");
+            watch::run_watch_check(&job_data_clone, &job_http_clone).await;
+        })
+    })?;
+
+    sched.add(watch_job).await.context("Failed to add watch check job")?;
+
+    let data_for_motw_job = app_data.clone();
+    let motw_schedule = app_data.config.schedule.motw_close.clone();
+    let http_for_motw_job = http.clone();
+    let motw_job = Job::new_async(motw_schedule.as_str(), move |_uuid, _l| {
+        let job_data_clone = data_for_motw_job.clone();
+        let job_http_clone = http_for_motw_job.clone();
+        Box::pin(async move {
+            info!("Scheduled Task: Closing map of the week polls...");
+            motw::close_all_polls(&job_data_clone, &job_http_clone).await;
+        })
+    })?;
+
+    sched.add(motw_job).await.context("Failed to add map of the week close job")?;
+
+    let data_for_author_job = app_data.clone();
+    let author_schedule = app_data.config.schedule.author_index_refresh.clone();
+    let author_job = Job::new_async(author_schedule.as_str(), move |_uuid, _l| {
+        let job_data_clone = data_for_author_job.clone();
+        Box::pin(async move {
+            info!("Scheduled Task: Rebuilding author, tag and map link indexes...");
+            map_cache::refresh_map_indexes(&job_data_clone).await;
+        })
+    })?;
+
+    sched.add(author_job).await.context("Failed to add author index refresh job")?;
+
+    let link_client = link_health::link_check_client().context("Failed to build link check HTTP client")?;
+    let data_for_link_job = app_data.clone();
+    let link_schedule = app_data.config.schedule.link_check.clone();
+    let link_job = Job::new_async(link_schedule.as_str(), move |_uuid, _l| {
+        let job_data_clone = data_for_link_job.clone();
+        let job_client_clone = link_client.clone();
+        Box::pin(async move {
+            info!("Scheduled Task: Checking community mod download links...");
+            link_health::run_link_check(&job_data_clone, &job_client_clone, link_health::LINK_CHECK_DELAY).await;
+        })
+    })?;
+
+    sched.add(link_job).await.context("Failed to add link check job")?;
+
+    let data_for_stats_job = app_data.clone();
+    let stats_schedule = app_data.config.schedule.stats_snapshot.clone();
+    let stats_job = Job::new_async(stats_schedule.as_str(), move |_uuid, _l| {
+        let job_data_clone = data_for_stats_job.clone();
+        Box::pin(async move {
+            info!("Scheduled Task: Snapshotting map stats...");
+            stats::take_snapshots(&job_data_clone, serenity::Timestamp::now().unix_timestamp()).await;
+        })
+    })?;
+
+    sched.add(stats_job).await.context("Failed to add stats snapshot job")?;
+
+    let data_for_digest_job = app_data.clone();
+    let digest_schedule = app_data.config.schedule.digest_check.clone();
+    let digest_job = Job::new_async(digest_schedule.as_str(), move |_uuid, _l| {
+        let job_data_clone = data_for_digest_job.clone();
+        let job_http_clone = http.clone();
+        Box::pin(async move {
+            digest::run_due_digests(&job_data_clone, &job_http_clone).await;
+        })
+    })?;
+
+    sched.add(digest_job).await.context("Failed to add digest job")?;
     sched.start().await.context("Failed to start slug-based mod cache refresh scheduler")?;
-    info!("Slug-based mod cache refresh scheduler started. Job scheduled for '0 30 0,6,12,18 * * *' (UTC).");
+    info!("Slug-based mod cache refresh scheduler started. Job scheduled for '{}' (UTC).", refresh_schedule);
+    info!("Watch check job scheduled for '{}' (UTC).", watch_schedule);
+    info!("Map of the week close job scheduled for '{}' (UTC).", motw_schedule);
+    info!("Author, tag and map link index refresh job scheduled for '{}' (UTC).", author_schedule);
+    info!("Link check job scheduled for '{}' (UTC).", link_schedule);
+    info!("Stats snapshot job scheduled for '{}' (UTC).", stats_schedule);
+    info!("Digest check job scheduled for '{}' (UTC).", digest_schedule);
     
     Ok(())
 }
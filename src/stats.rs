@@ -0,0 +1,143 @@
+use crate::{
+    config, map_cache,
+    mod_utils::{self, format_count},
+    repository,
+    types::{ApiModioMap, Data, Error, StatsSnapshot},
+};
+use std::collections::HashMap;
+use tracing::{error, info};
+
+/// Snapshots older than this are dropped; long enough for a monthly trend with room to spare.
+pub const STATS_RETENTION_SECS: i64 = 90 * 86_400;
+
+/// Leeway for a snapshot job that started a little after its scheduled time.
+const SNAPSHOT_SLACK_SECS: i64 = 60;
+
+/// Upcoming runs looked at to find the longest gap of a snapshot schedule.
+const INTERVAL_SAMPLE_RUNS: usize = 24;
+
+/// How far back `/maps trending` counts downloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, poise::ChoiceParameter)]
+pub enum TrendingWindow {
+    #[name = "Last 24 hours"]
+    Day,
+    #[default]
+    #[name = "Last 7 days"]
+    Week,
+    #[name = "Last 30 days"]
+    Month,
+}
+
+impl TrendingWindow {
+    pub fn secs(self) -> i64 {
+        match self {
+            Self::Day => 86_400,
+            Self::Week => 7 * 86_400,
+            Self::Month => 30 * 86_400,
+        }
+    }
+}
+
+pub fn snapshot_of(map: &ApiModioMap, taken_at: i64) -> StatsSnapshot {
+    StatsSnapshot {
+        map_id: map.id,
+        taken_at,
+        downloads: i64::from(map.stats.downloads_total),
+        subscribers: i64::from(map.stats.subscribers_total),
+        ratings_positive: map.stats.ratings_positive,
+        ratings_negative: map.stats.ratings_negative,
+    }
+}
+
+/// Records the current stats of every map and drops snapshots past the retention window.
+/// The maps are loaded anyway, so the map indexes are rebuilt from them too. Returns how many maps were snapshotted.
+pub async fn take_snapshots(data: &Data, now: i64) -> usize {
+    let maps = match repository::load_all_maps(data.maps.as_ref()).await {
+        Ok(maps) => maps,
+        Err(e) => {
+            error!("Stats snapshot: Failed to load maps: {}", e);
+            return 0;
+        }
+    };
+    let snapshots: Vec<StatsSnapshot> = maps.iter().map(|map| snapshot_of(map, now)).collect();
+    let ids: Vec<i32> = maps.iter().map(|map| map.id).collect();
+    map_cache::rebuild_map_indexes(data, maps).await;
+    if let Err(e) = data.stats.record(&snapshots).await {
+        error!("Stats snapshot: Failed to record snapshots: {}", e);
+        return 0;
+    }
+    if let Err(e) = data.stats.prune(&ids, now - STATS_RETENTION_SECS).await {
+        error!("Stats snapshot: Failed to prune old snapshots: {}", e);
+    }
+    info!(maps = snapshots.len(), "Stats snapshot taken.");
+    snapshots.len()
+}
+
+/// The longest gap between the next runs of the `stats_snapshot` schedule after `after`, or `None` when it doesn't run.
+pub fn snapshot_interval(cron: &str, after: i64) -> Option<i64> {
+    let mut previous = config::next_cron_run(cron, after)?;
+    let mut longest = None;
+    for _ in 0..INTERVAL_SAMPLE_RUNS {
+        let Some(next) = config::next_cron_run(cron, previous) else { break };
+        longest = longest.max(Some(next - previous));
+        previous = next;
+    }
+    longest
+}
+
+/// Drops baselines taken more than `max_lag` after `since`. The map wasn't tracked yet when the window started,
+/// so its gain would only cover part of the window.
+pub fn baselines_within(baselines: HashMap<i32, StatsSnapshot>, since: i64, max_lag: i64) -> HashMap<i32, StatsSnapshot> {
+    baselines.into_iter().filter(|(_, snapshot)| snapshot.taken_at - since <= max_lag).collect()
+}
+
+/// The baseline of each of `map_ids` for a window starting at `since`, leaving out maps first snapshotted later than one snapshot interval in.
+pub async fn window_baselines(data: &Data, map_ids: &[i32], since: i64) -> Result<HashMap<i32, StatsSnapshot>, Error> {
+    let baselines = data.stats.baselines(map_ids, since).await?;
+    let Some(interval) = snapshot_interval(&data.config.schedule.stats_snapshot, since) else { return Ok(baselines) };
+    Ok(baselines_within(baselines, since, interval + SNAPSHOT_SLACK_SECS))
+}
+
+/// Downloads a map gained since its baseline snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadGain {
+    pub map_id: i32,
+    pub name: String,
+    pub author: String,
+    pub profile_url: String,
+    pub gained: i64,
+}
+
+/// Maps that gained downloads since their baseline, biggest gain first. Maps without a baseline are skipped.
+pub fn download_gains<'a>(
+    maps: impl IntoIterator<Item = &'a ApiModioMap>,
+    baselines: &HashMap<i32, StatsSnapshot>,
+) -> Vec<DownloadGain> {
+    let mut gains: Vec<DownloadGain> = maps
+        .into_iter()
+        .filter_map(|map| {
+            let gained = i64::from(map.stats.downloads_total) - baselines.get(&map.id)?.downloads;
+            (gained > 0).then(|| DownloadGain {
+                map_id: map.id,
+                name: map.name.clone(),
+                author: map.submitted_by.username.clone(),
+                profile_url: map.profile_url.clone(),
+                gained,
+            })
+        })
+        .collect();
+    gains.sort_by(|a, b| b.gained.cmp(&a.gained).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    gains
+}
+
+/// One list line of `/maps trending`: rank, linked name, author and downloads gained.
+pub fn trending_line(rank: usize, gain: &DownloadGain) -> String {
+    format!(
+        "**{}.** [{}]({}) by {} · ⬇️ +{}",
+        rank,
+        mod_utils::truncate_with_ellipsis(&gain.name, 60),
+        gain.profile_url,
+        gain.author,
+        format_count(gain.gained)
+    )
+}
@@ -0,0 +1,132 @@
+use crate::{
+    mod_utils::{self, format_count},
+    types::{ApiModioMap, Data, MAP_TAG},
+};
+use std::collections::{BTreeSet, HashMap};
+use tracing::info;
+
+/// How several tags are combined when listing maps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, poise::ChoiceParameter)]
+pub enum TagMatch {
+    /// Maps carrying every tag (AND).
+    #[default]
+    #[name = "All tags (AND)"]
+    All,
+    /// Maps carrying at least one of the tags (OR).
+    #[name = "Any tag (OR)"]
+    Any,
+}
+
+/// A mod.io tag and the maps carrying it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagEntry {
+    /// Display name as first seen on mod.io.
+    pub name: String,
+    pub map_ids: Vec<i32>,
+}
+
+/// Tags present on maps, keyed case-insensitively, rebuilt periodically from the map store.
+/// The `Map` tag itself is left out since every entry in the index carries it.
+#[derive(Debug, Default)]
+pub struct TagIndex {
+    by_name: HashMap<String, TagEntry>,
+}
+
+impl TagIndex {
+    pub fn from_maps<'a>(maps: impl IntoIterator<Item = &'a ApiModioMap>) -> Self {
+        let mut by_name: HashMap<String, TagEntry> = HashMap::new();
+        for map in maps.into_iter().filter(|m| m.has_tag(MAP_TAG)) {
+            for tag in map.tags.iter().flatten().filter(|t| t.name != MAP_TAG && !t.name.trim().is_empty()) {
+                let entry = by_name
+                    .entry(tag.name.trim().to_lowercase())
+                    .or_insert_with(|| TagEntry { name: tag.name.trim().to_string(), map_ids: Vec::new() });
+                if !entry.map_ids.contains(&map.id) {
+                    entry.map_ids.push(map.id);
+                }
+            }
+        }
+        Self { by_name }
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_name.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_name.is_empty()
+    }
+
+    /// Case-insensitive lookup by tag name.
+    pub fn get(&self, name: &str) -> Option<&TagEntry> {
+        self.by_name.get(&name.trim().to_lowercase())
+    }
+
+    /// Tags whose name contains `partial`, most used first.
+    pub fn suggestions(&self, partial: &str, limit: usize) -> Vec<&TagEntry> {
+        let partial_lowercase = partial.trim().to_lowercase();
+        let mut matches: Vec<&TagEntry> = self
+            .by_name
+            .iter()
+            .filter(|(key, _)| key.contains(&partial_lowercase))
+            .map(|(_, tag)| tag)
+            .collect();
+        matches.sort_by(|a, b| b.map_ids.len().cmp(&a.map_ids.len()).then_with(|| a.name.cmp(&b.name)));
+        matches.truncate(limit);
+        matches
+    }
+
+    /// Canonical names for typed or autocompleted tags, without duplicates. Fails with the first unknown query.
+    pub fn resolve(&self, queries: &[String]) -> Result<Vec<String>, String> {
+        let mut names: Vec<String> = Vec::with_capacity(queries.len());
+        for query in queries {
+            let entry = self.get(query).ok_or_else(|| query.clone())?;
+            if !names.contains(&entry.name) {
+                names.push(entry.name.clone());
+            }
+        }
+        Ok(names)
+    }
+
+    /// IDs of the maps matching `tags` combined with `mode`, in ascending order.
+    /// Unknown tags match nothing, so they empty an AND query and are ignored by an OR query.
+    pub fn matching_ids(&self, tags: &[&str], mode: TagMatch) -> Vec<i32> {
+        let mut sets = tags.iter().map(|name| {
+            self.get(name).map(|tag| tag.map_ids.iter().copied().collect::<BTreeSet<i32>>()).unwrap_or_default()
+        });
+        let Some(first) = sets.next() else { return Vec::new() };
+        let combined = sets.fold(first, |acc, set| match mode {
+            TagMatch::All => acc.intersection(&set).copied().collect(),
+            TagMatch::Any => acc.union(&set).copied().collect(),
+        });
+        combined.into_iter().collect()
+    }
+}
+
+/// Rebuilds `data.tags` from `maps`, see [`crate::map_cache`]. Returns how many tags were indexed.
+pub async fn rebuild_tag_index(data: &Data, maps: &[ApiModioMap]) -> usize {
+    let index = TagIndex::from_maps(maps);
+    let count = index.len();
+    *data.tags.write().await = index;
+    info!(tags = count, maps = maps.len(), "Tag index refreshed.");
+    count
+}
+
+/// Joins tag names for a title, e.g. `"Street + Night"` for AND or `"Street or Night"` for OR.
+pub fn describe_tags(tags: &[&str], mode: TagMatch) -> String {
+    let separator = match mode {
+        TagMatch::All => " + ",
+        TagMatch::Any => " or ",
+    };
+    tags.join(separator)
+}
+
+/// One list line for a tagged map: linked name, author and downloads.
+pub fn tagged_map_line(map: &ApiModioMap) -> String {
+    format!(
+        "[{}]({}) by {} · ⬇️ {}",
+        mod_utils::truncate_with_ellipsis(&map.name, 60),
+        map.profile_url,
+        map.submitted_by.username,
+        format_count(i64::from(map.stats.downloads_total))
+    )
+}
@@ -0,0 +1,173 @@
+use crate::{
+    mod_utils,
+    types::{Error, StatsSnapshot, BOT_EMBED_COLOR},
+};
+use anyhow::anyhow;
+use plotters::prelude::*;
+use std::sync::OnceLock;
+
+pub const CHART_WIDTH: u32 = 800;
+pub const CHART_HEIGHT: u32 = 400;
+
+/// Bundled so charts render the same on hosts without system fonts, like the Alpine image.
+const CHART_FONT: &[u8] = include_bytes!("../assets/DejaVuSans.ttf");
+const FONT_FAMILY: &str = "sans-serif";
+
+/// Discord's dark theme embed background, so the chart blends in.
+const BACKGROUND: RGBColor = RGBColor(0x2b, 0x2d, 0x31);
+const FOREGROUND: RGBColor = RGBColor(0xdb, 0xde, 0xe1);
+const GRID: RGBColor = RGBColor(0x3f, 0x41, 0x47);
+const DOWNLOADS_COLOR: RGBColor = RGBColor((BOT_EMBED_COLOR >> 16) as u8, (BOT_EMBED_COLOR >> 8) as u8, BOT_EMBED_COLOR as u8);
+const SUBSCRIBERS_COLOR: RGBColor = RGBColor(0xf0, 0xa0, 0x30);
+
+/// How far back `/maps trend` looks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, poise::ChoiceParameter)]
+pub enum TrendRange {
+    #[name = "Last 7 days"]
+    Week,
+    #[default]
+    #[name = "Last 30 days"]
+    Month,
+    #[name = "Last 90 days"]
+    Quarter,
+}
+
+impl TrendRange {
+    pub fn secs(self) -> i64 {
+        match self {
+            Self::Week => 7 * 86_400,
+            Self::Month => 30 * 86_400,
+            Self::Quarter => 90 * 86_400,
+        }
+    }
+}
+
+/// Change over a stretch of snapshots, first to last.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrendSummary {
+    pub from: i64,
+    pub to: i64,
+    pub downloads_gained: i64,
+    pub subscribers_gained: i64,
+    /// Share of positive ratings at the first and last snapshot, `None` while unrated.
+    pub rating_from: Option<f64>,
+    pub rating_to: Option<f64>,
+}
+
+fn rating_percent(snapshot: &StatsSnapshot) -> Option<f64> {
+    let total = snapshot.ratings_positive + snapshot.ratings_negative;
+    (total > 0).then(|| f64::from(snapshot.ratings_positive) * 100.0 / f64::from(total))
+}
+
+/// `None` with fewer than two snapshots, since there is nothing to compare.
+pub fn summarize(history: &[StatsSnapshot]) -> Option<TrendSummary> {
+    let (first, last) = match history {
+        [first, .., last] => (first, last),
+        _ => return None,
+    };
+    Some(TrendSummary {
+        from: first.taken_at,
+        to: last.taken_at,
+        downloads_gained: last.downloads - first.downloads,
+        subscribers_gained: last.subscribers - first.subscribers,
+        rating_from: rating_percent(first),
+        rating_to: rating_percent(last),
+    })
+}
+
+fn register_font() -> Result<(), Error> {
+    static REGISTERED: OnceLock<bool> = OnceLock::new();
+    let ok = *REGISTERED.get_or_init(|| plotters::style::register_font(FONT_FAMILY, FontStyle::Normal, CHART_FONT).is_ok());
+    if ok { Ok(()) } else { Err(anyhow!("Bundled chart font could not be loaded")) }
+}
+
+/// Pads a value range so flat lines sit mid-chart instead of on the axis.
+fn padded_range(values: impl Iterator<Item = i64> + Clone) -> std::ops::Range<i64> {
+    let min = values.clone().min().unwrap_or(0);
+    let max = values.max().unwrap_or(0);
+    let pad = ((max - min) / 10).max(1);
+    (min - pad).max(0)..max + pad
+}
+
+fn date_label(timestamp: &i64) -> String {
+    chrono::DateTime::<chrono::Utc>::from_timestamp(*timestamp, 0).map(|at| at.format("%b %d").to_string()).unwrap_or_default()
+}
+
+fn count_label(count: &i64) -> String {
+    mod_utils::format_count(*count)
+}
+
+/// Draws downloads (left axis) and subscribers (right axis) over time and encodes the chart as a PNG.
+/// Needs at least two snapshots, oldest first.
+pub fn render_trend_png(title: &str, history: &[StatsSnapshot]) -> Result<Vec<u8>, Error> {
+    if history.len() < 2 {
+        return Err(anyhow!("At least two snapshots are needed to draw a trend"));
+    }
+    register_font()?;
+
+    let mut pixels = vec![0u8; (CHART_WIDTH * CHART_HEIGHT * 3) as usize];
+    {
+        let root = BitMapBackend::with_buffer(&mut pixels, (CHART_WIDTH, CHART_HEIGHT)).into_drawing_area();
+        root.fill(&BACKGROUND)?;
+
+        let times = history.first().map_or(0, |s| s.taken_at)..history.last().map_or(1, |s| s.taken_at);
+        let downloads = padded_range(history.iter().map(|s| s.downloads));
+        let subscribers = padded_range(history.iter().map(|s| s.subscribers));
+        let text = |size: u32| (FONT_FAMILY, size).into_font().color(&FOREGROUND);
+
+        let mut chart = ChartBuilder::on(&root)
+            .caption(mod_utils::truncate_with_ellipsis(title, 60), text(22))
+            .margin(16)
+            .x_label_area_size(32)
+            .y_label_area_size(64)
+            .right_y_label_area_size(64)
+            .build_cartesian_2d(times.clone(), downloads)?
+            .set_secondary_coord(times, subscribers);
+
+        chart
+            .configure_mesh()
+            .light_line_style(TRANSPARENT)
+            .bold_line_style(GRID)
+            .axis_style(FOREGROUND)
+            .label_style(text(14))
+            .x_labels(6)
+            .y_labels(6)
+            .x_label_formatter(&date_label)
+            .y_label_formatter(&count_label)
+            .y_desc("Downloads")
+            .draw()?;
+        chart
+            .configure_secondary_axes()
+            .axis_style(FOREGROUND)
+            .label_style(text(14))
+            .y_labels(6)
+            .y_label_formatter(&count_label)
+            .y_desc("Subscribers")
+            .draw()?;
+
+        chart
+            .draw_series(LineSeries::new(history.iter().map(|s| (s.taken_at, s.downloads)), DOWNLOADS_COLOR.stroke_width(3)))?
+            .label("Downloads")
+            .legend(|(x, y)| PathElement::new([(x, y), (x + 20, y)], DOWNLOADS_COLOR.stroke_width(3)));
+        chart
+            .draw_secondary_series(LineSeries::new(history.iter().map(|s| (s.taken_at, s.subscribers)), SUBSCRIBERS_COLOR.stroke_width(2)))?
+            .label("Subscribers")
+            .legend(|(x, y)| PathElement::new([(x, y), (x + 20, y)], SUBSCRIBERS_COLOR.stroke_width(2)));
+
+        chart
+            .configure_series_labels()
+            .position(SeriesLabelPosition::UpperLeft)
+            .background_style(BACKGROUND.mix(0.8))
+            .border_style(GRID)
+            .label_font(text(14))
+            .draw()?;
+        root.present()?;
+    }
+
+    let mut png_bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png_bytes, CHART_WIDTH, CHART_HEIGHT);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&pixels)?;
+    Ok(png_bytes)
+}
@@ -1,11 +1,24 @@
 use poise::ChoiceParameter;
-use serde::Deserialize;
-use std::{collections::HashMap, fmt, str::FromStr, sync::Arc}; // Keep FromStr for ModVersionBranch
+use serde::{Deserialize, Serialize};
+use std::{collections::{BTreeMap, HashMap, HashSet}, fmt, str::FromStr, sync::Arc}; // Keep FromStr for ModVersionBranch
 use tokio::sync::RwLock;
 use reqwest::Client as ReqwestClient;
-use deadpool_redis::{Pool, Config as DeadpoolRedisConfig, Runtime as DeadpoolRuntime};
+use deadpool_redis::{Config as DeadpoolRedisConfig, Runtime as DeadpoolRuntime};
+use crate::authors::AuthorIndex;
+use crate::tags::TagIndex;
+use crate::mentions::Cooldowns;
+use crate::rate_limit::RateLimiter;
+use crate::analytics::Invocations;
+use crate::config::Config;
+use crate::repository::{
+    AnalyticsRepository, DigestRepository, FailedSearchRepository, FavoritesRepository, HttpModRepository, InMemoryAnalyticsRepository, InMemoryDigestRepository, InMemoryFailedSearchRepository, InMemoryFavoritesRepository,
+    InMemoryLinkHealthRepository, InMemoryMentionChannelRepository, InMemoryMotwRepository, InMemoryStatsRepository,
+    InMemoryWatchRepository, LinkHealthRepository, MapRepository, MentionChannelRepository, ModRepository, MotwRepository,
+    RedisAnalyticsRepository, RedisDigestRepository, RedisFailedSearchRepository, RedisFavoritesRepository, RedisLinkHealthRepository, RedisMapRepository, RedisMentionChannelRepository,
+    RedisMotwRepository, RedisStatsRepository, RedisWatchRepository, StatsRepository, WatchRepository,
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ChoiceParameter)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ChoiceParameter)]
 pub enum ModVersionBranch {
     #[name = "Alpha"]
     Alpha,
@@ -146,36 +159,390 @@ pub struct ApiModioMap {
     pub media: Option<ApiModioMedia>,
 }
 
+impl ApiModioMap {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.as_ref().is_some_and(|tags| tags.iter().any(|t| t.name == tag))
+    }
+}
+
+/// Something a user can star or watch: a mod.io map by ID, or a community script mod by title and branch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FavoriteItem {
+    Map { id: i32 },
+    Mod { title: String, branch: ModVersionBranch },
+}
+
+impl FavoriteItem {
+    /// Stable string form used in Redis and component custom IDs: `map:{id}` or `mod:{slug}:{title}`.
+    pub fn key(&self) -> String {
+        match self {
+            Self::Map { id } => format!("map:{}", id),
+            Self::Mod { title, branch } => format!("mod:{}:{}", crate::mod_utils::resolve_version_slug(*branch), title),
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        let (kind, rest) = key.split_once(':')?;
+        match kind {
+            "map" => rest.parse().ok().map(|id| Self::Map { id }),
+            "mod" => {
+                let (slug, title) = rest.split_once(':')?;
+                let branch = crate::mod_utils::branch_for_slug(slug)?;
+                Some(Self::Mod { title: title.to_string(), branch })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for FavoriteItem {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.key())
+    }
+}
+
+impl<'de> Deserialize<'de> for FavoriteItem {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let key = String::deserialize(deserializer)?;
+        Self::from_key(&key).ok_or_else(|| serde::de::Error::custom(format!("invalid favorite key '{}'", key)))
+    }
+}
+
+/// A named snapshot of someone's favorites that others can load by its short code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Collection {
+    pub code: String,
+    pub name: String,
+    pub owner_id: u64,
+    pub items: Vec<FavoriteItem>,
+}
+
+/// An open map of the week vote in one guild. Votes are stored separately, keyed by voter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MotwPoll {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub message_id: u64,
+    /// Nominated map IDs, in ballot order.
+    pub nominees: Vec<i32>,
+    pub opened_at: i64,
+}
+
+/// A past map of the week winner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HallOfFameEntry {
+    pub map_id: i32,
+    pub map_name: String,
+    pub votes: usize,
+    pub closed_at: i64,
+}
+
+/// Outcome of the last health check of a community mod download link.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkStatus {
+    pub url: String,
+    /// HTTP status of the last check, `None` when the request itself failed.
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub dead: bool,
+    pub checked_at: i64,
+}
+
+/// Download and rating counters of one map at one point in time, see [`crate::stats`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatsSnapshot {
+    pub map_id: i32,
+    pub taken_at: i64,
+    pub downloads: i64,
+    pub subscribers: i64,
+    pub ratings_positive: i32,
+    pub ratings_negative: i32,
+}
+
+/// A guild's activity digest: where and when it is posted, and what the last one covered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestSubscription {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    /// Cron expression (with seconds, UTC) of the posting schedule.
+    pub cron: String,
+    pub subscribed_at: i64,
+    pub last_posted_at: Option<i64>,
+    /// When a digest was last due, posted or not. A failed post waits for the next scheduled run.
+    #[serde(default)]
+    pub last_attempt_at: Option<i64>,
+    /// Working version per community mod, keyed like [`FavoriteItem::key`], as of the last digest.
+    #[serde(default)]
+    pub mods_seen: BTreeMap<String, String>,
+}
+
+/// What a failed search was looking for, see [`crate::failed_searches`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ChoiceParameter)]
+pub enum SearchTarget {
+    #[name = "Maps"]
+    Maps,
+    #[name = "Mods"]
+    Mods,
+}
+
+impl SearchTarget {
+    /// Stable string form used in Redis keys.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Maps => "map",
+            Self::Mods => "mod",
+        }
+    }
+}
+
+/// A normalized query that found nothing, and how often it was searched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedSearch {
+    pub query: String,
+    pub count: i64,
+}
+
+/// One finished command invocation, see [`crate::analytics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandEvent {
+    /// Qualified name, e.g. `map search`.
+    pub command: String,
+    /// `None` in direct messages.
+    pub guild_id: Option<u64>,
+    pub success: bool,
+    /// Time until the first reply, or until the command finished when it never replied.
+    pub latency_ms: u64,
+    pub map_ids: Vec<i32>,
+    pub mod_titles: Vec<String>,
+    /// The query of a search that came up empty.
+    pub failed_query: Option<String>,
+    pub at: i64,
+}
+
+impl CommandEvent {
+    /// The guild ID as counted in [`UsageTotals::guilds`].
+    pub fn guild_key(&self) -> String {
+        self.guild_id.map_or_else(|| "dm".to_string(), |id| id.to_string())
+    }
+}
+
+/// Command usage counters summed over a range of days.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UsageTotals {
+    /// Invocations per command.
+    pub commands: HashMap<String, i64>,
+    /// Invocations per command that ended in an error.
+    pub failures: HashMap<String, i64>,
+    /// Summed latency per command, in milliseconds.
+    pub latency_ms: HashMap<String, i64>,
+    /// Invocations per guild ID; direct messages count under `"dm"`.
+    pub guilds: HashMap<String, i64>,
+    pub maps: HashMap<i32, i64>,
+    pub mods: HashMap<String, i64>,
+    pub failed_searches: HashMap<String, i64>,
+}
+
+impl UsageTotals {
+    /// Counts one event.
+    pub fn add(&mut self, event: &CommandEvent) {
+        *self.commands.entry(event.command.clone()).or_default() += 1;
+        if !event.success {
+            *self.failures.entry(event.command.clone()).or_default() += 1;
+        }
+        *self.latency_ms.entry(event.command.clone()).or_default() += event.latency_ms as i64;
+        *self.guilds.entry(event.guild_key()).or_default() += 1;
+        for id in &event.map_ids {
+            *self.maps.entry(*id).or_default() += 1;
+        }
+        for title in &event.mod_titles {
+            *self.mods.entry(title.clone()).or_default() += 1;
+        }
+        if let Some(query) = &event.failed_query {
+            *self.failed_searches.entry(query.clone()).or_default() += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: &UsageTotals) {
+        fn merge_counts<K: Clone + Eq + std::hash::Hash>(into: &mut HashMap<K, i64>, from: &HashMap<K, i64>) {
+            for (key, count) in from {
+                *into.entry(key.clone()).or_default() += count;
+            }
+        }
+        merge_counts(&mut self.commands, &other.commands);
+        merge_counts(&mut self.failures, &other.failures);
+        merge_counts(&mut self.latency_ms, &other.latency_ms);
+        merge_counts(&mut self.guilds, &other.guilds);
+        merge_counts(&mut self.maps, &other.maps);
+        merge_counts(&mut self.mods, &other.mods);
+        merge_counts(&mut self.failed_searches, &other.failed_searches);
+    }
+}
+
 #[derive(Clone)] // Removed Debug derive for now
 pub struct Data {
-    pub http_client: Arc<ReqwestClient>,
+    pub config: Arc<Config>,
     pub mod_cache: Arc<RwLock<HashMap<String, Vec<ModEntry>>>>,
-    pub redis_pool: Arc<Pool>,
+    pub authors: Arc<RwLock<AuthorIndex>>,
+    pub tags: Arc<RwLock<TagIndex>>,
+    /// Download links found dead by the last link check, see [`crate::link_health`].
+    pub dead_links: Arc<RwLock<HashSet<String>>>,
+    /// mod.io `name_id` to map ID, rebuilt with the tag index, for resolving pasted map links.
+    pub map_slugs: Arc<RwLock<HashMap<String, i32>>>,
+    /// Every map as of the last map index refresh, see [`crate::map_cache`]. Listings and reports read this instead of loading the whole store.
+    pub map_list: Arc<RwLock<Vec<ApiModioMap>>>,
+    /// Channels with mention replies turned on, mirrored from `mention_channels`.
+    pub mention_channel_ids: Arc<RwLock<HashSet<u64>>>,
+    pub mention_cooldowns: Arc<std::sync::Mutex<Cooldowns>>,
+    pub rate_limiter: Arc<std::sync::Mutex<RateLimiter>>,
+    /// Held for a whole watch check; the mod cache refresh and the watch job can fire at the same time.
+    pub watch_check_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Invocations in flight, see [`crate::analytics`].
+    pub invocations: Arc<std::sync::Mutex<Invocations>>,
+    pub maps: Arc<dyn MapRepository>,
+    pub mods: Arc<dyn ModRepository>,
+    pub favorites: Arc<dyn FavoritesRepository>,
+    pub watches: Arc<dyn WatchRepository>,
+    pub motw: Arc<dyn MotwRepository>,
+    pub link_health: Arc<dyn LinkHealthRepository>,
+    pub stats: Arc<dyn StatsRepository>,
+    pub digests: Arc<dyn DigestRepository>,
+    pub mention_channels: Arc<dyn MentionChannelRepository>,
+    pub analytics: Arc<dyn AnalyticsRepository>,
+    pub failed_searches: Arc<dyn FailedSearchRepository>,
 }
 
 // Manual implementation of Debug for Data
 impl fmt::Debug for Data {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Data")
-            .field("http_client", &self.http_client) // Arc<ReqwestClient> is Debug
+            .field("config", &self.config) // Secrets are redacted by their own Debug impl
             .field("mod_cache", &self.mod_cache)     // Arc<RwLock<...>> is Debug if inner is Debug
-            .field("redis_pool", &"<Redis Pool>") // Placeholder for non-Debug Pool
+            .field("authors", &self.authors)
+            .field("tags", &self.tags)
+            .field("dead_links", &self.dead_links)
+            .field("map_slugs", &self.map_slugs)
+            .field("map_list", &self.map_list)
+            .field("mention_channel_ids", &self.mention_channel_ids)
+            .field("mention_cooldowns", &self.mention_cooldowns)
+            .field("rate_limiter", &self.rate_limiter)
+            .field("watch_check_lock", &self.watch_check_lock)
+            .field("invocations", &self.invocations)
+            .field("maps", &"<MapRepository>") // Placeholder for trait objects
+            .field("mods", &"<ModRepository>")
+            .field("favorites", &"<FavoritesRepository>")
+            .field("watches", &"<WatchRepository>")
+            .field("motw", &"<MotwRepository>")
+            .field("link_health", &"<LinkHealthRepository>")
+            .field("stats", &"<StatsRepository>")
+            .field("digests", &"<DigestRepository>")
+            .field("mention_channels", &"<MentionChannelRepository>")
+            .field("analytics", &"<AnalyticsRepository>")
+            .field("failed_searches", &"<FailedSearchRepository>")
             .finish()
     }
 }
 
 
 impl Data {
-    pub fn new(redis_url: &str) -> Result<Self, Error> { // Changed AppError to Error
-        let cfg = DeadpoolRedisConfig::from_url(redis_url);
+    pub fn new(config: Config) -> Result<Self, Error> { // Changed AppError to Error
+        let cfg = DeadpoolRedisConfig::from_url(&config.redis.url);
         let pool = cfg.create_pool(Some(DeadpoolRuntime::Tokio1))
             .map_err(|e| anyhow::anyhow!("Failed to create Redis pool: {}", e))?;
         
-        Ok(Self {
-            http_client: Arc::new(ReqwestClient::new()),
+        Ok(Self::with_repositories(
+            Arc::new(RedisMapRepository::new(pool.clone(), config.redis.clone())),
+            Arc::new(HttpModRepository::with_base_url(ReqwestClient::new(), &config.api.mods_base_url)),
+        )
+        .with_favorites(Arc::new(RedisFavoritesRepository::new(pool.clone(), config.redis.clone())))
+        .with_watches(Arc::new(RedisWatchRepository::new(pool.clone(), config.redis.clone())))
+        .with_motw(Arc::new(RedisMotwRepository::new(pool.clone(), config.redis.clone())))
+        .with_link_health(Arc::new(RedisLinkHealthRepository::new(pool.clone(), config.redis.clone())))
+        .with_stats(Arc::new(RedisStatsRepository::new(pool.clone(), config.redis.clone())))
+        .with_digests(Arc::new(RedisDigestRepository::new(pool.clone(), config.redis.clone())))
+        .with_mention_channels(Arc::new(RedisMentionChannelRepository::new(pool.clone(), config.redis.clone())))
+        .with_analytics(Arc::new(RedisAnalyticsRepository::new(pool.clone(), config.redis.clone())))
+        .with_failed_searches(Arc::new(RedisFailedSearchRepository::new(pool, config.redis.clone())))
+        .with_config(config))
+    }
+
+    /// Builds `Data` around arbitrary data sources, e.g. the in-memory fakes.
+    /// Bot-owned stores start out in memory; swap them with the `with_*` methods.
+    pub fn with_repositories(maps: Arc<dyn MapRepository>, mods: Arc<dyn ModRepository>) -> Self {
+        Self {
+            config: Arc::new(Config::default()),
             mod_cache: Arc::new(RwLock::new(HashMap::new())),
-            redis_pool: Arc::new(pool),
-        })
+            authors: Arc::new(RwLock::new(AuthorIndex::default())),
+            tags: Arc::new(RwLock::new(TagIndex::default())),
+            dead_links: Arc::new(RwLock::new(HashSet::new())),
+            map_slugs: Arc::new(RwLock::new(HashMap::new())),
+            map_list: Arc::new(RwLock::new(Vec::new())),
+            mention_channel_ids: Arc::new(RwLock::new(HashSet::new())),
+            mention_cooldowns: Arc::new(std::sync::Mutex::new(Cooldowns::default())),
+            rate_limiter: Arc::new(std::sync::Mutex::new(RateLimiter::default())),
+            watch_check_lock: Arc::new(tokio::sync::Mutex::new(())),
+            invocations: Arc::new(std::sync::Mutex::new(Invocations::default())),
+            maps,
+            mods,
+            favorites: Arc::new(InMemoryFavoritesRepository::new()),
+            watches: Arc::new(InMemoryWatchRepository::new()),
+            motw: Arc::new(InMemoryMotwRepository::new()),
+            link_health: Arc::new(InMemoryLinkHealthRepository::new()),
+            stats: Arc::new(InMemoryStatsRepository::new()),
+            digests: Arc::new(InMemoryDigestRepository::new()),
+            mention_channels: Arc::new(InMemoryMentionChannelRepository::new()),
+            analytics: Arc::new(InMemoryAnalyticsRepository::new()),
+            failed_searches: Arc::new(InMemoryFailedSearchRepository::new()),
+        }
+    }
+
+    pub fn with_favorites(mut self, favorites: Arc<dyn FavoritesRepository>) -> Self {
+        self.favorites = favorites;
+        self
+    }
+
+    pub fn with_watches(mut self, watches: Arc<dyn WatchRepository>) -> Self {
+        self.watches = watches;
+        self
+    }
+
+    pub fn with_motw(mut self, motw: Arc<dyn MotwRepository>) -> Self {
+        self.motw = motw;
+        self
+    }
+
+    pub fn with_link_health(mut self, link_health: Arc<dyn LinkHealthRepository>) -> Self {
+        self.link_health = link_health;
+        self
+    }
+
+    pub fn with_stats(mut self, stats: Arc<dyn StatsRepository>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    pub fn with_digests(mut self, digests: Arc<dyn DigestRepository>) -> Self {
+        self.digests = digests;
+        self
+    }
+
+    pub fn with_mention_channels(mut self, mention_channels: Arc<dyn MentionChannelRepository>) -> Self {
+        self.mention_channels = mention_channels;
+        self
+    }
+
+    pub fn with_analytics(mut self, analytics: Arc<dyn AnalyticsRepository>) -> Self {
+        self.analytics = analytics;
+        self
+    }
+
+    pub fn with_failed_searches(mut self, failed_searches: Arc<dyn FailedSearchRepository>) -> Self {
+        self.failed_searches = failed_searches;
+        self
+    }
+
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = Arc::new(config);
+        self
     }
 }
 
@@ -0,0 +1,183 @@
+use crate::{
+    mod_utils,
+    types::{ApiModioMap, Data, Error, FavoriteItem, ModEntry, BOT_EMBED_COLOR},
+};
+use poise::serenity_prelude as serenity;
+use std::collections::HashMap;
+use tracing::{error, info, warn};
+
+/// An update to a watched item, with everyone who should hear about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchNotification {
+    pub item: FavoriteItem,
+    pub name: String,
+    pub url: Option<String>,
+    pub details: String,
+    pub watchers: Vec<u64>,
+    /// The item's new state, stored by [`mark_notified`] once the watchers were messaged.
+    pub fingerprint: String,
+}
+
+/// The current state of a watched item, as far as update detection cares.
+struct Snapshot {
+    fingerprint: String,
+    name: String,
+    url: Option<String>,
+}
+
+/// Changes whenever a new file is uploaded or the mod.io entry is edited.
+pub fn map_fingerprint(map: &ApiModioMap) -> String {
+    format!("{}:{}", map.modfile.as_ref().map_or(0, |file| file.id), map.date_updated)
+}
+
+/// The community list only tracks which game version a mod works on, so that is what we compare.
+pub fn mod_fingerprint(entry: &ModEntry) -> String {
+    entry.working_version.clone().unwrap_or_default()
+}
+
+fn map_snapshot(map: &ApiModioMap) -> Snapshot {
+    Snapshot { fingerprint: map_fingerprint(map), name: map.name.clone(), url: Some(map.profile_url.clone()) }
+}
+
+fn mod_snapshot(mod_cache: &HashMap<String, Vec<ModEntry>>, item: &FavoriteItem) -> Option<Snapshot> {
+    let FavoriteItem::Mod { title, branch } = item else { return None };
+    let entry = mod_cache
+        .get(mod_utils::resolve_version_slug(*branch))?
+        .iter()
+        .find(|entry| entry.title.eq_ignore_ascii_case(title))?;
+    Some(Snapshot { fingerprint: mod_fingerprint(entry), name: format!("{} ({})", entry.title, branch), url: None })
+}
+
+fn describe_change(item: &FavoriteItem, previous: &str, snapshot: &Snapshot, map: Option<&ApiModioMap>) -> String {
+    match item {
+        FavoriteItem::Map { .. } => {
+            let file_version = map.and_then(|m| m.modfile.as_ref()).and_then(|file| file.version.clone());
+            let updated = map.map(|m| format!(" <t:{}:R>", m.date_updated)).unwrap_or_default();
+            match file_version {
+                Some(version) => format!("New file version **{}** uploaded{}.", version, updated),
+                None => format!("The map was updated{}.", updated),
+            }
+        }
+        FavoriteItem::Mod { .. } => {
+            let or_unknown = |v: &str| if v.is_empty() { "unknown".to_string() } else { v.to_string() };
+            format!("Working version changed: **{}** → **{}**.", or_unknown(previous), or_unknown(&snapshot.fingerprint))
+        }
+    }
+}
+
+/// Remembers the item's current state so a later check only reports changes made after the user started watching.
+pub async fn record_baseline(data: &Data, item: &FavoriteItem) -> Result<(), Error> {
+    if data.watches.last_seen(item).await?.is_some() {
+        return Ok(());
+    }
+    let snapshot = match item {
+        FavoriteItem::Map { id } => data.maps.get_by_id(*id).await?.as_ref().map(map_snapshot),
+        FavoriteItem::Mod { .. } => mod_snapshot(&*data.mod_cache.read().await, item),
+    };
+    if let Some(snapshot) = snapshot {
+        data.watches.set_last_seen(item, &snapshot.fingerprint).await?;
+    }
+    Ok(())
+}
+
+/// Compares every watched item against its last seen state.
+/// Items seen for the first time only get a baseline; items that disappeared are left alone.
+/// Changed items keep their old state until [`mark_notified`], so an update isn't lost if sending fails midway.
+pub async fn find_updates(data: &Data) -> Result<Vec<WatchNotification>, Error> {
+    let items = data.watches.watched_items().await?;
+    let map_ids: Vec<i32> = items
+        .iter()
+        .filter_map(|item| match item {
+            FavoriteItem::Map { id } => Some(*id),
+            FavoriteItem::Mod { .. } => None,
+        })
+        .collect();
+    let maps: HashMap<i32, ApiModioMap> = data
+        .maps
+        .get_many(&map_ids)
+        .await?
+        .into_iter()
+        .flatten()
+        .map(|map| (map.id, map))
+        .collect();
+    let mod_cache = data.mod_cache.read().await.clone();
+
+    let mut notifications = Vec::new();
+    for item in items {
+        let map = match &item {
+            FavoriteItem::Map { id } => maps.get(id),
+            FavoriteItem::Mod { .. } => None,
+        };
+        let snapshot = match &item {
+            FavoriteItem::Map { .. } => map.map(map_snapshot),
+            FavoriteItem::Mod { .. } => mod_snapshot(&mod_cache, &item),
+        };
+        let Some(snapshot) = snapshot else { continue };
+
+        match data.watches.last_seen(&item).await? {
+            Some(previous) if previous == snapshot.fingerprint => continue,
+            Some(previous) => {
+                let watchers = data.watches.watchers(&item).await?;
+                notifications.push(WatchNotification {
+                    details: describe_change(&item, &previous, &snapshot, map),
+                    item: item.clone(),
+                    name: snapshot.name.clone(),
+                    url: snapshot.url.clone(),
+                    watchers,
+                    fingerprint: snapshot.fingerprint,
+                });
+            }
+            None => data.watches.set_last_seen(&item, &snapshot.fingerprint).await?,
+        }
+    }
+    Ok(notifications)
+}
+
+/// Records that the watchers of an update were messaged, so the next check doesn't report it again.
+pub async fn mark_notified(data: &Data, notification: &WatchNotification) -> Result<(), Error> {
+    data.watches.set_last_seen(&notification.item, &notification.fingerprint).await
+}
+
+pub fn build_update_embed(notification: &WatchNotification) -> serenity::CreateEmbed {
+    let mut embed = serenity::CreateEmbed::default()
+        .title(format!("🔔 {} was updated", notification.name))
+        .description(&notification.details)
+        .color(BOT_EMBED_COLOR)
+        .footer(serenity::CreateEmbedFooter::new("You're watching this · Stop with /watch remove"))
+        .timestamp(serenity::Timestamp::now());
+    if let Some(url) = &notification.url {
+        embed = embed.url(url);
+    }
+    embed
+}
+
+/// Checks watched items and DMs their watchers. Users with closed DMs are skipped.
+/// Overlapping checks wait for each other, so an update is never reported twice.
+pub async fn run_watch_check(data: &Data, http: &serenity::Http) {
+    if !data.config.features.watch_notifications {
+        return;
+    }
+    let _running = data.watch_check_lock.lock().await;
+    let notifications = match find_updates(data).await {
+        Ok(notifications) => notifications,
+        Err(e) => {
+            error!("Watch check failed: {}", e);
+            return;
+        }
+    };
+
+    let mut sent = 0;
+    for notification in &notifications {
+        let message = serenity::CreateMessage::new().embed(build_update_embed(notification));
+        for watcher in &notification.watchers {
+            match serenity::UserId::new(*watcher).direct_message(http, message.clone()).await {
+                Ok(_) => sent += 1,
+                Err(e) => warn!(user_id = watcher, item = %notification.item.key(), "Failed to DM watch notification: {}", e),
+            }
+        }
+        if let Err(e) = mark_notified(data, notification).await {
+            error!(item = %notification.item.key(), "Failed to store watch state after notifying, it may be reported again: {}", e);
+        }
+    }
+    info!(updates = notifications.len(), sent, "Watch check finished.");
+}
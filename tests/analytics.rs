@@ -0,0 +1,154 @@
+mod common;
+
+use common::{embed_field, embed_json, redis_stub::RedisStub};
+use skatebit_bot::{
+    analytics::{self, Invocations, UsageWindow},
+    config::RedisConfig,
+    repository::{AnalyticsRepository, InMemoryAnalyticsRepository, RedisAnalyticsRepository},
+    types::{CommandEvent, UsageTotals},
+};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const NOW: i64 = 1_700_000_000;
+const DAY: i64 = 86_400;
+
+fn event(command: &str, guild_id: Option<u64>, at: i64) -> CommandEvent {
+    CommandEvent {
+        command: command.to_string(),
+        guild_id,
+        success: true,
+        latency_ms: 100,
+        map_ids: Vec::new(),
+        mod_titles: Vec::new(),
+        failed_query: None,
+        at,
+    }
+}
+
+/// A week of traffic: map searches in two servers, a failed mod lookup and a DM from last month.
+fn sample_events() -> Vec<CommandEvent> {
+    let mut found = event("map", Some(1), NOW);
+    found.map_ids = vec![42];
+    let mut again = event("map", Some(2), NOW - DAY);
+    again.map_ids = vec![42];
+    again.latency_ms = 300;
+    let mut not_found = event("mod", Some(1), NOW - 2 * DAY);
+    not_found.failed_query = Some("xxl mod".to_string());
+    let mut errored = event("maps trend", Some(1), NOW);
+    errored.success = false;
+    let mut old = event("mod", None, NOW - 20 * DAY);
+    old.mod_titles = vec!["XXL Mod".to_string()];
+    vec![found, again, not_found, errored, old]
+}
+
+async fn assert_totals_by_window(repo: &dyn AnalyticsRepository) {
+    for event in sample_events() {
+        repo.record(&event).await.unwrap();
+    }
+
+    let today = repo.totals(&UsageWindow::Today.days(NOW)).await.unwrap();
+    assert_eq!(today.commands, HashMap::from([("map".to_string(), 1), ("maps trend".to_string(), 1)]));
+    assert_eq!(today.failures, HashMap::from([("maps trend".to_string(), 1)]));
+
+    let week = repo.totals(&UsageWindow::Week.days(NOW)).await.unwrap();
+    assert_eq!(week.commands["map"], 2);
+    assert_eq!(week.latency_ms["map"], 400);
+    assert_eq!(week.maps, HashMap::from([(42, 2)]));
+    assert_eq!(week.failed_searches, HashMap::from([("xxl mod".to_string(), 1)]));
+    assert_eq!(week.guilds, HashMap::from([("1".to_string(), 3), ("2".to_string(), 1)]));
+    assert!(week.mods.is_empty());
+
+    let month = repo.totals(&UsageWindow::Month.days(NOW)).await.unwrap();
+    assert_eq!(month.mods, HashMap::from([("XXL Mod".to_string(), 1)]));
+    assert_eq!(month.guilds["dm"], 1);
+}
+
+#[tokio::test]
+async fn in_memory_totals_follow_the_window() {
+    assert_totals_by_window(&InMemoryAnalyticsRepository::new()).await;
+}
+
+#[tokio::test]
+async fn redis_totals_follow_the_window_and_counters_expire() {
+    let redis = RedisStub::start().await;
+    let repo = RedisAnalyticsRepository::new(redis.pool(), RedisConfig::default());
+    assert_totals_by_window(&repo).await;
+
+    let key = format!("bot:analytics:{}:commands", analytics::day_of(NOW));
+    assert_eq!(redis.hgetall(&key).get("map").map(String::as_str), Some("1"));
+    assert_eq!(redis.ttl(&key), Some(analytics::ANALYTICS_RETENTION_DAYS * DAY));
+}
+
+#[test]
+fn windows_cover_whole_utc_days_ending_today() {
+    let today = analytics::day_of(NOW);
+    assert_eq!(UsageWindow::Today.days(NOW), vec![today]);
+    assert_eq!(UsageWindow::Week.days(NOW), (today - 6..=today).rev().collect::<Vec<_>>());
+    assert_eq!(UsageWindow::Month.days(NOW).len(), 30);
+    assert_eq!(analytics::day_of(-1), -1);
+}
+
+#[test]
+fn latency_stops_at_the_first_reply() {
+    let mut invocations = Invocations::default();
+    let start = Instant::now();
+    invocations.start(7, start);
+    invocations.replied(7, start + Duration::from_millis(250));
+    invocations.replied(7, start + Duration::from_secs(60));
+    invocations.resolved_map(7, 42);
+    invocations.resolved_map(7, 42);
+    invocations.failed_search(7, "  Berlin   PLAZA ");
+
+    let finished = invocations.finish(7).unwrap();
+    assert_eq!(finished.latency_ms(start + Duration::from_secs(120)), 250);
+    assert_eq!(finished.map_ids, vec![42]);
+    assert_eq!(finished.failed_query.as_deref(), Some("berlin plaza"));
+    assert!(invocations.finish(7).is_none());
+}
+
+#[test]
+fn replies_outside_an_invocation_are_ignored() {
+    let mut invocations = Invocations::default();
+    let start = Instant::now();
+    invocations.replied(9, start);
+    invocations.resolved_mod(9, "XXL Mod");
+    assert!(invocations.finish(9).is_none());
+
+    invocations.start(9, start);
+    assert_eq!(invocations.finish(9).unwrap().latency_ms(start + Duration::from_millis(80)), 80);
+}
+
+#[test]
+fn top_ranks_by_count_then_key() {
+    let counts = HashMap::from([("b".to_string(), 3), ("a".to_string(), 3), ("c".to_string(), 5), ("d".to_string(), 1)]);
+    assert_eq!(analytics::top(&counts, 3), vec![("c".to_string(), 5), ("a".to_string(), 3), ("b".to_string(), 3)]);
+}
+
+#[test]
+fn usage_report_names_maps_and_servers() {
+    let mut totals = UsageTotals::default();
+    for event in sample_events() {
+        totals.add(&event);
+    }
+    let map_names = HashMap::from([(42, "Berlin Plaza".to_string())]);
+    let guild_names = HashMap::from([(1, "Skate Crew".to_string())]);
+    let embed = embed_json(&analytics::build_usage_embed(UsageWindow::Month, &totals, &map_names, &guild_names));
+
+    assert_eq!(embed["title"], "📊 Command Usage · Last 30 days");
+    assert!(embed["description"].as_str().unwrap().starts_with("**5** commands · **1** failed · **1** empty searches · **2** servers"));
+    let commands = embed_field(&embed, "Top commands").unwrap();
+    assert!(commands.starts_with("`/map` · 2 · ⏱️ 200 ms avg"), "{}", commands);
+    assert!(commands.contains("`/maps trend` · 1 · ⚠️ 1 failed"), "{}", commands);
+    assert_eq!(embed_field(&embed, "Top maps"), Some("Berlin Plaza · 2"));
+    assert_eq!(embed_field(&embed, "Top mods"), Some("XXL Mod · 1"));
+    assert_eq!(embed_field(&embed, "Failed searches"), Some("`xxl mod` · 1"));
+    assert_eq!(embed_field(&embed, "Servers"), Some("Skate Crew · 3\n2 · 1\nDirect messages · 1"));
+}
+
+#[test]
+fn usage_report_without_traffic_says_so() {
+    let embed = embed_json(&analytics::build_usage_embed(UsageWindow::Today, &UsageTotals::default(), &HashMap::new(), &HashMap::new()));
+    assert_eq!(embed["description"], "No commands were recorded in this window.");
+    assert!(embed["fields"].as_array().is_none_or(|fields| fields.is_empty()));
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::{embed_field, embed_json, map};
+use skatebit_bot::{
+    authors::{author_field_value, author_stats, build_author_embed, sort_author_maps, AuthorIndex},
+    components::{map_action_rows, ComponentAction},
+    map_cache,
+    mod_utils::format_count,
+    repository::{InMemoryMapRepository, InMemoryModRepository},
+    types::{ApiModioMap, Data},
+};
+use std::sync::Arc;
+
+fn by(id: i32, name: &str, author_id: i32, author: &str, downloads: i32, date_live: i64) -> ApiModioMap {
+    let mut entry = map(id, name, &["Map"]);
+    entry.submitted_by.id = author_id;
+    entry.submitted_by.username = author.to_string();
+    entry.submitted_by.profile_url = Some(format!("https://mod.io/u/{}", author.to_lowercase()));
+    entry.stats.downloads_total = downloads;
+    entry.stats.subscribers_total = downloads / 2;
+    entry.date_live = date_live;
+    entry
+}
+
+fn sample() -> Vec<ApiModioMap> {
+    vec![
+        by(1, "Berlin Plaza", 7, "Kiwi", 1_500, 100),
+        by(2, "Brooklyn Banks", 7, "Kiwi", 12_000, 300),
+        by(3, "Quiet Park", 8, "Mcbtay", 40, 200),
+        by(4, "Kiwi's Script", 7, "Kiwi", 99, 400),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, mut m)| {
+        if i == 3 {
+            m.tags = Some(Vec::new()); // not a map
+        }
+        m
+    })
+    .collect()
+}
+
+#[test]
+fn counts_get_thousands_separators() {
+    assert_eq!(format_count(0), "0");
+    assert_eq!(format_count(999), "999");
+    assert_eq!(format_count(1_000), "1,000");
+    assert_eq!(format_count(1_234_567), "1,234,567");
+    assert_eq!(format_count(-12_000), "-12,000");
+}
+
+#[test]
+fn index_groups_maps_by_submitter() {
+    let index = AuthorIndex::from_maps(&sample());
+    assert_eq!(index.len(), 2);
+    assert_eq!(index.get(7).unwrap().map_ids, [1, 2]);
+    assert_eq!(index.find_by_name(" kiwi ").unwrap().id, 7);
+    assert_eq!(index.resolve("8").unwrap().username, "Mcbtay");
+    assert_eq!(index.resolve("mcbtay").unwrap().id, 8);
+    assert!(index.resolve("nobody").is_none());
+
+    let names: Vec<&str> = index.suggestions("", 25).iter().map(|a| a.username.as_str()).collect();
+    assert_eq!(names, ["Kiwi", "Mcbtay"], "most maps first");
+    assert_eq!(index.suggestions("MCB", 25).len(), 1);
+}
+
+#[test]
+fn profile_totals_and_latest_release() {
+    let mut maps: Vec<ApiModioMap> = sample().into_iter().filter(|m| m.submitted_by.id == 7 && m.id != 4).collect();
+    sort_author_maps(&mut maps);
+    assert_eq!(maps.iter().map(|m| m.id).collect::<Vec<_>>(), [2, 1]);
+
+    let stats = author_stats(&maps);
+    assert_eq!((stats.map_count, stats.downloads, stats.subscribers), (2, 13_500, 6_750));
+    assert_eq!(stats.latest.map(|m| m.id), Some(2));
+
+    let index = AuthorIndex::from_maps(&sample());
+    let embed = embed_json(&build_author_embed(index.get(7).unwrap(), &maps, 0));
+    assert_eq!(embed["title"], "🛹 Maps by Kiwi");
+    assert_eq!(embed["url"], "https://mod.io/u/kiwi");
+    assert_eq!(embed_field(&embed, "Downloads"), Some("13,500"));
+    assert_eq!(embed_field(&embed, "Latest Release"), Some("[Brooklyn Banks](https://mod.io/g/skaterxl/m/brooklyn-banks) · <t:300:D>"));
+    assert!(embed["description"].as_str().unwrap().starts_with("[Brooklyn Banks](https://mod.io/g/skaterxl/m/brooklyn-banks) · ⬇️ 12,000 · 👥 6,000"));
+}
+
+#[tokio::test]
+async fn refresh_builds_the_index_from_the_store() {
+    let data = Data::with_repositories(Arc::new(InMemoryMapRepository::with_maps(sample())), Arc::new(InMemoryModRepository::new()));
+    map_cache::refresh_map_indexes(&data).await;
+    assert_eq!(data.authors.read().await.len(), 2);
+    assert_eq!(data.authors.read().await.get(8).unwrap().map_ids, [3]);
+}
+
+#[test]
+fn map_buttons_link_to_the_author_profile() {
+    let entry = by(1, "Berlin Plaza", 7, "Kiwi", 1, 1);
+    let rows = serde_json::to_value(map_action_rows(&entry)).unwrap();
+    let buttons = rows[0]["components"].as_array().unwrap();
+    assert_eq!(buttons.len(), 3);
+    assert_eq!(buttons[2]["custom_id"], "author:7");
+    assert_eq!(buttons[2]["label"], "More by Kiwi");
+    assert_eq!(ComponentAction::parse("author:7"), Some(ComponentAction::AuthorProfile { author_id: 7 }));
+}
+
+#[test]
+fn author_field_mentions_the_author_command_once_registered() {
+    let entry = by(1, "Berlin Plaza", 7, "Kiwi", 1, 1);
+    assert_eq!(author_field_value(&entry.submitted_by, Some(1234)), "Kiwi · </author:1234>");
+    assert_eq!(author_field_value(&entry.submitted_by, None), "[Kiwi](https://mod.io/u/kiwi)");
+}
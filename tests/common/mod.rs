@@ -0,0 +1,74 @@
+#![allow(dead_code)]
+
+pub mod redis_stub;
+
+use serde_json::{json, Value};
+use skatebit_bot::types::{ApiModioMap, ModEntry};
+
+pub const MODS_12104_JSON: &str = include_str!("../fixtures/mods_12104.json");
+
+pub fn fixture_mods_12104() -> Vec<ModEntry> {
+    serde_json::from_str(MODS_12104_JSON).expect("valid mod fixture")
+}
+
+pub fn mod_entry(title: &str) -> ModEntry {
+    serde_json::from_value(json!({ "title": title })).expect("valid mod entry")
+}
+
+/// A mod.io entry shaped like the JSON the Go API stores under `mod:{id}`.
+pub fn map_json(id: i32, name: &str, tags: &[&str]) -> Value {
+    let name_id = name.to_lowercase().replace(' ', "-");
+    json!({
+        "id": id,
+        "game_id": 629,
+        "name": name,
+        "name_id": name_id,
+        "summary": format!("{} summary", name),
+        "description_plaintext": format!("{} description", name),
+        "profile_url": format!("https://mod.io/g/skaterxl/m/{}", name_id),
+        "submitted_by": { "id": 7, "username": "builder", "profile_url": "https://mod.io/u/builder" },
+        "date_added": 1_700_000_000,
+        "date_updated": 1_700_100_000,
+        "date_live": 1_700_000_000,
+        "logo": {
+            "filename": "logo.png",
+            "original": "https://img.example/logo.png",
+            "thumb_320x180": null,
+            "thumb_640x360": null,
+            "thumb_1280x720": "https://img.example/logo_1280.png"
+        },
+        "modfile": {
+            "id": id * 10,
+            "filename": format!("{}.zip", name_id),
+            "version": "1.0",
+            "filesize": 52_428_800,
+            "download": { "binary_url": format!("https://mod.io/dl/{}", id), "date_expires": 1_900_000_000 }
+        },
+        "tags": tags.iter().map(|t| json!({ "name": t })).collect::<Vec<_>>(),
+        "stats": {
+            "downloads_total": 1000,
+            "subscribers_total": 500,
+            "ratings_positive": 90,
+            "ratings_negative": 10,
+            "ratings_display_text": "Very Positive"
+        },
+        "media": { "images": [] }
+    })
+}
+
+pub fn map(id: i32, name: &str, tags: &[&str]) -> ApiModioMap {
+    serde_json::from_value(map_json(id, name, tags)).expect("valid map fixture")
+}
+
+/// Serializes a `CreateEmbed` so tests can assert on its fields.
+pub fn embed_json(embed: &poise::serenity_prelude::CreateEmbed) -> Value {
+    serde_json::to_value(embed).expect("embed serializes")
+}
+
+pub fn embed_field<'a>(embed: &'a Value, name: &str) -> Option<&'a str> {
+    embed["fields"]
+        .as_array()?
+        .iter()
+        .find(|f| f["name"] == name)
+        .and_then(|f| f["value"].as_str())
+}
@@ -0,0 +1,340 @@
+//! A tiny in-process Redis speaking just enough RESP2 for the bot's commands.
+
+use deadpool_redis::{Config as DeadpoolRedisConfig, Pool, Runtime as DeadpoolRuntime};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+#[derive(Default)]
+struct State {
+    strings: HashMap<Vec<u8>, Vec<u8>>,
+    zsets: HashMap<Vec<u8>, Vec<(f64, Vec<u8>)>>,
+    sets: HashMap<Vec<u8>, BTreeSet<Vec<u8>>>,
+    hashes: HashMap<Vec<u8>, HashMap<Vec<u8>, Vec<u8>>>,
+    /// TTL in seconds per key, as last set by EXPIRE. Keys never actually expire.
+    expirations: HashMap<Vec<u8>, i64>,
+}
+
+impl State {
+    fn zadd(&mut self, key: &[u8], score: f64, member: &[u8]) -> i64 {
+        let zset = self.zsets.entry(key.to_vec()).or_default();
+        let existed = zset.iter().position(|(_, m)| m == member).map(|i| zset.remove(i)).is_some();
+        zset.push((score, member.to_vec()));
+        zset.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+        if existed { 0 } else { 1 }
+    }
+}
+
+enum Reply {
+    Status(&'static str),
+    Error(String),
+    Int(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Vec<Reply>),
+}
+
+impl Reply {
+    fn write_to(&self, out: &mut Vec<u8>) {
+        match self {
+            Reply::Status(s) => out.extend_from_slice(format!("+{}\r\n", s).as_bytes()),
+            Reply::Error(e) => out.extend_from_slice(format!("-{}\r\n", e).as_bytes()),
+            Reply::Int(i) => out.extend_from_slice(format!(":{}\r\n", i).as_bytes()),
+            Reply::Bulk(None) => out.extend_from_slice(b"$-1\r\n"),
+            Reply::Bulk(Some(b)) => {
+                out.extend_from_slice(format!("${}\r\n", b.len()).as_bytes());
+                out.extend_from_slice(b);
+                out.extend_from_slice(b"\r\n");
+            }
+            Reply::Array(items) => {
+                out.extend_from_slice(format!("*{}\r\n", items.len()).as_bytes());
+                for item in items {
+                    item.write_to(out);
+                }
+            }
+        }
+    }
+}
+
+fn bulk_array(items: impl IntoIterator<Item = Vec<u8>>) -> Reply {
+    Reply::Array(items.into_iter().map(|i| Reply::Bulk(Some(i))).collect())
+}
+
+fn text(arg: &[u8]) -> String {
+    String::from_utf8_lossy(arg).into_owned()
+}
+
+fn int_arg(arg: &[u8]) -> i64 {
+    text(arg).parse().unwrap_or(0)
+}
+
+/// Resolves Redis-style inclusive (possibly negative) indices against `len`.
+fn index_range(len: usize, start: i64, stop: i64) -> std::ops::Range<usize> {
+    let len = len as i64;
+    let norm = |i: i64| if i < 0 { len + i } else { i };
+    let (start, stop) = (norm(start).max(0), norm(stop).min(len - 1));
+    if start > stop || start >= len { 0..0 } else { start as usize..stop as usize + 1 }
+}
+
+fn lex_matches(member: &[u8], min: &[u8], max: &[u8]) -> bool {
+    let above_min = match min.first() {
+        Some(b'-') => true,
+        Some(b'[') => member >= &min[1..],
+        Some(b'(') => member > &min[1..],
+        _ => false,
+    };
+    let below_max = match max.first() {
+        Some(b'+') => true,
+        Some(b'[') => member <= &max[1..],
+        Some(b'(') => member < &max[1..],
+        _ => false,
+    };
+    above_min && below_max
+}
+
+/// Whether `score` lies within a Redis score bound pair like `-inf`, `(5` or `+inf`.
+fn score_matches(score: f64, min: &[u8], max: &[u8]) -> bool {
+    let bound = |arg: &[u8]| -> (f64, bool) {
+        let raw = text(arg);
+        let (value, exclusive) = match raw.strip_prefix('(') {
+            Some(rest) => (rest.to_string(), true),
+            None => (raw, false),
+        };
+        let value = match value.as_str() {
+            "-inf" => f64::NEG_INFINITY,
+            "+inf" | "inf" => f64::INFINITY,
+            other => other.parse().unwrap_or(0.0),
+        };
+        (value, exclusive)
+    };
+    let ((min, min_exclusive), (max, max_exclusive)) = (bound(min), bound(max));
+    let above_min = if min_exclusive { score > min } else { score >= min };
+    let below_max = if max_exclusive { score < max } else { score <= max };
+    above_min && below_max
+}
+
+fn execute(state: &mut State, args: &[Vec<u8>]) -> Reply {
+    let command = text(&args[0]).to_uppercase();
+    match (command.as_str(), &args[1..]) {
+        ("PING", _) => Reply::Status("PONG"),
+        ("CLIENT" | "SELECT", _) => Reply::Status("OK"),
+        ("GET", [key]) => Reply::Bulk(state.strings.get(key).cloned()),
+        ("SET", [key, value, options @ ..]) => {
+            let nx = options.iter().any(|o| text(o).eq_ignore_ascii_case("NX"));
+            if nx && state.strings.contains_key(key) {
+                return Reply::Bulk(None);
+            }
+            state.strings.insert(key.clone(), value.clone());
+            Reply::Status("OK")
+        }
+        ("MGET", keys) => Reply::Array(keys.iter().map(|k| Reply::Bulk(state.strings.get(k).cloned())).collect()),
+        ("DEL", keys) => {
+            let removed = keys
+                .iter()
+                .filter(|k| state.strings.remove(*k).is_some() | state.zsets.remove(*k).is_some() | state.sets.remove(*k).is_some() | state.hashes.remove(*k).is_some())
+                .count();
+            Reply::Int(removed as i64)
+        }
+        ("ZADD", [key, rest @ ..]) => {
+            let added = rest.chunks(2).map(|pair| state.zadd(key, text(&pair[0]).parse().unwrap_or(0.0), &pair[1])).sum();
+            Reply::Int(added)
+        }
+        ("ZRANGE", [key, start, stop, ..]) => {
+            let zset = state.zsets.get(key).cloned().unwrap_or_default();
+            let range = index_range(zset.len(), int_arg(start), int_arg(stop));
+            bulk_array(zset[range].iter().map(|(_, m)| m.clone()))
+        }
+        ("ZINCRBY", [key, by, member]) => {
+            let score = state.zsets.get(key).and_then(|z| z.iter().find(|(_, m)| m == member)).map_or(0.0, |(s, _)| *s);
+            let incremented = score + text(by).parse::<f64>().unwrap_or(0.0);
+            state.zadd(key, incremented, member);
+            Reply::Bulk(Some(incremented.to_string().into_bytes()))
+        }
+        ("ZREVRANGE", [key, start, stop, options @ ..]) => {
+            let mut zset = state.zsets.get(key).cloned().unwrap_or_default();
+            zset.reverse();
+            let range = index_range(zset.len(), int_arg(start), int_arg(stop));
+            let with_scores = options.iter().any(|o| text(o).eq_ignore_ascii_case("WITHSCORES"));
+            let items = zset[range].iter().flat_map(|(s, m)| if with_scores { vec![m.clone(), s.to_string().into_bytes()] } else { vec![m.clone()] }).collect::<Vec<_>>();
+            bulk_array(items)
+        }
+        ("ZREMRANGEBYRANK", [key, start, stop]) => {
+            let zset = state.zsets.entry(key.clone()).or_default();
+            let range = index_range(zset.len(), int_arg(start), int_arg(stop));
+            let removed = zset.drain(range).count();
+            Reply::Int(removed as i64)
+        }
+        ("ZRANGEBYLEX", [key, min, max, rest @ ..]) => {
+            let zset = state.zsets.get(key).cloned().unwrap_or_default();
+            let (offset, count) = match rest {
+                [limit, offset, count] if text(limit).eq_ignore_ascii_case("LIMIT") => (int_arg(offset) as usize, int_arg(count)),
+                _ => (0, -1),
+            };
+            let matching = zset.into_iter().map(|(_, m)| m).filter(|m| lex_matches(m, min, max)).skip(offset);
+            if count < 0 { bulk_array(matching) } else { bulk_array(matching.take(count as usize)) }
+        }
+        ("ZRANGEBYSCORE", [key, min, max, rest @ ..]) => {
+            let zset = state.zsets.get(key).cloned().unwrap_or_default();
+            let (offset, count) = match rest {
+                [limit, offset, count] if text(limit).eq_ignore_ascii_case("LIMIT") => (int_arg(offset) as usize, int_arg(count)),
+                _ => (0, -1),
+            };
+            let matching = zset.into_iter().filter(|(s, _)| score_matches(*s, min, max)).map(|(_, m)| m).skip(offset);
+            if count < 0 { bulk_array(matching) } else { bulk_array(matching.take(count as usize)) }
+        }
+        ("ZREMRANGEBYSCORE", [key, min, max]) => {
+            let zset = state.zsets.entry(key.clone()).or_default();
+            let before = zset.len();
+            zset.retain(|(s, _)| !score_matches(*s, min, max));
+            Reply::Int((before - zset.len()) as i64)
+        }
+        ("SADD", [key, members @ ..]) => {
+            let set = state.sets.entry(key.clone()).or_default();
+            Reply::Int(members.iter().filter(|m| set.insert(m.to_vec())).count() as i64)
+        }
+        ("SREM", [key, members @ ..]) => {
+            let set = state.sets.entry(key.clone()).or_default();
+            Reply::Int(members.iter().filter(|m| set.remove(*m)).count() as i64)
+        }
+        ("SMEMBERS", [key]) => bulk_array(state.sets.get(key).cloned().unwrap_or_default()),
+        ("SISMEMBER", [key, member]) => Reply::Int(state.sets.get(key).is_some_and(|s| s.contains(member)) as i64),
+        ("SCARD", [key]) => Reply::Int(state.sets.get(key).map_or(0, |s| s.len()) as i64),
+        ("HSET", [key, pairs @ ..]) => {
+            let hash = state.hashes.entry(key.clone()).or_default();
+            Reply::Int(pairs.chunks(2).filter(|pair| hash.insert(pair[0].clone(), pair[1].clone()).is_none()).count() as i64)
+        }
+        ("HGETALL", [key]) => {
+            let hash = state.hashes.get(key).cloned().unwrap_or_default();
+            bulk_array(hash.into_iter().flat_map(|(field, value)| [field, value]))
+        }
+        ("HGET", [key, field]) => Reply::Bulk(state.hashes.get(key).and_then(|h| h.get(field)).cloned()),
+        ("HINCRBY", [key, field, by]) => {
+            let value = state.hashes.entry(key.clone()).or_default().entry(field.clone()).or_insert_with(|| b"0".to_vec());
+            let incremented = int_arg(value) + int_arg(by);
+            *value = incremented.to_string().into_bytes();
+            Reply::Int(incremented)
+        }
+        ("EXPIRE", [key, secs]) => {
+            let exists = state.strings.contains_key(key) || state.zsets.contains_key(key) || state.sets.contains_key(key) || state.hashes.contains_key(key);
+            if exists {
+                state.expirations.insert(key.clone(), int_arg(secs));
+            }
+            Reply::Int(exists as i64)
+        }
+        ("HDEL", [key, fields @ ..]) => {
+            let hash = state.hashes.entry(key.clone()).or_default();
+            Reply::Int(fields.iter().filter(|f| hash.remove(*f).is_some()).count() as i64)
+        }
+        _ => Reply::Error(format!("ERR unknown command '{}'", command)),
+    }
+}
+
+async fn read_command(reader: &mut BufReader<TcpStream>) -> Option<Vec<Vec<u8>>> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.ok()?;
+    let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        line.clear();
+        reader.read_line(&mut line).await.ok()?;
+        let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+        let mut buf = vec![0; len + 2];
+        reader.read_exact(&mut buf).await.ok()?;
+        buf.truncate(len);
+        args.push(buf);
+    }
+    Some(args)
+}
+
+async fn serve(stream: TcpStream, state: Arc<Mutex<State>>) {
+    let mut reader = BufReader::new(stream);
+    // Commands sent between MULTI and EXEC on this connection.
+    let mut queued: Option<Vec<Vec<Vec<u8>>>> = None;
+    while let Some(args) = read_command(&mut reader).await {
+        if args.is_empty() {
+            continue;
+        }
+        let mut out = Vec::new();
+        let command = text(&args[0]).to_uppercase();
+        let reply = match (command.as_str(), queued.as_mut()) {
+            ("MULTI", _) => {
+                queued = Some(Vec::new());
+                Reply::Status("OK")
+            }
+            // Queued commands run back to back under one lock, like a real transaction.
+            ("EXEC", Some(_)) => {
+                let commands = queued.take().unwrap_or_default();
+                let mut state = state.lock().unwrap();
+                Reply::Array(commands.iter().map(|args| execute(&mut state, args)).collect())
+            }
+            ("DISCARD", Some(_)) => {
+                queued = None;
+                Reply::Status("OK")
+            }
+            (_, Some(commands)) => {
+                commands.push(args);
+                Reply::Status("QUEUED")
+            }
+            (_, None) => execute(&mut state.lock().unwrap(), &args),
+        };
+        reply.write_to(&mut out);
+        if reader.get_mut().write_all(&out).await.is_err() {
+            break;
+        }
+    }
+}
+
+pub struct RedisStub {
+    pub url: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl RedisStub {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind redis stub");
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(State::default()));
+        let accept_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, accept_state.clone()));
+            }
+        });
+        Self { url, state }
+    }
+
+    pub fn pool(&self) -> Pool {
+        DeadpoolRedisConfig::from_url(&self.url)
+            .create_pool(Some(DeadpoolRuntime::Tokio1))
+            .expect("create pool for redis stub")
+    }
+
+    pub fn set(&self, key: &str, value: &str) {
+        self.state.lock().unwrap().strings.insert(key.as_bytes().to_vec(), value.as_bytes().to_vec());
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.state.lock().unwrap().strings.get(key.as_bytes()).map(|v| text(v))
+    }
+
+    pub fn smembers(&self, key: &str) -> Vec<String> {
+        self.state.lock().unwrap().sets.get(key.as_bytes()).map(|s| s.iter().map(|m| text(m)).collect()).unwrap_or_default()
+    }
+
+    pub fn hgetall(&self, key: &str) -> HashMap<String, String> {
+        let state = self.state.lock().unwrap();
+        state.hashes.get(key.as_bytes()).map(|h| h.iter().map(|(f, v)| (text(f), text(v))).collect()).unwrap_or_default()
+    }
+
+    pub fn ttl(&self, key: &str) -> Option<i64> {
+        self.state.lock().unwrap().expirations.get(key.as_bytes()).copied()
+    }
+
+    pub fn zadd(&self, key: &str, score: f64, member: &str) {
+        self.state.lock().unwrap().zadd(key.as_bytes(), score, member.as_bytes());
+    }
+}
@@ -0,0 +1,135 @@
+use skatebit_bot::{
+    config::{next_cron_run, Config, DEFAULT_MOTW_CLOSE_CRON},
+    mod_utils::DEFAULT_MODS_API_BASE_URL,
+};
+use std::collections::HashMap;
+
+fn env_from(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    move |key| vars.get(key).cloned()
+}
+
+#[test]
+fn defaults_match_the_go_api_layout() {
+    let config = Config::from_sources(None, env_from(&[])).unwrap();
+    assert_eq!(config.api.mods_base_url, DEFAULT_MODS_API_BASE_URL);
+    assert_eq!(config.redis.mod_key(42), "mod:42");
+    assert_eq!(config.redis.title_index_key("Map"), "mod_titles:map");
+}
+
+#[test]
+fn toml_file_sets_upstream_and_key_schema() {
+    let toml = r#"
+        [api]
+        mods_base_url = "https://staging.skatebit.example"
+
+        [redis]
+        namespace = "staging"
+        mod_key_prefix = "modio:"
+    "#;
+    let config = Config::from_sources(Some(toml), env_from(&[])).unwrap();
+
+    assert_eq!(config.api.mods_base_url, "https://staging.skatebit.example");
+    assert_eq!(config.redis.mod_key(1), "staging:modio:1");
+    assert_eq!(config.redis.title_index_key("Script"), "staging:mod_titles:script");
+}
+
+#[test]
+fn env_overrides_file_values() {
+    let toml = "[redis]\nnamespace = \"staging\"\n";
+    let env = env_from(&[("REDIS_NAMESPACE", "prod"), ("MODS_API_BASE_URL", "http://localhost:3000")]);
+    let config = Config::from_sources(Some(toml), env).unwrap();
+
+    assert_eq!(config.redis.mod_key(1), "prod:mod:1");
+    assert_eq!(config.api.mods_base_url, "http://localhost:3000");
+}
+
+#[test]
+fn unknown_keys_are_rejected() {
+    let err = Config::from_sources(Some("[redis]\nnamspace = \"typo\"\n"), env_from(&[])).unwrap_err();
+    assert!(format!("{:#}", err).contains("namspace"));
+}
+
+fn valid_env() -> impl Fn(&str) -> Option<String> {
+    env_from(&[("DISCORD_TOKEN", "super-secret-token")])
+}
+
+#[test]
+fn discord_settings_come_from_file_and_env() {
+    let toml = "[discord]\nowners = [1, 2]\ndev_guild = 99\n";
+    let env = env_from(&[("DISCORD_TOKEN", "abc"), ("BOT_OWNERS", "3, 4"), ("REDIS_URL", "redis://cache:6379")]);
+    let config = Config::from_sources(Some(toml), env).unwrap();
+
+    assert_eq!(config.discord.token.expose(), "abc");
+    assert_eq!(config.discord.owners, vec![3, 4]);
+    assert_eq!(config.discord.dev_guild, Some(99));
+    assert_eq!(config.redis.url, "redis://cache:6379");
+}
+
+#[test]
+fn malformed_env_ids_are_reported() {
+    let err = Config::from_sources(None, env_from(&[("BOT_OWNERS", "12,abc")])).unwrap_err();
+    assert!(err.to_string().contains("'abc'"));
+    assert!(Config::from_sources(None, env_from(&[("TEST_GUILD_ID", "guild")])).is_err());
+}
+
+#[test]
+fn validation_passes_for_defaults_with_token() {
+    Config::from_sources(None, valid_env()).unwrap().validate().unwrap();
+}
+
+#[test]
+fn validation_lists_every_problem() {
+    let toml = r#"
+        [api]
+        mods_base_url = "ftp://example.com"
+
+        [redis]
+        url = "localhost:6379"
+
+        [schedule]
+        mod_cache_refresh = "every six hours"
+    "#;
+    let err = Config::from_sources(Some(toml), env_from(&[])).unwrap().validate().unwrap_err().to_string();
+
+    assert!(err.contains("discord.token is required"), "{}", err);
+    assert!(err.contains("api.mods_base_url"), "{}", err);
+    assert!(err.contains("redis.url"), "{}", err);
+    assert!(err.contains("schedule.mod_cache_refresh"), "{}", err);
+}
+
+#[test]
+fn presence_rotation_interval_comes_from_env_and_has_a_floor() {
+    let config = Config::from_sources(Some("[presence]\nrotation_interval_secs = 120"), valid_env()).unwrap();
+    assert!(config.presence.enabled);
+    assert_eq!(config.presence.rotation_interval_secs, 120);
+
+    let env = env_from(&[("DISCORD_TOKEN", "token"), ("PRESENCE_ROTATION_SECS", "5")]);
+    let err = Config::from_sources(None, env).unwrap().validate().unwrap_err().to_string();
+    assert!(err.contains("presence.rotation_interval_secs must be at least 30"), "{}", err);
+
+    let env = env_from(&[("PRESENCE_ROTATION_SECS", "five minutes")]);
+    assert!(Config::from_sources(None, env).is_err());
+}
+
+#[test]
+fn redacted_output_hides_token_and_redis_password() {
+    let env = env_from(&[("DISCORD_TOKEN", "super-secret-token"), ("REDIS_URL", "redis://:hunter2@cache:6379")]);
+    let config = Config::from_sources(None, env).unwrap();
+    let rendered = config.to_redacted_toml().unwrap();
+
+    assert!(!rendered.contains("super-secret-token"));
+    assert!(!rendered.contains("hunter2"));
+    assert!(rendered.contains("redis://:%3Credacted%3E@cache:6379"), "{}", rendered);
+    assert!(rendered.contains("[features]"));
+    assert!(!format!("{:?}", config).contains("super-secret-token"));
+    assert!(!format!("{:?}", config).contains("hunter2"));
+}
+
+#[test]
+fn next_cron_run_finds_the_following_occurrence() {
+    // Tuesday 2023-11-14 22:13:20 UTC -> Sunday 2023-11-19 18:00:00 UTC
+    assert_eq!(next_cron_run(DEFAULT_MOTW_CLOSE_CRON, 1_700_000_000), Some(1_700_416_800));
+    assert_eq!(next_cron_run(DEFAULT_MOTW_CLOSE_CRON, 1_700_416_800), Some(1_700_416_800 + 7 * 86_400));
+    assert_eq!(next_cron_run("not a cron", 1_700_000_000), None);
+}
@@ -0,0 +1,208 @@
+mod common;
+
+use common::{embed_field, embed_json, map, redis_stub::RedisStub};
+use serde_json::json;
+use skatebit_bot::{
+    config::RedisConfig,
+    digest::{self, build_digest_embed, build_report, is_due, merge_mod_snapshot, mod_changes, mod_snapshot, validate_digest_cron, ModChange},
+    map_cache,
+    repository::{DigestRepository, InMemoryMapRepository, InMemoryModRepository, InMemoryStatsRepository, RedisDigestRepository, RedisStatsRepository, StatsRepository},
+    stats::{self, download_gains, snapshot_of},
+    types::{ApiModioMap, Data, DigestSubscription, ModEntry, ModVersionBranch, StatsSnapshot},
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+const NOW: i64 = 1_700_600_000;
+const WEEK: i64 = 7 * 86_400;
+
+fn mod_entry(title: &str, version: &str) -> ModEntry {
+    serde_json::from_value(json!({ "title": title, "workingVersion": version })).unwrap()
+}
+
+fn with_downloads(mut entry: ApiModioMap, downloads: i32) -> ApiModioMap {
+    entry.stats.downloads_total = downloads;
+    entry
+}
+
+fn snapshot(map_id: i32, taken_at: i64, downloads: i64) -> StatsSnapshot {
+    StatsSnapshot { map_id, taken_at, downloads, subscribers: 0, ratings_positive: 0, ratings_negative: 0 }
+}
+
+fn subscription(cron: &str) -> DigestSubscription {
+    DigestSubscription {
+        guild_id: 1,
+        channel_id: 2,
+        cron: cron.to_string(),
+        subscribed_at: NOW - WEEK,
+        last_posted_at: None,
+        last_attempt_at: None,
+        mods_seen: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn guild_schedules_must_not_post_more_than_daily() {
+    assert!(validate_digest_cron(digest::DEFAULT_DIGEST_CRON, NOW).is_ok());
+    assert!(validate_digest_cron("0 0 9 * * *", NOW).is_ok());
+    assert!(validate_digest_cron("0 0 * * * *", NOW).is_err(), "hourly is too often");
+    assert!(validate_digest_cron("0 0 9,10 * * Mon", NOW).is_err(), "two runs an hour apart");
+    assert!(validate_digest_cron("not a cron", NOW).is_err());
+}
+
+#[test]
+fn digest_is_due_once_per_scheduled_run() {
+    let mut sub = subscription("0 0 9 * * *");
+    assert!(is_due(&sub, NOW), "a run fell between subscribing and now");
+
+    sub.last_posted_at = Some(NOW - 60);
+    assert!(!is_due(&sub, NOW));
+    assert!(is_due(&sub, NOW + 86_400));
+
+    // A failed post waits for the next run instead of retrying, but keeps the period open.
+    sub.last_attempt_at = Some(NOW + 86_400);
+    assert!(!is_due(&sub, NOW + 86_400 + 60));
+    assert_eq!(digest::period_start(&sub, NOW + 86_400), NOW - 60);
+}
+
+#[test]
+fn mod_list_changes_are_reported_per_branch() {
+    let before = HashMap::from([
+        ("1228".to_string(), vec![mod_entry("XLGearModifier", "1.0"), mod_entry("Old Mod", "0.9")]),
+        ("12104".to_string(), vec![mod_entry("BonedOllieMod", "2.0")]),
+    ]);
+    let seen = mod_snapshot(&before);
+    let after = HashMap::from([
+        ("1228".to_string(), vec![mod_entry("XLGearModifier", "1.1"), mod_entry("New Mod", "")]),
+        ("12104".to_string(), vec![mod_entry("BonedOllieMod", "2.0")]),
+    ]);
+
+    let changes = mod_changes(&seen, &after);
+    assert_eq!(changes.len(), 1, "unchanged branches are left out");
+    let (branch, changes) = &changes[0];
+    assert_eq!(*branch, ModVersionBranch::Alpha);
+    assert_eq!(
+        changes,
+        &vec![
+            ModChange::VersionChanged { title: "XLGearModifier".into(), from: "1.0".into(), to: "1.1".into() },
+            ModChange::Added { title: "New Mod".into(), version: "unknown".into() },
+            ModChange::Removed { title: "Old Mod".into() },
+        ]
+    );
+}
+
+#[test]
+fn branches_missing_from_the_cache_keep_their_baseline() {
+    let before = HashMap::from([
+        ("1228".to_string(), vec![mod_entry("XLGearModifier", "1.0")]),
+        ("12104".to_string(), vec![mod_entry("BonedOllieMod", "2.0")]),
+    ]);
+    let seen = mod_snapshot(&before);
+    let partial = HashMap::from([("1228".to_string(), vec![mod_entry("XLGearModifier", "1.1")])]);
+
+    assert!(mod_changes(&seen, &partial).iter().all(|(branch, _)| *branch == ModVersionBranch::Alpha));
+    let expected = HashMap::from([
+        ("1228".to_string(), vec![mod_entry("XLGearModifier", "1.1")]),
+        ("12104".to_string(), vec![mod_entry("BonedOllieMod", "2.0")]),
+    ]);
+    assert_eq!(merge_mod_snapshot(&seen, &partial), mod_snapshot(&expected));
+}
+
+#[test]
+fn download_gains_rank_maps_with_a_baseline() {
+    let maps = vec![
+        with_downloads(map(1, "Berlin Plaza", &["Map"]), 1_500),
+        with_downloads(map(2, "Brooklyn Banks", &["Map"]), 900),
+        with_downloads(map(3, "No Baseline", &["Map"]), 5_000),
+        with_downloads(map(4, "Quiet Park", &["Map"]), 100),
+    ];
+    let baselines = HashMap::from([(1, snapshot(1, NOW, 1_000)), (2, snapshot(2, NOW, 100)), (4, snapshot(4, NOW, 100))]);
+
+    let gains = download_gains(&maps, &baselines);
+    let ranked: Vec<(i32, i64)> = gains.iter().map(|g| (g.map_id, g.gained)).collect();
+    assert_eq!(ranked, vec![(2, 800), (1, 500)]);
+}
+
+#[test]
+fn report_splits_new_and_updated_maps() {
+    let since = NOW - WEEK;
+    let mut fresh = map(1, "Fresh Spot", &["Map"]);
+    fresh.date_added = since + 100;
+    fresh.date_updated = since + 100;
+    let mut touched = map(2, "Old Spot", &["Map"]);
+    touched.date_added = since - WEEK;
+    touched.date_updated = since + 200;
+    let mut stale = map(3, "Stale Spot", &["Map"]);
+    stale.date_added = since - WEEK;
+    stale.date_updated = since - 10;
+    let mut script = map(4, "New Script", &["Script"]);
+    script.date_added = since + 100;
+
+    let report = build_report(&[fresh, touched, stale, script], &HashMap::new(), &HashMap::new(), &BTreeMap::new(), since, NOW);
+    assert_eq!(report.added.iter().map(|m| m.id).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(report.updated.iter().map(|m| m.id).collect::<Vec<_>>(), vec![2]);
+    assert!(!report.is_empty());
+
+    let embed = embed_json(&build_digest_embed(&report));
+    assert!(embed_field(&embed, "🆕 New maps (1)").unwrap().contains("Fresh Spot"));
+    assert!(embed_field(&embed, "🔄 Updated maps (1)").unwrap().contains("Old Spot"));
+}
+
+#[test]
+fn quiet_periods_still_produce_a_digest() {
+    let report = build_report(&[], &HashMap::new(), &HashMap::new(), &BTreeMap::new(), NOW - WEEK, NOW);
+    assert!(report.is_empty());
+    let embed = embed_json(&build_digest_embed(&report));
+    assert!(embed["description"].as_str().unwrap().contains("A quiet one"));
+    assert!(embed["fields"].as_array().is_none_or(|f| f.is_empty()));
+}
+
+#[tokio::test]
+async fn snapshots_feed_the_digest_gainers() {
+    let maps = Arc::new(InMemoryMapRepository::with_maps([with_downloads(map(1, "Berlin Plaza", &["Map"]), 100)]));
+    let data = Data::with_repositories(maps.clone(), Arc::new(InMemoryModRepository::new()));
+    assert_eq!(stats::take_snapshots(&data, NOW - WEEK + 3_600).await, 1);
+    maps.insert(with_downloads(map(1, "Berlin Plaza", &["Map"]), 350));
+    map_cache::refresh_map_indexes(&data).await;
+
+    let report = digest::compile_digest(&data, &subscription(digest::DEFAULT_DIGEST_CRON), NOW).await.unwrap();
+    assert_eq!(report.gainers.len(), 1);
+    assert_eq!(report.gainers[0].gained, 250);
+
+    let data = data.with_stats(Arc::new(InMemoryStatsRepository::new()));
+    stats::take_snapshots(&data, NOW - 86_400).await;
+    let report = digest::compile_digest(&data, &subscription(digest::DEFAULT_DIGEST_CRON), NOW).await.unwrap();
+    assert!(report.gainers.is_empty(), "a baseline from the last day of the week doesn't stand for the whole week");
+}
+
+#[tokio::test]
+async fn redis_repositories_round_trip() {
+    let redis = RedisStub::start().await;
+    let stats = RedisStatsRepository::new(redis.pool(), RedisConfig::default());
+    let berlin = map(1, "Berlin Plaza", &["Map"]);
+    for (i, downloads) in [100, 200, 300].into_iter().enumerate() {
+        let taken_at = NOW + i as i64 * 3_600;
+        stats.record(&[snapshot_of(&with_downloads(berlin.clone(), downloads), taken_at)]).await.unwrap();
+    }
+
+    let history = stats.history(1, NOW + 1).await.unwrap();
+    assert_eq!(history.iter().map(|s| s.downloads).collect::<Vec<_>>(), vec![200, 300]);
+    assert_eq!(redis.ttl("bot:stats:1"), Some(stats::STATS_RETENTION_SECS), "maps that leave the store stop refreshing their expiry");
+    let baselines = stats.baselines(&[1, 2], NOW + 1).await.unwrap();
+    assert_eq!(baselines.len(), 1);
+    assert_eq!(baselines[&1].downloads, 200);
+    stats.prune(&[1], NOW + 3_600).await.unwrap();
+    assert_eq!(stats.history(1, 0).await.unwrap().len(), 2);
+
+    let digests = RedisDigestRepository::new(redis.pool(), RedisConfig::default());
+    let mut sub = subscription(digest::DEFAULT_DIGEST_CRON);
+    sub.mods_seen.insert("mod:1228:XLGearModifier".into(), "1.0".into());
+    digests.save(&sub).await.unwrap();
+    assert_eq!(digests.get(1).await.unwrap(), Some(sub.clone()));
+    assert_eq!(digests.all().await.unwrap(), vec![sub]);
+    assert!(digests.remove(1).await.unwrap());
+    assert!(!digests.remove(1).await.unwrap());
+    assert_eq!(digests.get(1).await.unwrap(), None);
+}
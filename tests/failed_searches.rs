@@ -0,0 +1,98 @@
+mod common;
+
+use common::{embed_json, map, mod_entry, redis_stub::RedisStub};
+use skatebit_bot::{
+    config::RedisConfig,
+    failed_searches::{self, edit_distance, nearest_candidates, normalize_query, similarity},
+    map_cache,
+    repository::{FailedSearchRepository, InMemoryFailedSearchRepository, InMemoryMapRepository, InMemoryModRepository, RedisFailedSearchRepository},
+    types::{Data, FailedSearch, SearchTarget},
+};
+use std::sync::Arc;
+
+fn titles(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn queries_are_normalized_before_counting() {
+    assert_eq!(normalize_query("  Brooklyn-Banks!  "), "brooklyn banks");
+    assert_eq!(normalize_query("XXL   Mod"), "xxl mod");
+    assert_eq!(normalize_query("?!"), "");
+    assert_eq!(normalize_query(&"a".repeat(300)).len(), 100);
+}
+
+#[test]
+fn edit_distance_counts_characters() {
+    assert_eq!(edit_distance("brooklin", "brooklyn"), 1);
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("café", "cafe"), 1);
+}
+
+#[test]
+fn similarity_favors_typos_and_contained_queries() {
+    assert_eq!(similarity("berlin plaza", "Berlin Plaza"), 1.0);
+    assert!(similarity("brooklin banks", "Brooklyn Banks") > 0.9);
+    assert_eq!(similarity("xxl", "XXL Mod"), 0.8);
+    assert!(similarity("berlin", "Brooklyn Banks") < failed_searches::MIN_SIMILARITY);
+}
+
+#[test]
+fn nearest_candidates_rank_and_dedupe() {
+    let titles = titles(&["Brooklyn Banks", "brooklyn-banks", "Brooklyn Plaza", "Berlin Plaza", "Wall Ride"]);
+    let nearest = nearest_candidates("brooklin banks", titles.iter().map(String::as_str), 3);
+    assert_eq!(nearest, vec!["Brooklyn Banks".to_string(), "Brooklyn Plaza".to_string()]);
+    assert!(nearest_candidates("zzz", titles.iter().map(String::as_str), 3).is_empty());
+}
+
+async fn assert_top_queries(repo: &dyn FailedSearchRepository) {
+    for query in ["xxl mod", "blender", "xxl mod", "xxl mod", "blender", "walls"] {
+        repo.record(SearchTarget::Mods, query).await.unwrap();
+    }
+    repo.record(SearchTarget::Maps, "brooklin").await.unwrap();
+
+    let top = repo.top(SearchTarget::Mods, 2).await.unwrap();
+    assert_eq!(
+        top,
+        vec![FailedSearch { query: "xxl mod".to_string(), count: 3 }, FailedSearch { query: "blender".to_string(), count: 2 }]
+    );
+    assert_eq!(repo.top(SearchTarget::Maps, 10).await.unwrap(), vec![FailedSearch { query: "brooklin".to_string(), count: 1 }]);
+    assert!(repo.top(SearchTarget::Maps, 0).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn in_memory_counts_queries_per_target() {
+    assert_top_queries(&InMemoryFailedSearchRepository::new()).await;
+}
+
+#[tokio::test]
+async fn redis_counts_queries_per_target() {
+    let redis = RedisStub::start().await;
+    assert_top_queries(&RedisFailedSearchRepository::new(redis.pool(), RedisConfig::default())).await;
+}
+
+#[tokio::test]
+async fn candidates_come_from_maps_or_community_mods() {
+    let maps = Arc::new(InMemoryMapRepository::new());
+    maps.insert(map(1, "Brooklyn Banks", &["Map"]));
+    let data = Data::with_repositories(maps, Arc::new(InMemoryModRepository::new()));
+    data.mod_cache.write().await.insert("12104".to_string(), vec![mod_entry("XXL Mod")]);
+    assert!(failed_searches::candidate_titles(&data, SearchTarget::Maps).await.unwrap().is_empty(), "read from the cached map list");
+    map_cache::refresh_map_indexes(&data).await;
+
+    assert_eq!(failed_searches::candidate_titles(&data, SearchTarget::Maps).await.unwrap(), titles(&["Brooklyn Banks"]));
+    assert_eq!(failed_searches::candidate_titles(&data, SearchTarget::Mods).await.unwrap(), titles(&["XXL Mod"]));
+}
+
+#[test]
+fn report_lists_queries_with_their_closest_titles() {
+    let searches = vec![FailedSearch { query: "xxl".to_string(), count: 12 }, FailedSearch { query: "blender".to_string(), count: 1_500 }];
+    let embed = embed_json(&failed_searches::build_failed_searches_embed(SearchTarget::Mods, &searches, &titles(&["XXL Mod", "Walls"])));
+
+    assert_eq!(embed["title"], "🔍 Failed Searches · Mods");
+    assert_eq!(embed["description"], "**1.** `xxl` · 12× · → XXL Mod\n**2.** `blender` · 1,500× · no close match");
+
+    let empty = embed_json(&failed_searches::build_failed_searches_embed(SearchTarget::Maps, &[], &[]));
+    assert_eq!(empty["description"], "No failed map searches recorded yet.");
+}
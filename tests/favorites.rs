@@ -0,0 +1,100 @@
+mod common;
+
+use common::{map, redis_stub::RedisStub};
+use rand::{rngs::StdRng, SeedableRng};
+use skatebit_bot::{
+    commands::favorites_cmd::{describe_favorites, generate_collection_code, normalize_code},
+    components::{item_action_rows, ComponentAction},
+    config::RedisConfig,
+    repository::{FavoritesRepository, InMemoryFavoritesRepository, InMemoryMapRepository, RedisFavoritesRepository},
+    types::{Collection, FavoriteItem, ModVersionBranch},
+};
+
+fn walking_mod() -> FavoriteItem {
+    FavoriteItem::Mod { title: "Walking Mod".to_string(), branch: ModVersionBranch::BetaPublic }
+}
+
+#[test]
+fn favorite_keys_round_trip() {
+    for item in [FavoriteItem::Map { id: 42 }, walking_mod(), FavoriteItem::Mod { title: "A: B".to_string(), branch: ModVersionBranch::Alpha }] {
+        assert_eq!(FavoriteItem::from_key(&item.key()), Some(item));
+    }
+    assert_eq!(walking_mod().key(), "mod:12104:Walking Mod");
+    assert_eq!(FavoriteItem::from_key("mod:9999:Unknown Branch"), None);
+    assert_eq!(FavoriteItem::from_key("map:abc"), None);
+}
+
+#[test]
+fn favorite_button_custom_ids_parse_back() {
+    let action = ComponentAction::ToggleFavorite(FavoriteItem::Map { id: 7 });
+    assert_eq!(action.custom_id(), "fav:map:7");
+    assert_eq!(ComponentAction::parse("fav:map:7"), Some(action));
+    assert_eq!(ComponentAction::parse("page:123:next"), None);
+}
+
+#[test]
+fn no_button_when_custom_id_would_be_too_long() {
+    assert_eq!(item_action_rows(&walking_mod()).len(), 1);
+    let long = FavoriteItem::Mod { title: "x".repeat(120), branch: ModVersionBranch::Alpha };
+    assert!(item_action_rows(&long).is_empty());
+}
+
+#[test]
+fn collection_codes_are_short_and_unambiguous() {
+    let code = generate_collection_code(&mut StdRng::seed_from_u64(7));
+    assert_eq!(code.len(), 6);
+    assert!(code.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()));
+    assert!(!code.contains(['0', 'O', '1', 'I']));
+    assert_eq!(normalize_code("  ab12cd "), "AB12CD");
+}
+
+#[tokio::test]
+async fn describe_favorites_resolves_map_names() {
+    let maps = InMemoryMapRepository::with_maps([map(1, "Berlin Plaza", &["Map"])]);
+    let lines = describe_favorites(&maps, &[FavoriteItem::Map { id: 1 }, walking_mod(), FavoriteItem::Map { id: 9 }]).await;
+    assert_eq!(
+        lines,
+        vec![
+            "🗺️ [Berlin Plaza](https://mod.io/g/skaterxl/m/berlin-plaza)",
+            "🧩 **Walking Mod** (Beta/Public)",
+            "🗺️ Map #9 (no longer available)",
+        ]
+    );
+}
+
+async fn exercise_favorites(repo: &dyn FavoritesRepository) {
+    assert!(repo.toggle(1, &FavoriteItem::Map { id: 5 }).await.unwrap());
+    assert!(repo.toggle(1, &walking_mod()).await.unwrap());
+    assert!(!repo.toggle(1, &FavoriteItem::Map { id: 5 }).await.unwrap(), "second toggle removes");
+    assert_eq!(repo.list(1).await.unwrap(), vec![walking_mod()]);
+    assert!(repo.list(2).await.unwrap().is_empty(), "favorites are per user");
+
+    assert_eq!(repo.add_many(2, &[walking_mod(), FavoriteItem::Map { id: 3 }]).await.unwrap(), 2);
+    assert_eq!(repo.add_many(2, &[walking_mod()]).await.unwrap(), 0);
+    assert!(repo.remove(2, &walking_mod()).await.unwrap());
+    assert!(!repo.remove(2, &walking_mod()).await.unwrap());
+
+    let collection = Collection { code: "ABC234".to_string(), name: "my street setup".to_string(), owner_id: 1, items: vec![walking_mod()] };
+    assert!(repo.create_collection(&collection).await.unwrap());
+    let clash = Collection { owner_id: 2, ..collection.clone() };
+    assert!(!repo.create_collection(&clash).await.unwrap(), "codes are unique");
+    assert_eq!(repo.get_collection("ABC234").await.unwrap(), Some(collection.clone()));
+    assert_eq!(repo.get_collection("ZZZ999").await.unwrap(), None);
+    assert_eq!(repo.list_collections(1).await.unwrap(), vec![collection]);
+    assert!(repo.list_collections(2).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn in_memory_favorites_behave_like_a_set() {
+    exercise_favorites(&InMemoryFavoritesRepository::new()).await;
+}
+
+#[tokio::test]
+async fn redis_favorites_behave_like_a_set() {
+    let redis = RedisStub::start().await;
+    let keys = RedisConfig { namespace: Some("test".to_string()), ..RedisConfig::default() };
+    exercise_favorites(&RedisFavoritesRepository::new(redis.pool(), keys)).await;
+
+    assert_eq!(redis.smembers("test:bot:favorites:1"), vec!["mod:12104:Walking Mod"]);
+    assert!(redis.get("test:bot:collection:ABC234").unwrap().contains(r#""items":["mod:12104:Walking Mod"]"#));
+}
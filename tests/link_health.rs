@@ -0,0 +1,137 @@
+mod common;
+
+use common::{embed_json, redis_stub::RedisStub};
+use serde_json::json;
+use skatebit_bot::{
+    config::RedisConfig,
+    link_health::{self, broken_links_report, build_link_report_embed, check_link, collect_links},
+    mod_utils::format_mod_entry,
+    repository::{InMemoryMapRepository, InMemoryModRepository, LinkHealthRepository, RedisLinkHealthRepository},
+    types::{Data, LinkStatus, ModEntry, ModVersionBranch},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+const NOW: i64 = 1_700_000_000;
+
+fn mod_with_links(title: &str, links: &[(&str, &str)]) -> ModEntry {
+    let links: Vec<_> = links.iter().map(|(label, url)| json!({ "label": label, "url": url })).collect();
+    serde_json::from_value(json!({ "title": title, "downloadLinks": links })).unwrap()
+}
+
+async fn mock_file_host() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD")).and(path("/ok")).respond_with(ResponseTemplate::new(200)).mount(&server).await;
+    Mock::given(method("HEAD")).and(path("/gone")).respond_with(ResponseTemplate::new(404)).mount(&server).await;
+    Mock::given(method("HEAD")).and(path("/busy")).respond_with(ResponseTemplate::new(503)).mount(&server).await;
+    Mock::given(method("HEAD")).and(path("/no-head")).respond_with(ResponseTemplate::new(405)).mount(&server).await;
+    Mock::given(method("GET")).and(path("/no-head")).respond_with(ResponseTemplate::new(200)).mount(&server).await;
+    server
+}
+
+fn status(url: &str, code: Option<u16>, dead: bool) -> LinkStatus {
+    LinkStatus { url: url.to_string(), status: code, error: code.is_none().then(|| "connection refused".to_string()), dead, checked_at: NOW }
+}
+
+#[tokio::test]
+async fn only_gone_links_are_dead() {
+    let server = mock_file_host().await;
+    let client = link_health::link_check_client().unwrap();
+    let check = |p: &str| {
+        let url = format!("{}{}", server.uri(), p);
+        let client = client.clone();
+        async move { check_link(&client, &url, NOW).await }
+    };
+
+    let ok = check("/ok").await;
+    assert_eq!((ok.status, ok.dead, ok.checked_at), (Some(200), false, NOW));
+    assert_eq!((check("/gone").await.status, check("/gone").await.dead), (Some(404), true));
+    assert!(!check("/busy").await.dead, "server errors are temporary");
+    assert_eq!(check("/no-head").await.status, Some(200), "falls back to GET when HEAD is refused");
+
+    let refused = check_link(&client, "http://127.0.0.1:1/file.zip", NOW).await;
+    assert!(!refused.dead && refused.status.is_none() && refused.error.is_some(), "unreachable hosts are unknown, not dead");
+}
+
+#[tokio::test]
+async fn run_checks_every_listed_link_and_tracks_dead_ones() {
+    let server = mock_file_host().await;
+    let ok = format!("{}/ok", server.uri());
+    let gone = format!("{}/gone", server.uri());
+
+    let data = Data::with_repositories(Arc::new(InMemoryMapRepository::new()), Arc::new(InMemoryModRepository::new()));
+    data.link_health.record(&status("https://old.example/removed.zip", Some(404), true)).await.unwrap();
+    {
+        let mut mod_cache = data.mod_cache.write().await;
+        mod_cache.insert("12104".to_string(), vec![mod_with_links("Gear Mod", &[("GitHub", &ok), ("Drive", &gone)])]);
+        mod_cache.insert("1228".to_string(), vec![mod_with_links("Gear Mod", &[("GitHub", &ok)])]);
+    }
+    assert_eq!(collect_links(&*data.mod_cache.read().await), [gone.clone(), ok.clone()]);
+
+    let summary = link_health::run_link_check(&data, &link_health::link_check_client().unwrap(), Duration::ZERO).await;
+    assert_eq!((summary.checked, summary.dead), (2, 1));
+    assert_eq!(*data.dead_links.read().await, HashSet::from([gone.clone()]));
+
+    let stored = data.link_health.all().await.unwrap();
+    assert_eq!(stored.len(), 2, "results for links no longer listed are dropped");
+    assert!(stored[&gone].dead && !stored[&ok].dead);
+
+    *data.dead_links.write().await = HashSet::new();
+    assert_eq!(link_health::load_dead_links(&data).await, 1);
+}
+
+#[test]
+fn dead_links_are_flagged_in_mod_details() {
+    let entry = mod_with_links("Gear Mod", &[("GitHub", "https://github.com/a"), ("Drive", "https://drive.example/b")]);
+    let text = format_mod_entry(&entry, &HashSet::from(["https://drive.example/b".to_string()]));
+    assert!(text.ends_with("**Links:** [GitHub](https://github.com/a) | ~~[Drive](https://drive.example/b)~~ ⚠️ dead link"));
+}
+
+#[test]
+fn report_groups_dead_links_by_branch() {
+    let mod_cache = HashMap::from([
+        ("12104".to_string(), vec![mod_with_links("Gear Mod", &[("GitHub", "https://a"), ("Drive", "https://b")])]),
+        ("1228".to_string(), vec![mod_with_links("Walking Mod", &[("Discord", "https://c")])]),
+    ]);
+    let statuses = HashMap::from([
+        ("https://a".to_string(), status("https://a", Some(200), false)),
+        ("https://b".to_string(), status("https://b", Some(410), true)),
+        ("https://c".to_string(), status("https://c", Some(404), true)),
+    ]);
+
+    let report = broken_links_report(&mod_cache, &statuses);
+    assert_eq!(report.iter().map(|(branch, broken)| (*branch, broken.len())).collect::<Vec<_>>(), [
+        (ModVersionBranch::Alpha, 1),
+        (ModVersionBranch::BetaPublic, 1)
+    ]);
+
+    let embed = embed_json(&build_link_report_embed(&report, 3));
+    assert_eq!(embed["fields"][0]["name"], "Alpha (1)");
+    assert_eq!(embed["fields"][0]["value"], format!("**Walking Mod** — [Discord](https://c) (404, <t:{}:R>)", NOW));
+    assert_eq!(embed["fields"][1]["value"], format!("**Gear Mod** — [Drive](https://b) (410, <t:{}:R>)", NOW));
+    assert_eq!(embed_json(&build_link_report_embed(&[], 3))["description"], "All 3 checked links are working.");
+}
+
+#[tokio::test]
+async fn redis_keeps_one_result_per_link() {
+    let redis = RedisStub::start().await;
+    let repo = RedisLinkHealthRepository::new(redis.pool(), RedisConfig::default());
+    repo.record(&status("https://a", Some(200), false)).await.unwrap();
+    repo.record(&status("https://a", Some(404), true)).await.unwrap();
+    repo.record(&status("https://b", Some(200), false)).await.unwrap();
+
+    let stored = repo.all().await.unwrap();
+    assert_eq!(stored.len(), 2);
+    assert!(stored["https://a"].dead);
+
+    repo.forget(&["https://a".to_string()]).await.unwrap();
+    repo.forget(&[]).await.unwrap();
+    assert_eq!(repo.all().await.unwrap().into_keys().collect::<Vec<_>>(), ["https://b"]);
+}
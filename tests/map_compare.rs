@@ -0,0 +1,72 @@
+mod common;
+
+use common::{embed_field, embed_json, map};
+use skatebit_bot::{
+    commands::map_compare_cmd::{build_compare_embed, compare_rows, CompareRow, Side},
+    types::ApiModioMap,
+};
+
+fn pair() -> (ApiModioMap, ApiModioMap) {
+    let mut left = map(1, "Berlin Plaza", &["Map", "Street"]);
+    left.stats.downloads_total = 5_000;
+    left.stats.subscribers_total = 100;
+    left.stats.ratings_positive = 90;
+    left.stats.ratings_negative = 10;
+    left.modfile.as_mut().unwrap().filesize = Some(80 * 1024 * 1024);
+
+    let mut right = map(2, "Brooklyn Banks", &["Map", "Park"]);
+    right.stats.downloads_total = 1_200;
+    right.stats.subscribers_total = 100;
+    right.stats.ratings_positive = 95;
+    right.stats.ratings_negative = 5;
+    right.modfile.as_mut().unwrap().filesize = Some(20 * 1024 * 1024);
+    right.date_updated = left.date_updated + 86_400;
+    (left, right)
+}
+
+fn better(rows: &[CompareRow], label: &str) -> Option<Side> {
+    rows.iter().find(|r| r.label == label).expect("row present").better
+}
+
+#[test]
+fn better_value_is_picked_per_row() {
+    let (left, right) = pair();
+    let rows = compare_rows(&left, &right);
+
+    assert_eq!(better(&rows, "Downloads"), Some(Side::Left));
+    assert_eq!(better(&rows, "Rating"), Some(Side::Right));
+    assert_eq!(better(&rows, "Size"), Some(Side::Right), "smaller file wins");
+    assert_eq!(better(&rows, "Updated"), Some(Side::Right));
+    assert_eq!(better(&rows, "Subscribers"), None, "ties have no winner");
+    assert_eq!(better(&rows, "Author"), None);
+    assert_eq!(better(&rows, "Tags"), None);
+}
+
+#[test]
+fn unknown_values_have_no_winner() {
+    let (mut left, right) = pair();
+    left.stats.ratings_positive = 0;
+    left.stats.ratings_negative = 0;
+    left.modfile = None;
+    let rows = compare_rows(&left, &right);
+
+    assert_eq!(better(&rows, "Rating"), None);
+    assert_eq!(better(&rows, "Size"), None);
+    let size = rows.iter().find(|r| r.label == "Size").unwrap();
+    assert_eq!(size.left, "Unknown");
+}
+
+#[test]
+fn embed_shows_both_maps_side_by_side() {
+    let (left, right) = pair();
+    let embed = embed_json(&build_compare_embed(&left, &right));
+
+    let left_column = embed_field(&embed, "Berlin Plaza").expect("left column");
+    let right_column = embed_field(&embed, "Brooklyn Banks").expect("right column");
+    assert!(left_column.contains("**5,000** ✅"), "{}", left_column);
+    assert!(right_column.contains("1,200") && !right_column.contains("**1,200**"), "{}", right_column);
+    assert!(right_column.contains("**20.00 MB** ✅"), "{}", right_column);
+    assert!(left_column.contains("Street") && right_column.contains("Park"));
+    assert_eq!(left_column.lines().count(), right_column.lines().count());
+    assert!(embed["fields"].as_array().unwrap().iter().all(|f| f["inline"] == true));
+}
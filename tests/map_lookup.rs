@@ -0,0 +1,231 @@
+mod common;
+
+use common::{embed_field, embed_json, map, map_json, redis_stub::RedisStub};
+use skatebit_bot::{
+    config::RedisConfig,
+    commands::map_cmd::{build_map_embed, build_modio_embed, download_field_value, find_map, find_tagged, map_suggestions, parse_map_id, title_suggestions},
+    repository::{InMemoryMapRepository, MapRepository, RedisMapRepository},
+};
+
+/// Seeds the stub the way the Go API does: a title ZSET plus one JSON blob per mod.
+async fn seeded_redis() -> (RedisStub, RedisMapRepository) {
+    let redis = RedisStub::start().await;
+    for (id, name, tags) in [
+        (1, "Berlin Plaza", vec!["Map"]),
+        (2, "Brooklyn Banks", vec!["Map", "Street"]),
+        (3, "Danny Way Mega Ramp", vec!["Map"]),
+        (4, "Better Grinds", vec!["Script"]),
+    ] {
+        redis.set(&format!("mod:{}", id), &map_json(id, name, &tags).to_string());
+        redis.zadd("mod_titles:map", 0.0, &format!("{}:{}", name.to_lowercase(), id));
+    }
+    redis.zadd("mod_titles:script", 0.0, "better grinds:4");
+    // Index members whose mod entry is missing or unreadable.
+    redis.zadd("mod_titles:map", 0.0, "ghost park:5");
+    redis.zadd("mod_titles:map", 0.0, "broken bowl:6");
+    redis.set("mod:6", "{\"id\": 6, \"name\": ");
+    let repo = RedisMapRepository::new(redis.pool(), RedisConfig::default());
+    (redis, repo)
+}
+
+#[test]
+fn parse_map_id_reads_autocomplete_choices() {
+    assert_eq!(parse_map_id("Berlin Plaza (ID: 1)"), Some(1));
+    assert_eq!(parse_map_id("Park (Night) (ID: 42)"), Some(42));
+    assert_eq!(parse_map_id("Berlin Plaza"), None);
+    assert_eq!(parse_map_id("Berlin (ID: abc)"), None);
+}
+
+#[tokio::test]
+async fn autocomplete_defaults_to_first_entries_in_lex_order() {
+    let (_redis, repo) = seeded_redis().await;
+    let suggestions = map_suggestions(&repo, "").await;
+    assert_eq!(
+        suggestions,
+        vec![
+            "Berlin Plaza (ID: 1)",
+            "Better Grinds (ID: 4)",
+            "broken bowl (ID: 6)",
+            "Brooklyn Banks (ID: 2)",
+            "Danny Way Mega Ramp (ID: 3)",
+            "ghost park (ID: 5)",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn autocomplete_filters_by_normalized_prefix() {
+    let (_redis, repo) = seeded_redis().await;
+    assert_eq!(
+        map_suggestions(&repo, "  BR ").await,
+        vec!["broken bowl (ID: 6)", "Brooklyn Banks (ID: 2)"]
+    );
+    assert!(map_suggestions(&repo, "zzz").await.is_empty());
+}
+
+#[tokio::test]
+async fn autocomplete_is_capped_at_25() {
+    let redis = RedisStub::start().await;
+    for id in 0..40 {
+        redis.zadd("mod_titles:map", 0.0, &format!("park {:02}:{}", id, id));
+    }
+    let repo = RedisMapRepository::new(redis.pool(), RedisConfig::default());
+    let suggestions = map_suggestions(&repo, "park").await;
+    assert_eq!(suggestions.len(), 25);
+    assert_eq!(suggestions[0], "park 00 (ID: 0)");
+}
+
+#[tokio::test]
+async fn namespaced_keys_are_isolated_from_other_environments() {
+    let redis = RedisStub::start().await;
+    redis.zadd("mod_titles:map", 0.0, "berlin plaza:1");
+    redis.set("mod:1", &map_json(1, "Berlin Plaza", &["Map"]).to_string());
+    redis.zadd("staging:titles:map", 0.0, "staging park:2");
+    redis.set("staging:m:2", &map_json(2, "Staging Park", &["Map"]).to_string());
+
+    let keys = RedisConfig {
+        namespace: Some("staging".to_string()),
+        mod_key_prefix: "m:".to_string(),
+        title_index_prefix: "titles:".to_string(),
+        ..RedisConfig::default()
+    };
+    let repo = RedisMapRepository::new(redis.pool(), keys);
+
+    assert_eq!(map_suggestions(&repo, "").await, vec!["Staging Park (ID: 2)"]);
+    assert!(find_map(&repo, "Berlin Plaza (ID: 1)").await.unwrap().is_none());
+    assert_eq!(find_map(&repo, "staging").await.unwrap().unwrap().id, 2);
+}
+
+#[tokio::test]
+async fn missing_and_malformed_entries_are_not_found() {
+    let (_redis, repo) = seeded_redis().await;
+    assert!(repo.get_by_id(5).await.unwrap().is_none());
+    assert!(repo.get_by_id(6).await.unwrap().is_none());
+    let many = repo.get_many(&[1, 5, 6]).await.unwrap();
+    assert_eq!(many.iter().map(Option::is_some).collect::<Vec<_>>(), vec![true, false, false]);
+    assert!(repo.get_many(&[]).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn find_map_by_autocomplete_id() {
+    let (_redis, repo) = seeded_redis().await;
+    let found = find_map(&repo, "Brooklyn Banks (ID: 2)").await.unwrap().unwrap();
+    assert_eq!(found.name, "Brooklyn Banks");
+}
+
+#[tokio::test]
+async fn find_map_rejects_non_map_missing_and_malformed_ids() {
+    let (_redis, repo) = seeded_redis().await;
+    assert!(find_map(&repo, "Better Grinds (ID: 4)").await.unwrap().is_none());
+    assert!(find_map(&repo, "Ghost Park (ID: 5)").await.unwrap().is_none());
+    assert!(find_map(&repo, "Broken Bowl (ID: 6)").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn find_map_by_unambiguous_prefix() {
+    let (_redis, repo) = seeded_redis().await;
+    assert_eq!(find_map(&repo, "danny").await.unwrap().unwrap().id, 3);
+    assert!(find_map(&repo, "br").await.unwrap().is_none(), "ambiguous prefix must not resolve");
+    assert!(find_map(&repo, "nowhere").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn find_map_surfaces_unreachable_redis() {
+    let pool = deadpool_redis::Config::from_url("redis://127.0.0.1:1")
+        .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+        .unwrap();
+    let repo = RedisMapRepository::new(pool, RedisConfig::default());
+    assert!(find_map(&repo, "danny").await.is_err());
+    assert!(map_suggestions(&repo, "danny").await.is_empty());
+}
+
+#[tokio::test]
+async fn in_memory_repository_matches_redis_behaviour() {
+    let repo = InMemoryMapRepository::with_maps([map(1, "Berlin Plaza", &["Map"]), map(2, "Brooklyn Banks", &["Map"])]);
+    repo.insert_title("Map", "Ghost Park", 5);
+
+    assert_eq!(map_suggestions(&repo, "b").await, vec!["Berlin Plaza (ID: 1)", "Brooklyn Banks (ID: 2)"]);
+    assert_eq!(map_suggestions(&repo, "gh").await, vec!["ghost park (ID: 5)"]);
+    assert_eq!(find_map(&repo, "brook").await.unwrap().unwrap().id, 2);
+    assert!(find_map(&repo, "ghost").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn scripts_resolve_from_their_own_index() {
+    let (_redis, repo) = seeded_redis().await;
+    assert_eq!(title_suggestions(&repo, "Script", "").await, vec!["Better Grinds (ID: 4)"]);
+    assert_eq!(find_tagged(&repo, "Script", "better").await.unwrap().unwrap().id, 4);
+    assert!(find_tagged(&repo, "Script", "Berlin Plaza (ID: 1)").await.unwrap().is_none(), "maps are not scripts");
+    assert!(find_map(&repo, "Better Grinds (ID: 4)").await.unwrap().is_none(), "scripts are not maps");
+
+    let memory = InMemoryMapRepository::with_maps([map(1, "Berlin Plaza", &["Map"]), map(4, "Better Grinds", &["Script"])]);
+    assert_eq!(title_suggestions(&memory, "Script", "b").await, vec!["Better Grinds (ID: 4)"]);
+    assert_eq!(memory.all_ids("Map").await.unwrap(), [1]);
+}
+
+#[test]
+fn script_embed_labels_the_download() {
+    let embed = embed_json(&build_modio_embed(&map(4, "Better Grinds", &["Script"]), "Download Script", "tester"));
+    assert_eq!(embed_field(&embed, "Link"), Some("[Download Script](https://mod.io/dl/4)"));
+    assert_eq!(embed_field(&embed, "Stats"), Some("⬇️ 1,000 · 👥 500 · Very Positive"));
+}
+
+#[test]
+fn map_embed_shows_details_and_download() {
+    let entry = map(2, "Brooklyn Banks", &["Map", "Street"]);
+    let embed = embed_json(&build_map_embed(&entry, "tester"));
+
+    assert_eq!(embed["title"], "Brooklyn Banks");
+    assert_eq!(embed["url"], "https://mod.io/g/skaterxl/m/brooklyn-banks");
+    assert_eq!(embed["image"]["url"], "https://img.example/logo_1280.png");
+    assert_eq!(embed_field(&embed, "Author"), Some("[builder](https://mod.io/u/builder)"));
+    assert_eq!(embed_field(&embed, "Size"), Some("50.00 MB"));
+    assert_eq!(embed_field(&embed, "Tags"), Some("Map, Street"));
+    assert_eq!(embed_field(&embed, "Link"), Some("[Download Map](https://mod.io/dl/2)"));
+    assert_eq!(embed["footer"]["text"], "ID: 2 | Source: mod.io | Requested by tester");
+}
+
+#[test]
+fn map_embed_without_modfile_has_no_download() {
+    let mut entry = map(1, "Berlin Plaza", &[]);
+    entry.modfile = None;
+    entry.tags = None;
+    let embed = embed_json(&build_map_embed(&entry, "tester"));
+
+    assert_eq!(embed_field(&embed, "Size"), Some("Unknown"));
+    assert_eq!(embed_field(&embed, "Tags"), Some("None"));
+    assert_eq!(embed_field(&embed, "Link"), Some("No download link"));
+}
+
+#[test]
+fn expiring_download_links_fall_back_to_the_mod_page() {
+    let mut entry = map(2, "Brooklyn Banks", &["Map"]);
+    let expires_at = entry.modfile.as_ref().unwrap().download.date_expires;
+
+    assert_eq!(download_field_value(&entry, "Download Map", expires_at - 7 * 86_400), "[Download Map](https://mod.io/dl/2)");
+    assert_eq!(
+        download_field_value(&entry, "Download Map", expires_at - 3_600),
+        format!("[Download Map](https://mod.io/dl/2) · expires <t:{}:R>", expires_at)
+    );
+    let fallback = "[Download Map on mod.io](https://mod.io/g/skaterxl/m/brooklyn-banks) (direct link expired)";
+    assert_eq!(download_field_value(&entry, "Download Map", expires_at - 60), fallback);
+    assert_eq!(download_field_value(&entry, "Download Map", expires_at + 60), fallback);
+
+    entry.modfile.as_mut().unwrap().download.date_expires = 0;
+    assert_eq!(download_field_value(&entry, "Download Map", expires_at * 2), "[Download Map](https://mod.io/dl/2)", "no expiry known");
+    entry.modfile.as_mut().unwrap().download.binary_url.clear();
+    assert_eq!(download_field_value(&entry, "Download Map", 0), "No download link");
+}
+
+#[test]
+fn map_searches_directly_and_maps_holds_the_other_map_commands() {
+    let map = skatebit_bot::commands::map_cmd::map();
+    assert!(map.subcommands.is_empty(), "`/map <name>` stays a plain lookup");
+    let registered = serde_json::to_value(map.create_as_slash_command().expect("registers as a slash command")).unwrap();
+    let options: Vec<&str> = registered["options"].as_array().unwrap().iter().map(|o| o["name"].as_str().unwrap()).collect();
+    assert_eq!(options, ["search"]);
+
+    let maps = skatebit_bot::commands::maps_cmd::maps();
+    let subcommands: Vec<&str> = maps.subcommands.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(subcommands, ["tag", "trending", "random", "roulette", "compare", "trend"]);
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use common::{embed_json, map, map_json, redis_stub::RedisStub};
+use rand::{rngs::StdRng, SeedableRng};
+use skatebit_bot::{
+    commands::map_random_cmd::{build_roulette_embed, pick_random_maps, rating_percent, MapFilter, RouletteVotes},
+    config::RedisConfig,
+    repository::{InMemoryMapRepository, MapRepository, RedisMapRepository},
+    types::ApiModioMap,
+};
+use std::collections::BTreeSet;
+
+const NOW: i64 = 1_710_000_000; // Months after the fixture maps were added
+
+fn rated(id: i32, name: &str, tags: &[&str], positive: i32, negative: i32) -> ApiModioMap {
+    let mut entry = map(id, name, tags);
+    entry.stats.ratings_positive = positive;
+    entry.stats.ratings_negative = negative;
+    entry
+}
+
+fn sample_maps() -> Vec<ApiModioMap> {
+    let mut small_new = rated(4, "Tiny DIY", &["Map", "DIY"], 8, 2);
+    small_new.modfile.as_mut().unwrap().filesize = Some(10 * 1024 * 1024);
+    small_new.date_added = NOW - 3 * 86_400;
+    vec![
+        rated(1, "Berlin Plaza", &["Map", "Street"], 90, 10),
+        rated(2, "Brooklyn Banks", &["Map", "Street"], 30, 70),
+        rated(3, "Better Grinds", &["Script"], 100, 0),
+        small_new,
+        rated(5, "Unrated Park", &["Map"], 0, 0),
+    ]
+}
+
+fn ids(maps: &[ApiModioMap]) -> BTreeSet<i32> {
+    maps.iter().map(|m| m.id).collect()
+}
+
+#[test]
+fn rating_is_share_of_positive_votes() {
+    assert_eq!(rating_percent(&rated(1, "A", &["Map"], 90, 10)), Some(90.0));
+    assert_eq!(rating_percent(&rated(1, "A", &["Map"], 0, 0)), None);
+}
+
+#[test]
+fn filters_combine_and_skip_non_maps() {
+    let maps = sample_maps();
+    let matching = |filter: MapFilter| -> BTreeSet<i32> { maps.iter().filter(|m| filter.matches(m, NOW)).map(|m| m.id).collect() };
+
+    assert_eq!(matching(MapFilter::default()), BTreeSet::from([1, 2, 4, 5]));
+    assert_eq!(matching(MapFilter { tag: Some(" street ".into()), ..Default::default() }), BTreeSet::from([1, 2]));
+    assert_eq!(matching(MapFilter { min_rating: Some(80.0), ..Default::default() }), BTreeSet::from([1, 4]));
+    assert_eq!(matching(MapFilter { max_size_mb: Some(20.0), ..Default::default() }), BTreeSet::from([4]));
+    assert_eq!(matching(MapFilter { max_age_days: Some(7), ..Default::default() }), BTreeSet::from([4]));
+    assert_eq!(
+        matching(MapFilter { tag: Some("Street".into()), min_rating: Some(50.0), ..Default::default() }),
+        BTreeSet::from([1])
+    );
+}
+
+#[test]
+fn filter_description_lists_active_constraints() {
+    assert_eq!(MapFilter::default().describe(), "");
+    let filter = MapFilter { tag: Some("Street".into()), min_rating: Some(80.0), max_size_mb: Some(500.0), max_age_days: Some(30) };
+    assert_eq!(filter.describe(), "tag Street · 80%+ rating · up to 500 MB · added in the last 30 days");
+}
+
+#[tokio::test]
+async fn random_draws_are_distinct_and_filtered() {
+    let repo = InMemoryMapRepository::with_maps(sample_maps());
+    let mut rng = StdRng::seed_from_u64(7);
+
+    let all = pick_random_maps(&repo, &MapFilter::default(), 10, NOW, &mut rng).await.unwrap();
+    assert_eq!(ids(&all), BTreeSet::from([1, 2, 4, 5]));
+    assert_eq!(all.len(), 4);
+
+    let street = MapFilter { tag: Some("Street".into()), ..Default::default() };
+    let one = pick_random_maps(&repo, &street, 1, NOW, &mut rng).await.unwrap();
+    assert_eq!(one.len(), 1);
+    assert!([1, 2].contains(&one[0].id));
+
+    let none = MapFilter { min_rating: Some(99.0), ..Default::default() };
+    assert!(pick_random_maps(&repo, &none, 3, NOW, &mut rng).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn random_draws_reach_every_map_over_time() {
+    let repo = InMemoryMapRepository::with_maps(sample_maps());
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut seen = BTreeSet::new();
+    for _ in 0..50 {
+        seen.extend(ids(&pick_random_maps(&repo, &MapFilter::default(), 1, NOW, &mut rng).await.unwrap()));
+    }
+    assert_eq!(seen, BTreeSet::from([1, 2, 4, 5]));
+}
+
+#[tokio::test]
+async fn redis_lists_every_indexed_id() {
+    let redis = RedisStub::start().await;
+    for (id, name) in [(1, "Berlin Plaza"), (2, "Brooklyn Banks"), (3, "Danny Way")] {
+        redis.set(&format!("mod:{}", id), &map_json(id, name, &["Map"]).to_string());
+        redis.zadd("mod_titles:map", 0.0, &format!("{}:{}", name.to_lowercase(), id));
+    }
+    let repo = RedisMapRepository::new(redis.pool(), RedisConfig::default());
+    assert_eq!(repo.all_ids("Map").await.unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn roulette_votes_move_and_ties_go_to_the_first_draw() {
+    let mut votes = RouletteVotes::default();
+    assert_eq!(votes.winner(3), None);
+
+    votes.cast(1, 2);
+    votes.cast(2, 1);
+    assert_eq!(votes.tally(3), [0, 1, 1]);
+    assert_eq!(votes.winner(3), Some(1));
+
+    votes.cast(2, 2);
+    assert_eq!(votes.tally(3), [0, 0, 2]);
+    assert_eq!(votes.winner(3), Some(2));
+}
+
+#[test]
+fn roulette_embed_marks_the_winner_once_closed() {
+    let maps = vec![rated(1, "Berlin Plaza", &["Map"], 90, 10), rated(2, "Brooklyn Banks", &["Map"], 0, 0)];
+    let mut votes = RouletteVotes::default();
+    votes.cast(10, 1);
+
+    let open = embed_json(&build_roulette_embed(&maps, &votes, NOW, false));
+    let description = open["description"].as_str().unwrap();
+    assert!(description.contains("**1.** [Berlin Plaza](https://mod.io/g/skaterxl/m/berlin-plaza) by builder · 90% 👍 — **0 votes**"));
+    assert!(description.contains("**2.** [Brooklyn Banks]"));
+    assert!(description.contains("unrated — **1 vote**"));
+    assert!(description.contains(&format!("<t:{}:R>", NOW)));
+
+    let closed = embed_json(&build_roulette_embed(&maps, &votes, NOW, true));
+    assert_eq!(closed["title"], "🎰 Map Roulette — Results");
+    let description = closed["description"].as_str().unwrap();
+    assert!(description.contains("🏆 **2.** [Brooklyn Banks]"));
+    assert!(description.ends_with("**Brooklyn Banks** wins!"));
+}
@@ -0,0 +1,221 @@
+mod common;
+
+use common::{embed_json, map, mod_entry, redis_stub::RedisStub};
+use poise::serenity_prelude::ChannelId;
+use skatebit_bot::{
+    config::{Config, RedisConfig},
+    map_cache,
+    mentions::{self, find_mentions, Cooldowns, Mention, MentionTarget, MAX_MENTIONS_PER_MESSAGE},
+    repository::{InMemoryMapRepository, InMemoryModRepository, MentionChannelRepository, RedisMentionChannelRepository},
+    types::{Data, ModVersionBranch},
+};
+use std::{collections::HashMap, sync::Arc};
+
+#[test]
+fn finds_links_and_names_in_order() {
+    let content = "check [[Berlin Plaza]] and https://mod.io/g/skaterxl/m/brooklyn-banks?tab=comments, or [[ XXLMod3 ]]";
+    assert_eq!(
+        find_mentions(content),
+        vec![
+            Mention::Name("Berlin Plaza".to_string()),
+            Mention::MapLink("brooklyn-banks".to_string()),
+            Mention::Name("XXLMod3".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn mentions_are_deduplicated_and_capped() {
+    assert_eq!(find_mentions("[[Berlin]] [[berlin]] https://MOD.IO/g/skaterxl/m/Love-Park mod.io/g/skaterxl/m/love-park").len(), 2);
+    let many = "[[a]] [[b]] [[c]] [[d]] [[e]]";
+    assert_eq!(find_mentions(many).len(), MAX_MENTIONS_PER_MESSAGE);
+}
+
+#[test]
+fn ignores_empty_multiline_and_unrelated_brackets() {
+    assert!(find_mentions("[[]] [[  ]] [[two\nlines]] [x] https://mod.io/g/other-game/m/park https://mod.io/g/skaterxl/m/").is_empty());
+    assert_eq!(find_mentions("[[a [[Love Park]]"), vec![Mention::Name("Love Park".to_string())]);
+    assert!(find_mentions(&format!("[[{}]]", "x".repeat(101))).is_empty());
+}
+
+#[test]
+fn links_wrapped_in_angle_brackets_are_skipped() {
+    assert!(find_mentions("no preview please <https://mod.io/g/skaterxl/m/love-park>").is_empty());
+    assert_eq!(find_mentions("(https://mod.io/g/skaterxl/m/love-park)"), vec![Mention::MapLink("love-park".to_string())]);
+}
+
+#[test]
+fn links_after_multibyte_whitespace_are_found() {
+    assert_eq!(find_mentions("see\u{a0}https://mod.io/g/skaterxl/m/foo"), vec![Mention::MapLink("foo".to_string())]);
+    assert!(find_mentions("see\u{3000}<https://mod.io/g/skaterxl/m/foo>").is_empty());
+}
+
+fn seeded_data() -> Data {
+    let maps = Arc::new(InMemoryMapRepository::with_maps([
+        map(1, "Berlin Plaza", &["Map"]),
+        map(2, "Berlin Wall Spot", &["Map"]),
+        map(3, "Love Park", &["Map"]),
+        map(4, "Love Park Script", &["Script"]),
+    ]));
+    Data::with_repositories(maps, Arc::new(InMemoryModRepository::new()))
+}
+
+fn map_id(target: Option<MentionTarget>) -> Option<i32> {
+    match target {
+        Some(MentionTarget::Map(map)) => Some(map.id),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn links_resolve_through_the_slug_index() {
+    let data = seeded_data();
+    let link = Mention::MapLink("love-park".to_string());
+    assert!(mentions::resolve_mention(&data, &link).await.unwrap().is_none(), "index not built yet");
+
+    map_cache::refresh_map_indexes(&data).await;
+    assert_eq!(data.map_slugs.read().await.len(), 3, "scripts are left out");
+    assert_eq!(map_id(mentions::resolve_mention(&data, &link).await.unwrap()), Some(3));
+    let unknown = Mention::MapLink("love-park-script".to_string());
+    assert!(mentions::resolve_mention(&data, &unknown).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn names_match_exact_titles_or_a_single_prefix() {
+    let data = seeded_data();
+    let resolve = |name: &str| {
+        let data = data.clone();
+        let mention = Mention::Name(name.to_string());
+        async move { mentions::resolve_mention(&data, &mention).await.unwrap() }
+    };
+    assert_eq!(map_id(resolve("berlin plaza").await), Some(1));
+    assert_eq!(map_id(resolve("Love").await), Some(3), "scripts don't count towards ambiguity");
+    assert_eq!(map_id(resolve("Berlin").await), None, "ambiguous prefix");
+}
+
+#[tokio::test]
+async fn names_fall_back_to_the_mod_list() {
+    let data = seeded_data();
+    *data.mod_cache.write().await = HashMap::from([
+        ("1228".to_string(), vec![mod_entry("XXLMod3"), mod_entry("BonedOllieMod")]),
+        ("12104".to_string(), vec![mod_entry("XXLMod3")]),
+    ]);
+
+    match mentions::resolve_mention(&data, &Mention::Name("xxlmod3".to_string())).await.unwrap() {
+        Some(MentionTarget::Mod { entry, branch }) => {
+            assert_eq!(entry.title, "XXLMod3");
+            assert_eq!(branch, ModVersionBranch::BetaPublic);
+        }
+        other => panic!("expected a mod, got {:?}", other),
+    }
+    assert!(matches!(
+        mentions::resolve_mention(&data, &Mention::Name("BonedOllieMod".to_string())).await.unwrap(),
+        Some(MentionTarget::Mod { branch: ModVersionBranch::Alpha, .. })
+    ));
+    assert!(mentions::resolve_mention(&data, &Mention::Name("Nope".to_string())).await.unwrap().is_none());
+}
+
+#[test]
+fn compact_map_embed_links_the_map_with_stats() {
+    let embed = embed_json(&mentions::build_compact_map_embed(&map(3, "Love Park", &["Map"])));
+    assert_eq!(embed["title"], "Love Park");
+    assert_eq!(embed["url"], "https://mod.io/g/skaterxl/m/love-park");
+    assert!(embed["description"].as_str().unwrap().starts_with("by **builder** · ⬇️"), "{}", embed["description"]);
+    assert!(embed["footer"]["text"].as_str().unwrap().contains("ID: 3"));
+}
+
+#[test]
+fn cooldowns_expire() {
+    let mut cooldowns = Cooldowns::default();
+    assert!(!cooldowns.is_active("user:1", 100));
+    cooldowns.start("user:1", 100, 20);
+    assert!(cooldowns.is_active("user:1", 119));
+    assert!(!cooldowns.is_active("user:1", 120));
+    assert!(!cooldowns.is_active("user:2", 110));
+}
+
+#[tokio::test]
+async fn redis_mention_channels_round_trip() {
+    let redis = RedisStub::start().await;
+    let channels = RedisMentionChannelRepository::new(redis.pool(), RedisConfig::default());
+
+    assert!(channels.enable(10, 200).await.unwrap());
+    assert!(!channels.enable(10, 200).await.unwrap(), "already enabled");
+    assert!(channels.enable(11, 100).await.unwrap());
+    assert_eq!(channels.all().await.unwrap(), vec![(10, 200), (11, 100)]);
+
+    assert!(channels.disable(200).await.unwrap());
+    assert!(!channels.disable(200).await.unwrap());
+    assert_eq!(channels.all().await.unwrap(), vec![(11, 100)]);
+}
+
+#[tokio::test]
+async fn load_channels_fills_the_cache() {
+    let data = seeded_data();
+    data.mention_channels.enable(10, 200).await.unwrap();
+    data.mention_channels.enable(10, 201).await.unwrap();
+    assert_eq!(mentions::load_channels(&data).await, 2);
+    assert!(data.mention_channel_ids.read().await.contains(&201));
+}
+
+const CHANNEL: ChannelId = ChannelId::new(500);
+const NOW: i64 = 1_700_000_000;
+
+async fn indexed_data(unfurl_links: bool) -> Data {
+    let mut config = Config::default();
+    config.features.unfurl_links = unfurl_links;
+    let data = seeded_data().with_config(config);
+    map_cache::refresh_map_indexes(&data).await;
+    data
+}
+
+#[tokio::test]
+async fn pasted_links_are_unfurled_everywhere_with_the_full_embed() {
+    let data = indexed_data(true).await;
+    let content = "https://mod.io/g/skaterxl/m/love-park also [[Berlin Plaza]]";
+    let reply = mentions::prepare_reply(&data, CHANNEL, "skater", content, NOW).await.expect("link is unfurled");
+
+    assert_eq!(reply.embeds.len(), 1, "names need the channel to be enabled");
+    assert_eq!(reply.unfurled.iter().map(|m| m.id).collect::<Vec<_>>(), vec![3]);
+    let embed = embed_json(&reply.embeds[0]);
+    assert_eq!(embed["title"], "Love Park");
+    assert!(embed["fields"].as_array().unwrap().iter().any(|f| f["name"] == "Link"), "full map embed: {}", embed);
+    assert!(embed["footer"]["text"].as_str().unwrap().ends_with("Requested by skater"));
+}
+
+#[tokio::test]
+async fn enabled_channels_mix_unfurls_and_compact_embeds() {
+    let data = indexed_data(true).await;
+    data.mention_channel_ids.write().await.insert(CHANNEL.get());
+    let content = "[[Berlin Plaza]] https://mod.io/g/skaterxl/m/love-park";
+    let reply = mentions::prepare_reply(&data, CHANNEL, "skater", content, NOW).await.unwrap();
+
+    assert_eq!(reply.embeds.len(), 2);
+    assert_eq!(reply.unfurled.len(), 1);
+    assert!(embed_json(&reply.embeds[0])["fields"].is_null(), "compact embed for the name");
+    assert_eq!(reply.item_keys, vec!["channel:500:map:1", "channel:500:map:3"]);
+}
+
+#[tokio::test]
+async fn unfurling_is_off_unless_configured() {
+    assert!(!Config::default().features.unfurl_links);
+    let data = indexed_data(false).await;
+    let content = "https://mod.io/g/skaterxl/m/love-park";
+    assert!(mentions::prepare_reply(&data, CHANNEL, "skater", content, NOW).await.is_none());
+
+    data.mention_channel_ids.write().await.insert(CHANNEL.get());
+    let reply = mentions::prepare_reply(&data, CHANNEL, "skater", content, NOW).await.unwrap();
+    assert!(reply.unfurled.is_empty());
+    assert!(embed_json(&reply.embeds[0])["fields"].is_null(), "compact embed in enabled channels");
+}
+
+#[tokio::test]
+async fn items_on_cooldown_are_not_repeated_in_the_channel() {
+    let data = indexed_data(true).await;
+    data.mention_cooldowns.lock().unwrap().start("channel:500:map:3", NOW, mentions::ITEM_COOLDOWN_SECS);
+    let content = "https://mod.io/g/skaterxl/m/love-park";
+
+    assert!(mentions::prepare_reply(&data, CHANNEL, "skater", content, NOW + 60).await.is_none());
+    assert!(mentions::prepare_reply(&data, ChannelId::new(501), "skater", content, NOW + 60).await.is_some());
+    assert!(mentions::prepare_reply(&data, CHANNEL, "skater", content, NOW + mentions::ITEM_COOLDOWN_SECS).await.is_some());
+}
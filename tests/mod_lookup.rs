@@ -0,0 +1,68 @@
+mod common;
+
+use common::{embed_json, fixture_mods_12104, mod_entry};
+use skatebit_bot::{
+    commands::mod_cmd::{build_mod_embed, find_mod_matches, mod_title_suggestions, parse_mod_search},
+    types::ModVersionBranch,
+};
+use std::collections::{HashMap, HashSet};
+
+fn cache_with(slug: &str, mods: Vec<skatebit_bot::types::ModEntry>) -> HashMap<String, Vec<skatebit_bot::types::ModEntry>> {
+    HashMap::from([(slug.to_string(), mods)])
+}
+
+#[test]
+fn suggestions_match_case_insensitively_with_branch_suffix() {
+    let cache = cache_with("12104", fixture_mods_12104());
+    assert_eq!(mod_title_suggestions(&cache, "mod"), vec!["XLGearModifier - Beta/Public", "Walking Mod - Beta/Public", "BonedOllieMod - Beta/Public"]);
+    assert_eq!(mod_title_suggestions(&cache, "WALK"), vec!["Walking Mod - Beta/Public"]);
+}
+
+#[test]
+fn suggestions_skip_unknown_slugs_and_cap_at_25() {
+    let many: Vec<_> = (0..40).map(|i| mod_entry(&format!("Mod {}", i))).collect();
+    let mut cache = cache_with("1228", many);
+    cache.insert("9999".to_string(), vec![mod_entry("Hidden Mod")]);
+
+    let suggestions = mod_title_suggestions(&cache, "mod");
+    assert_eq!(suggestions.len(), 25);
+    assert!(suggestions.iter().all(|s| s.ends_with(" - Alpha")));
+}
+
+#[test]
+fn suggestions_fit_discord_choice_limit() {
+    let cache = cache_with("12104", vec![mod_entry(&"ü".repeat(120))]);
+    let suggestions = mod_title_suggestions(&cache, "");
+    assert_eq!(suggestions.len(), 1);
+    assert!(suggestions[0].chars().count() <= 100);
+    assert!(suggestions[0].ends_with("... - Beta/Public"));
+}
+
+#[test]
+fn parse_mod_search_splits_title_and_branch() {
+    assert_eq!(parse_mod_search("Walking Mod - Beta/Public"), Ok(("Walking Mod", ModVersionBranch::BetaPublic)));
+    assert_eq!(parse_mod_search("Some - Mod - alpha"), Ok(("Some - Mod", ModVersionBranch::Alpha)));
+}
+
+#[test]
+fn parse_mod_search_rejects_bad_input() {
+    assert!(parse_mod_search("Walking Mod").unwrap_err().contains("Mod Title - Branch"));
+    assert!(parse_mod_search("Walking Mod - Gamma").unwrap_err().contains("Invalid branch name 'Gamma'"));
+}
+
+#[test]
+fn find_mod_matches_is_exact_but_case_insensitive() {
+    let mods = fixture_mods_12104();
+    assert_eq!(find_mod_matches(&mods, "walking mod").len(), 1);
+    assert!(find_mod_matches(&mods, "walking").is_empty());
+}
+
+#[test]
+fn mod_embed_contains_entry_and_version() {
+    let mods = fixture_mods_12104();
+    let embed = embed_json(&build_mod_embed(&mods[0], ModVersionBranch::Alpha, "tester", &HashSet::new()));
+
+    assert_eq!(embed["title"], "XLGearModifier");
+    assert!(embed["description"].as_str().unwrap().contains("**Author:** Kiwi"));
+    assert_eq!(embed["footer"]["text"], "Version: Alpha | Requested by tester");
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::{fixture_mods_12104, MODS_12104_JSON};
+use skatebit_bot::{
+    mod_utils::{self, fetch_mods_for_version, format_mod_entry, truncate_with_ellipsis},
+    repository::{HttpModRepository, InMemoryMapRepository, InMemoryModRepository},
+    types::Data,
+};
+use std::{collections::HashSet, sync::Arc};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+async fn mock_mods_api() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/mods/12104"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(MODS_12104_JSON, "application/json"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/mods/1228"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("upstream exploded"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/mods/broken"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw("[{\"title\": 42}", "application/json"))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn fetch_mods_parses_camel_case_fields() {
+    let server = mock_mods_api().await;
+    let mods = fetch_mods_for_version(&reqwest::Client::new(), &server.uri(), "12104").await.unwrap();
+
+    assert_eq!(mods.len(), 3);
+    assert_eq!(mods[0].title, "XLGearModifier");
+    assert_eq!(mods[0].working_version.as_deref(), Some("1.2.4"));
+    assert_eq!(mods[0].download_links.as_ref().unwrap()[1].label, "Drive");
+    assert!(mods[2].download_links.is_none());
+}
+
+#[tokio::test]
+async fn fetch_mods_tolerates_trailing_slash_in_base_url() {
+    let server = mock_mods_api().await;
+    let base_url = format!("{}/", server.uri());
+    let mods = fetch_mods_for_version(&reqwest::Client::new(), &base_url, "12104").await.unwrap();
+    assert_eq!(mods.len(), 3);
+}
+
+#[tokio::test]
+async fn fetch_mods_fails_on_error_status() {
+    let server = mock_mods_api().await;
+    let err = fetch_mods_for_version(&reqwest::Client::new(), &server.uri(), "1228").await.unwrap_err();
+    assert!(err.to_string().contains("500"), "unexpected error: {}", err);
+}
+
+#[tokio::test]
+async fn fetch_mods_fails_on_malformed_json() {
+    let server = mock_mods_api().await;
+    assert!(fetch_mods_for_version(&reqwest::Client::new(), &server.uri(), "broken").await.is_err());
+}
+
+#[tokio::test]
+async fn refresh_keeps_versions_that_loaded() {
+    let server = mock_mods_api().await;
+    let data = Data::with_repositories(
+        Arc::new(InMemoryMapRepository::new()),
+        Arc::new(HttpModRepository::with_base_url(reqwest::Client::new(), &server.uri())),
+    );
+
+    assert_eq!(mod_utils::refresh_mod_cache(&data).await, 3);
+    let cache = data.mod_cache.read().await;
+    assert_eq!(cache.get("12104").map(Vec::len), Some(3));
+    assert!(!cache.contains_key("1228"));
+}
+
+#[tokio::test]
+async fn refresh_leaves_cache_untouched_when_everything_fails() {
+    let data = Data::with_repositories(Arc::new(InMemoryMapRepository::new()), Arc::new(InMemoryModRepository::new()));
+    data.mod_cache.write().await.insert("12104".to_string(), fixture_mods_12104());
+
+    assert_eq!(mod_utils::refresh_mod_cache(&data).await, 0);
+    assert_eq!(data.mod_cache.read().await.get("12104").map(Vec::len), Some(3));
+}
+
+#[test]
+fn format_mod_entry_renders_all_fields() {
+    let mods = fixture_mods_12104();
+    let text = format_mod_entry(&mods[0], &HashSet::new());
+
+    assert!(text.contains("**Author:** Kiwi"));
+    assert!(text.contains("**Mod Version:** 1.2.4"));
+    assert!(text.contains("**Features:** Custom gear, Texture swaps"));
+    assert!(text.contains("**Note:** Requires XLShredLib."));
+    assert!(text.ends_with("**Links:** [GitHub](https://github.com/example/xlgm/releases) | [Drive](https://drive.google.com/file/d/abc)"));
+}
+
+#[test]
+fn format_mod_entry_uses_placeholders_for_missing_fields() {
+    let mods = fixture_mods_12104();
+    let text = format_mod_entry(&mods[1], &HashSet::new());
+
+    assert_eq!(
+        text,
+        "**Author:** Unknown\n**Mod Version:** N/A\n**Game Version:** N/A\n**Keybind:** None\n**Features:** N/A"
+    );
+}
+
+#[test]
+fn truncation_respects_char_boundaries() {
+    assert_eq!(truncate_with_ellipsis("short", 10), "short");
+    assert_eq!(truncate_with_ellipsis("ééééééééééé", 6), "ééé...");
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use common::{embed_json, fixture_mods_12104, mod_entry};
+use skatebit_bot::{
+    commands::modlist_cmd::{build_mod_page_embed, sort_mods, ModSort},
+    types::ModVersionBranch,
+};
+
+#[test]
+fn sort_by_title_is_case_insensitive() {
+    let mut mods = fixture_mods_12104();
+    mods.push(mod_entry("aaa first"));
+    let titles: Vec<_> = sort_mods(&mods, ModSort::Title).iter().map(|m| m.title.as_str()).collect();
+    assert_eq!(titles, vec!["aaa first", "BonedOllieMod", "Walking Mod", "XLGearModifier"]);
+}
+
+#[test]
+fn sort_by_author_puts_unknown_authors_last() {
+    let mods = fixture_mods_12104();
+    let titles: Vec<_> = sort_mods(&mods, ModSort::Author).iter().map(|m| m.title.as_str()).collect();
+    assert_eq!(titles, vec!["XLGearModifier", "BonedOllieMod", "Walking Mod"]);
+}
+
+#[test]
+fn page_embed_numbers_entries_across_pages() {
+    let mods: Vec<_> = (0..23).map(|i| mod_entry(&format!("Mod {:02}", i))).collect();
+    let sorted = sort_mods(&mods, ModSort::Title);
+    let embed = embed_json(&build_mod_page_embed(&sorted, 2, ModVersionBranch::Alpha, ModSort::Title, "tester"));
+
+    let description = embed["description"].as_str().unwrap();
+    assert_eq!(description.lines().count(), 3);
+    assert!(description.starts_with("`21.` **Mod 20** — Unknown"));
+    assert_eq!(embed["footer"]["text"], "Page 3/3 · 23 mods · Sorted by Title · Requested by tester");
+    assert_eq!(embed["title"], "📜 Skater XL Script Mods — Alpha");
+}
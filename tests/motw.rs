@@ -0,0 +1,138 @@
+mod common;
+
+use common::{embed_json, map, redis_stub::RedisStub};
+use skatebit_bot::{
+    components::ComponentAction,
+    config::RedisConfig,
+    motw::{self, build_poll_embed, build_results_embed, tally, winning_index},
+    repository::{InMemoryMapRepository, InMemoryModRepository, InMemoryMotwRepository, MotwRepository, RedisMotwRepository},
+    types::{Data, HallOfFameEntry, MotwPoll},
+};
+use std::{collections::HashMap, sync::Arc};
+
+const GUILD: u64 = 900;
+
+fn poll(nominees: Vec<i32>) -> MotwPoll {
+    MotwPoll { guild_id: GUILD, channel_id: 901, message_id: 902, nominees, opened_at: 1_700_000_000 }
+}
+
+#[test]
+fn vote_buttons_carry_the_map_id() {
+    let action = ComponentAction::MotwVote { map_id: 42 };
+    assert_eq!(action.custom_id(), "motw:42");
+    assert_eq!(ComponentAction::parse("motw:42"), Some(action));
+    assert_eq!(ComponentAction::parse("motw:abc"), None);
+}
+
+#[test]
+fn tally_ignores_votes_off_the_ballot_and_ties_go_to_the_first_nominee() {
+    let votes = HashMap::from([(1, 20), (2, 10), (3, 99), (4, 20), (5, 10)]);
+    let counts = tally(&[10, 20, 30], &votes);
+    assert_eq!(counts, [2, 2, 0]);
+    assert_eq!(winning_index(&counts), Some(0));
+    assert_eq!(winning_index(&[0, 0]), None);
+}
+
+#[test]
+fn poll_embed_lists_nominees_and_closing_time() {
+    let embed = embed_json(&build_poll_embed(&[map(1, "Berlin Plaza", &["Map"]), map(2, "Brooklyn Banks", &["Map"])], Some(1_700_600_000)));
+    let description = embed["description"].as_str().unwrap();
+    assert!(description.starts_with("**1.** [Berlin Plaza](https://mod.io/g/skaterxl/m/berlin-plaza) by builder\n**2.** [Brooklyn Banks]"));
+    assert!(description.ends_with("Voting closes <t:1700600000:F>."));
+}
+
+#[tokio::test]
+async fn finishing_a_poll_crowns_the_winner_once() {
+    let maps = InMemoryMapRepository::with_maps([map(1, "Berlin Plaza", &["Map"]), map(2, "Brooklyn Banks", &["Map"])]);
+    let data = Data::with_repositories(Arc::new(maps), Arc::new(InMemoryModRepository::new()));
+    assert!(data.motw.open_poll(&poll(vec![1, 2, 3])).await.unwrap());
+    assert!(!data.motw.open_poll(&poll(vec![1])).await.unwrap(), "one open poll per guild");
+    for (user, map_id) in [(10, 1), (11, 2), (12, 2), (10, 2)] {
+        data.motw.vote(&poll(vec![1, 2, 3]), user, map_id).await.unwrap();
+    }
+
+    let closed = motw::finish_poll(&data, GUILD, 1_700_600_000).await.unwrap().unwrap();
+    assert_eq!(closed.counts, [0, 3, 0]);
+    let expected = HallOfFameEntry { map_id: 2, map_name: "Brooklyn Banks".into(), votes: 3, closed_at: 1_700_600_000 };
+    assert_eq!(closed.winner.as_ref(), Some(&expected));
+    assert_eq!(data.motw.winners(GUILD).await.unwrap(), [expected]);
+
+    let results = embed_json(&build_results_embed(&closed));
+    let description = results["description"].as_str().unwrap();
+    assert!(description.contains("🏆 **2.** Brooklyn Banks — **3 votes**"));
+    assert!(description.contains("**3.** Map #3 — **0 votes**"), "{}", description);
+
+    assert!(data.motw.get_poll(GUILD).await.unwrap().is_none());
+    assert!(motw::finish_poll(&data, GUILD, 1_700_600_001).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn polls_without_votes_have_no_winner() {
+    let data = Data::with_repositories(Arc::new(InMemoryMapRepository::new()), Arc::new(InMemoryModRepository::new()));
+    data.motw.open_poll(&poll(vec![1, 2])).await.unwrap();
+    let closed = motw::finish_poll(&data, GUILD, 1_700_600_000).await.unwrap().unwrap();
+    assert_eq!(closed.winner, None);
+    assert!(data.motw.winners(GUILD).await.unwrap().is_empty());
+}
+
+async fn exercise_motw(repo: &dyn MotwRepository) {
+    assert!(repo.open_poll(&poll(vec![1, 2])).await.unwrap());
+    assert!(!repo.open_poll(&poll(vec![3])).await.unwrap());
+    assert_eq!(repo.open_polls().await.unwrap(), [poll(vec![1, 2])]);
+
+    repo.vote(&poll(vec![1, 2]), 10, 1).await.unwrap();
+    repo.vote(&poll(vec![1, 2]), 10, 2).await.unwrap();
+    repo.vote(&poll(vec![1, 2]), 11, 1).await.unwrap();
+    assert_eq!(repo.votes(&poll(vec![1, 2])).await.unwrap(), HashMap::from([(10, 2), (11, 1)]));
+
+    let (closed, votes) = repo.close_poll(GUILD).await.unwrap().unwrap();
+    assert_eq!(closed, poll(vec![1, 2]));
+    assert_eq!(votes.len(), 2);
+    assert!(repo.open_polls().await.unwrap().is_empty());
+    assert!(repo.votes(&poll(vec![1, 2])).await.unwrap().is_empty());
+    assert!(repo.close_poll(GUILD).await.unwrap().is_none(), "a poll is only closed once");
+
+    repo.vote(&poll(vec![1, 2]), 12, 1).await.unwrap();
+    let next = MotwPoll { message_id: 903, ..poll(vec![1, 2]) };
+    assert!(repo.open_poll(&next).await.unwrap());
+    assert!(repo.votes(&next).await.unwrap().is_empty(), "a late vote stays with its own poll");
+    let (_, votes) = repo.close_poll(GUILD).await.unwrap().unwrap();
+    assert!(votes.is_empty());
+
+    let older = HallOfFameEntry { map_id: 1, map_name: "Berlin Plaza".into(), votes: 4, closed_at: 100 };
+    let newer = HallOfFameEntry { map_id: 2, map_name: "Brooklyn Banks".into(), votes: 2, closed_at: 200 };
+    repo.add_winner(GUILD, &older).await.unwrap();
+    repo.add_winner(GUILD, &newer).await.unwrap();
+    assert_eq!(repo.winners(GUILD).await.unwrap(), [newer, older]);
+}
+
+#[tokio::test]
+async fn in_memory_motw_store_round_trips() {
+    exercise_motw(&InMemoryMotwRepository::new()).await;
+}
+
+#[tokio::test]
+async fn redis_motw_store_round_trips() {
+    let redis = RedisStub::start().await;
+    exercise_motw(&RedisMotwRepository::new(redis.pool(), RedisConfig::default())).await;
+    assert!(redis.get("bot:motw:poll:900").is_none());
+    assert!(redis.ttl("bot:motw:votes:900:902").is_some(), "the late vote expires");
+}
+
+#[tokio::test]
+async fn racing_closes_only_hand_the_poll_to_one_caller() {
+    let redis = RedisStub::start().await;
+    let repo = RedisMotwRepository::new(redis.pool(), RedisConfig::default());
+    for _ in 0..20 {
+        repo.open_poll(&poll(vec![1, 2])).await.unwrap();
+        let (a, b) = tokio::join!(repo.close_poll(GUILD), repo.close_poll(GUILD));
+        assert_eq!(usize::from(a.unwrap().is_some()) + usize::from(b.unwrap().is_some()), 1);
+    }
+}
+
+#[test]
+fn hall_of_fame_lines_count_votes_in_the_singular_and_plural() {
+    let one = HallOfFameEntry { map_id: 1, map_name: "Berlin Plaza".into(), votes: 1, closed_at: 100 };
+    assert_eq!(motw::hall_of_fame_line(&one), "<t:100:D> — 🏆 **Berlin Plaza** (1 vote)");
+    assert_eq!(motw::hall_of_fame_line(&HallOfFameEntry { votes: 4, ..one }), "<t:100:D> — 🏆 **Berlin Plaza** (4 votes)");
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::embed_json;
+use skatebit_bot::pagination::{apply_page_action, build_list_page_embed, page_components, page_count, page_slice, parse_page_action, PageAction};
+
+#[test]
+fn page_count_rounds_up_and_never_hits_zero() {
+    assert_eq!(page_count(0, 10), 1);
+    assert_eq!(page_count(10, 10), 1);
+    assert_eq!(page_count(11, 10), 2);
+}
+
+#[test]
+fn page_slice_clamps_to_bounds() {
+    let items: Vec<u32> = (0..23).collect();
+    assert_eq!(page_slice(&items, 2, 10), &[20, 21, 22]);
+    assert!(page_slice(&items, 5, 10).is_empty());
+}
+
+#[test]
+fn list_pages_number_themselves_in_the_footer() {
+    let lines: Vec<String> = (1..=12).map(|i| format!("line {}", i)).collect();
+    let embed = embed_json(&build_list_page_embed(&lines, 1, "🏆 Hall of Fame", "12 winners"));
+    assert_eq!(embed["title"], "🏆 Hall of Fame");
+    assert_eq!(embed["description"], "line 11\nline 12");
+    assert_eq!(embed["footer"]["text"], "Page 2/2 · 12 winners");
+}
+
+#[test]
+fn actions_stay_within_range() {
+    assert_eq!(apply_page_action(PageAction::Prev, 0, 3), 0);
+    assert_eq!(apply_page_action(PageAction::Next, 2, 3), 2);
+    assert_eq!(apply_page_action(PageAction::Last, 0, 3), 2);
+    assert_eq!(apply_page_action(PageAction::Jump(99), 0, 3), 2);
+    assert_eq!(apply_page_action(PageAction::First, 2, 3), 0);
+}
+
+#[test]
+fn custom_ids_round_trip() {
+    assert_eq!(parse_page_action("page:1", "page:1:next", &[]), Some(PageAction::Next));
+    assert_eq!(parse_page_action("page:1", "page:1:jump", &["4".to_string()]), Some(PageAction::Jump(4)));
+    assert_eq!(parse_page_action("page:1", "page:12:next", &[]), None);
+    assert_eq!(parse_page_action("page:1", "fav:map:3", &[]), None);
+}
+
+#[test]
+fn components_only_for_multiple_pages() {
+    assert!(page_components("page:1", 0, 1).is_empty());
+
+    let rows = serde_json::to_value(page_components("page:1", 0, 40)).unwrap();
+    let buttons = rows[0]["components"].as_array().unwrap();
+    assert_eq!(buttons[0]["disabled"], true);
+    assert_eq!(buttons[2]["disabled"], false);
+    let options = rows[1]["components"][0]["options"].as_array().unwrap();
+    assert_eq!(options.len(), 25, "jump menu is capped at Discord's limit");
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::{map, mod_entry};
+use skatebit_bot::{
+    map_cache,
+    presence::{self, SEARCH_HINT},
+    repository::{InMemoryMapRepository, InMemoryModRepository},
+    types::Data,
+};
+use std::{collections::HashMap, sync::Arc};
+
+#[test]
+fn status_lines_cover_maps_latest_map_and_mods() {
+    let mut newer = map(2, "Brooklyn Banks", &["Map"]);
+    newer.date_added += 86_400;
+    let maps = vec![map(1, "Berlin Plaza", &["Map"]), newer, map(3, "Love Park", &["Map"])];
+
+    assert_eq!(presence::latest_map(&maps).map(|m| m.id), Some(2));
+    assert_eq!(
+        presence::status_lines(Some(maps.len()), presence::latest_map(&maps), 1_250),
+        vec!["🛹 3 maps indexed", "🆕 Latest map: Brooklyn Banks", "🧩 1,250 community mods", SEARCH_HINT]
+    );
+}
+
+#[test]
+fn status_lines_fall_back_to_the_search_hint() {
+    assert_eq!(presence::status_lines(None, None, 0), vec![SEARCH_HINT]);
+    assert_eq!(presence::status_lines(Some(0), None, 0), vec![SEARCH_HINT]);
+}
+
+#[test]
+fn long_map_names_are_truncated() {
+    let long = map(1, &"Very Long Plaza ".repeat(10), &["Map"]);
+    let lines = presence::status_lines(Some(1), Some(&long), 0);
+    assert!(lines[1].chars().count() <= 128, "{}", lines[1]);
+    assert!(lines[1].ends_with("..."), "{}", lines[1]);
+}
+
+#[test]
+fn mods_on_several_branches_are_counted_once() {
+    let cache = HashMap::from([
+        ("12104".to_string(), vec![mod_entry("XLGearModifier"), mod_entry("XXLMod3")]),
+        ("1228".to_string(), vec![mod_entry("xlgearmodifier"), mod_entry("BonedOllieMod")]),
+    ]);
+    assert_eq!(presence::distinct_mod_count(&cache), 3);
+}
+
+#[tokio::test]
+async fn current_status_lines_read_the_map_index_map_list_and_mod_cache() {
+    let maps = Arc::new(InMemoryMapRepository::with_maps([map(1, "Berlin Plaza", &["Map"])]));
+    let data = Data::with_repositories(maps, Arc::new(InMemoryModRepository::new()));
+    *data.mod_cache.write().await = HashMap::from([("12104".to_string(), vec![mod_entry("XXLMod3")])]);
+    assert_eq!(
+        presence::current_status_lines(&data).await,
+        vec!["🛹 1 map indexed", "🧩 1 community mod", SEARCH_HINT],
+        "no latest map before the map list is cached"
+    );
+
+    map_cache::refresh_map_indexes(&data).await;
+    assert_eq!(
+        presence::current_status_lines(&data).await,
+        vec!["🛹 1 map indexed", "🆕 Latest map: Berlin Plaza", "🧩 1 community mod", SEARCH_HINT]
+    );
+}
@@ -0,0 +1,129 @@
+use skatebit_bot::{
+    config::{Config, RateLimitConfig, RateLimitRule},
+    rate_limit::{self, buckets, LimitScope, RateLimiter},
+};
+
+fn config() -> RateLimitConfig {
+    RateLimitConfig {
+        enabled: true,
+        user: RateLimitRule::new(3, 10),
+        channel: RateLimitRule::new(5, 10),
+        guild: RateLimitRule::new(0, 10),
+        commands: [("maps trend".to_string(), RateLimitRule::new(1, 30))].into(),
+    }
+}
+
+#[test]
+fn buckets_follow_the_configured_scopes() {
+    let scopes = |command: &str, guild: Option<u64>| buckets(&config(), command, 1, 2, guild).iter().map(|b| b.scope).collect::<Vec<_>>();
+    assert_eq!(scopes("map", Some(3)), vec![LimitScope::User, LimitScope::Channel]);
+    assert_eq!(scopes("maps trend", Some(3)), vec![LimitScope::User, LimitScope::Channel, LimitScope::Command]);
+
+    let with_guild = RateLimitConfig { guild: RateLimitRule::new(10, 10), ..config() };
+    assert_eq!(buckets(&with_guild, "ping", 1, 2, Some(3))[2].key, "guild:3");
+    assert_eq!(buckets(&with_guild, "ping", 1, 2, None).len(), 2, "no guild bucket in DMs");
+}
+
+#[test]
+fn user_limit_resets_as_the_window_slides() {
+    let mut limiter = RateLimiter::default();
+    let user = buckets(&config(), "ping", 1, 2, None);
+    for at in [0, 1_000, 2_000] {
+        assert!(limiter.try_acquire(&user, at).is_ok());
+    }
+    let limited = limiter.try_acquire(&user, 4_500).unwrap_err();
+    assert_eq!(limited.scope, LimitScope::User);
+    assert_eq!(limited.retry_after_ms, 5_500);
+    assert_eq!(limited.retry_after_secs(), 6);
+
+    assert!(limiter.try_acquire(&user, 10_000).is_ok(), "the first use left the window");
+    assert!(limiter.try_acquire(&user, 10_500).is_err());
+}
+
+#[test]
+fn limited_invocations_are_not_counted() {
+    let mut limiter = RateLimiter::default();
+    let trend = buckets(&config(), "maps trend", 1, 2, None);
+    assert!(limiter.try_acquire(&trend, 0).is_ok());
+    for at in [1_000, 2_000, 3_000] {
+        assert_eq!(limiter.try_acquire(&trend, at).unwrap_err().scope, LimitScope::Command);
+    }
+    // Only the one allowed trend counted against the user's overall limit.
+    let other = buckets(&config(), "ping", 1, 2, None);
+    assert!(limiter.try_acquire(&other, 4_000).is_ok());
+    assert!(limiter.try_acquire(&other, 4_000).is_ok());
+    assert!(limiter.try_acquire(&other, 4_000).is_err());
+}
+
+#[test]
+fn the_longest_wait_is_reported() {
+    let mut limiter = RateLimiter::default();
+    let trend = buckets(&config(), "maps trend", 1, 2, None);
+    assert!(limiter.try_acquire(&trend, 0).is_ok());
+    for (user_id, at) in [(6, 5_000), (7, 6_000), (8, 7_000), (9, 8_000)] {
+        assert!(limiter.try_acquire(&buckets(&config(), "ping", user_id, 2, None), at).is_ok());
+    }
+    // The channel is full for another second, the trend command for another 21.
+    let limited = limiter.try_acquire(&trend, 9_000).unwrap_err();
+    assert_eq!(limited.scope, LimitScope::Command);
+    assert_eq!(limited.retry_after_ms, 21_000);
+
+    let ping = buckets(&config(), "ping", 1, 2, None);
+    assert_eq!(limiter.try_acquire(&ping, 9_000).unwrap_err().scope, LimitScope::Channel);
+    assert!(limiter.try_acquire(&ping, 10_000).is_ok());
+}
+
+#[test]
+fn users_are_limited_independently() {
+    let mut limiter = RateLimiter::default();
+    let no_channel = RateLimitConfig { channel: RateLimitRule::new(0, 10), ..config() };
+    for _ in 0..3 {
+        assert!(limiter.try_acquire(&buckets(&no_channel, "ping", 1, 2, None), 0).is_ok());
+    }
+    assert!(limiter.try_acquire(&buckets(&no_channel, "ping", 1, 2, None), 0).is_err());
+    assert!(limiter.try_acquire(&buckets(&no_channel, "ping", 7, 2, None), 0).is_ok());
+}
+
+#[test]
+fn message_names_the_wait_and_shared_limits() {
+    let mut limiter = RateLimiter::default();
+    let user = buckets(&config(), "ping", 1, 2, None);
+    for _ in 0..3 {
+        limiter.try_acquire(&user, 0).unwrap();
+    }
+    let limited = limiter.try_acquire(&user, 7_200).unwrap_err();
+    assert_eq!(rate_limit::limited_message(&limited), "🐢 Slow down, try again in 3s.");
+
+    let mut limiter = RateLimiter::default();
+    for user_id in 10..15 {
+        limiter.try_acquire(&buckets(&config(), "ping", user_id, 2, None), 0).unwrap();
+    }
+    let limited = limiter.try_acquire(&user, 9_999).unwrap_err();
+    assert_eq!(rate_limit::limited_message(&limited), "🐢 Slow down, try again in 1s. This channel is running a lot of commands right now.");
+}
+
+#[test]
+fn rate_limits_are_configurable_and_validated() {
+    let toml = r#"
+        [rate_limit]
+        user = { max = 2, per_secs = 5 }
+        channel = { max = 0, per_secs = 0 }
+
+        [rate_limit.commands]
+        "maps compare" = { max = 1, per_secs = 0 }
+        "maps random" = { max = 0, per_secs = 0 }
+    "#;
+    let config = Config::from_sources(Some(toml), |key| (key == "DISCORD_TOKEN").then(|| "token".to_string())).unwrap();
+    assert_eq!(config.rate_limit.user, RateLimitRule::new(2, 5));
+    assert_eq!(config.rate_limit.guild, RateLimitConfig::default().guild, "scopes left out keep their defaults");
+    assert!(buckets(&config.rate_limit, "ping", 1, 2, Some(3)).iter().all(|b| b.scope != LimitScope::Channel), "max = 0 turns a scope off");
+    assert!(!config.rate_limit.commands.contains_key("maps trend"), "the table replaces the default overrides");
+
+    let err = config.validate().unwrap_err().to_string();
+    assert!(!err.contains("channel") && !err.contains("maps random"), "limits that are off need no window: {}", err);
+    assert!(err.contains(r#"rate_limit.commands."maps compare".per_secs must be at least 1"#), "{}", err);
+
+    let defaults = Config::default();
+    assert!(defaults.rate_limit.enabled);
+    assert!(defaults.to_redacted_toml().unwrap().contains("[rate_limit.commands"), "{}", defaults.to_redacted_toml().unwrap());
+}
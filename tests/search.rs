@@ -0,0 +1,102 @@
+mod common;
+
+use common::{embed_field, embed_json, map, mod_entry};
+use skatebit_bot::{
+    commands::search_cmd::{build_results_embed, match_score, open_hit, rank_hits, search_all, SearchHit, SearchKind},
+    repository::{InMemoryMapRepository, InMemoryModRepository},
+    types::{Data, FavoriteItem, ModVersionBranch},
+};
+use std::sync::Arc;
+
+async fn sample_data() -> Data {
+    let maps = InMemoryMapRepository::with_maps([
+        map(1, "Grind Park", &["Map"]),
+        map(2, "Berlin Plaza", &["Map"]),
+        map(3, "Grind", &["Script"]),
+        map(4, "Grindable Ledges", &["Script"]),
+    ]);
+    let data = Data::with_repositories(Arc::new(maps), Arc::new(InMemoryModRepository::new()));
+    {
+        let mut mod_cache = data.mod_cache.write().await;
+        mod_cache.insert("12104".to_string(), vec![mod_entry("Better Grinds"), mod_entry("XXL Grind Fix")]);
+        mod_cache.insert("1228".to_string(), vec![mod_entry("Better Grinds")]);
+    }
+    data
+}
+
+fn summary(hits: &[SearchHit]) -> Vec<(SearchKind, &str, u8)> {
+    hits.iter().map(|h| (h.kind, h.title.as_str(), h.score)).collect()
+}
+
+#[test]
+fn score_prefers_exact_then_prefix_then_word_then_substring() {
+    assert_eq!(match_score("Grind", " GRIND "), Some(0));
+    assert_eq!(match_score("Grind Park", "grind"), Some(1));
+    assert_eq!(match_score("XXL Grind Fix", "grind"), Some(2));
+    assert_eq!(match_score("Better Grinds", "rind"), Some(3));
+    assert_eq!(match_score("Berlin Plaza", "grind"), None);
+    assert_eq!(match_score("Anything", "  "), None);
+}
+
+#[test]
+fn ties_order_maps_scripts_then_mods() {
+    let hit = |kind, title: &str, score| SearchHit { kind, title: title.into(), item: FavoriteItem::Map { id: 1 }, score };
+    let ranked = rank_hits(vec![hit(SearchKind::Mod, "A", 1), hit(SearchKind::Map, "B", 1), hit(SearchKind::Script, "C", 0)]);
+    assert_eq!(summary(&ranked), [(SearchKind::Script, "C", 0), (SearchKind::Map, "B", 1), (SearchKind::Mod, "A", 1)]);
+    assert_eq!(rank_hits((0..40).map(|i| hit(SearchKind::Map, &i.to_string(), 1)).collect()).len(), 25);
+}
+
+#[tokio::test]
+async fn searches_every_source() {
+    let data = sample_data().await;
+    let hits = search_all(&data, "grind").await;
+    assert_eq!(
+        summary(&hits),
+        [
+            (SearchKind::Script, "Grind", 0),
+            (SearchKind::Map, "Grind Park", 1),
+            (SearchKind::Script, "Grindable Ledges", 1),
+            (SearchKind::Mod, "Better Grinds", 2),
+            (SearchKind::Mod, "Better Grinds", 2),
+            (SearchKind::Mod, "XXL Grind Fix", 2),
+        ]
+    );
+    assert_eq!(hits[3].badge(), "🧩 Mod · Beta/Public", "slug 12104 sorts before 1228");
+
+    let badges: Vec<String> = search_all(&data, "better").await.iter().map(|h| h.badge()).collect();
+    assert_eq!(badges, ["🧩 Mod · Beta/Public", "🧩 Mod · Alpha"], "one hit per branch");
+}
+
+#[tokio::test]
+async fn results_open_the_matching_detail_embed() {
+    let data = sample_data().await;
+    let hits = search_all(&data, "grind").await;
+
+    let (script, rows) = open_hit(&data, &hits[0], "tester").await.unwrap().unwrap();
+    assert_eq!(embed_field(&embed_json(&script), "Link"), Some("[Download Script](https://mod.io/dl/3)"));
+    assert_eq!(rows.len(), 1);
+
+    let (map, _) = open_hit(&data, &hits[1], "tester").await.unwrap().unwrap();
+    assert_eq!(embed_field(&embed_json(&map), "Link"), Some("[Download Map](https://mod.io/dl/1)"));
+
+    let (community, _) = open_hit(&data, &hits[5], "tester").await.unwrap().unwrap();
+    let community = embed_json(&community);
+    assert_eq!(community["title"], "XXL Grind Fix");
+    assert_eq!(community["footer"]["text"], "Version: Beta/Public | Requested by tester");
+
+    let gone = SearchHit {
+        kind: SearchKind::Mod,
+        title: "Removed".into(),
+        item: FavoriteItem::Mod { title: "Removed".into(), branch: ModVersionBranch::Alpha },
+        score: 0,
+    };
+    assert!(open_hit(&data, &gone, "tester").await.unwrap().is_none());
+}
+
+#[test]
+fn results_embed_badges_each_line() {
+    let hits = vec![SearchHit { kind: SearchKind::Script, title: "Grind".into(), item: FavoriteItem::Map { id: 3 }, score: 0 }];
+    let embed = embed_json(&build_results_embed(" grind ", &hits));
+    assert_eq!(embed["title"], "🔎 Results for 'grind'");
+    assert_eq!(embed["description"], "**1.** 📜 Script — Grind");
+}
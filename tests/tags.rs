@@ -0,0 +1,79 @@
+mod common;
+
+use common::map;
+use skatebit_bot::{
+    map_cache,
+    repository::{self, InMemoryMapRepository, InMemoryModRepository},
+    tags::{self, describe_tags, TagIndex, TagMatch},
+    types::Data,
+};
+use std::sync::Arc;
+
+fn sample_index() -> TagIndex {
+    TagIndex::from_maps(&[
+        map(1, "Berlin Plaza", &["Map", "Street", "Night"]),
+        map(2, "Brooklyn Banks", &["Map", "Street"]),
+        map(3, "Quiet Park", &["Map", "Park", "night"]),
+        map(4, "Better Grinds", &["Script", "Street"]),
+    ])
+}
+
+#[test]
+fn index_collects_tags_of_maps_only() {
+    let index = sample_index();
+    assert_eq!(index.len(), 3, "Map itself and script-only tags are not indexed");
+    assert!(index.get("map").is_none());
+    assert_eq!(index.get(" street ").unwrap().map_ids, [1, 2]);
+    let night = index.get("NIGHT").unwrap();
+    assert_eq!((night.name.as_str(), night.map_ids.as_slice()), ("Night", [1, 3].as_slice()));
+}
+
+#[test]
+fn suggestions_put_the_most_used_tags_first() {
+    let index = sample_index();
+    let names = |partial: &str| -> Vec<String> { index.suggestions(partial, 25).into_iter().map(|t| t.name.clone()).collect() };
+    assert_eq!(names(""), ["Night", "Street", "Park"]);
+    assert_eq!(names("ar"), ["Park"]);
+    assert_eq!(index.suggestions("", 1).len(), 1);
+}
+
+#[test]
+fn tags_combine_with_and_or() {
+    let index = sample_index();
+    assert_eq!(index.matching_ids(&["Street"], TagMatch::All), [1, 2]);
+    assert_eq!(index.matching_ids(&["Street", "Night"], TagMatch::All), [1]);
+    assert_eq!(index.matching_ids(&["Street", "Night"], TagMatch::Any), [1, 2, 3]);
+    assert_eq!(index.matching_ids(&["Street", "Park"], TagMatch::All), Vec::<i32>::new());
+    assert_eq!(index.matching_ids(&["Street", "Unknown"], TagMatch::Any), [1, 2]);
+    assert!(index.matching_ids(&[], TagMatch::Any).is_empty());
+}
+
+#[test]
+fn queries_resolve_to_canonical_names() {
+    let index = sample_index();
+    assert_eq!(index.resolve(&["street".into(), "NIGHT".into(), "Street".into()]), Ok(vec!["Street".to_string(), "Night".to_string()]));
+    assert_eq!(index.resolve(&["street".into(), "Snow".into()]), Err("Snow".to_string()));
+    assert_eq!(describe_tags(&["Street", "Night"], TagMatch::All), "Street + Night");
+    assert_eq!(describe_tags(&["Street", "Night"], TagMatch::Any), "Street or Night");
+}
+
+#[test]
+fn list_line_links_the_map() {
+    let line = tags::tagged_map_line(&map(2, "Brooklyn Banks", &["Map", "Street"]));
+    assert!(line.starts_with("[Brooklyn Banks](https://mod.io/g/skaterxl/m/brooklyn-banks) by builder · ⬇️ "));
+}
+
+#[tokio::test]
+async fn refresh_builds_the_index_and_maps_load_by_id() {
+    let repo = Arc::new(InMemoryMapRepository::with_maps(vec![
+        map(1, "Berlin Plaza", &["Map", "Street"]),
+        map(2, "Brooklyn Banks", &["Map", "Street"]),
+    ]));
+    let data = Data::with_repositories(repo.clone(), Arc::new(InMemoryModRepository::new()));
+    assert_eq!(map_cache::refresh_map_indexes(&data).await, 2);
+    assert_eq!(data.tags.read().await.len(), 1);
+
+    let ids = data.tags.read().await.matching_ids(&["street"], TagMatch::All);
+    let maps = repository::load_maps(repo.as_ref(), &[ids[1], 99]).await.unwrap();
+    assert_eq!(maps.iter().map(|m| m.id).collect::<Vec<_>>(), [2]);
+}
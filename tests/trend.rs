@@ -0,0 +1,60 @@
+mod common;
+
+use common::{embed_field, embed_json, map};
+use skatebit_bot::{
+    commands::map_trend_cmd::build_trend_embed,
+    trend::{render_trend_png, summarize, TrendRange, CHART_HEIGHT, CHART_WIDTH},
+    types::StatsSnapshot,
+};
+
+const NOW: i64 = 1_700_000_000;
+
+fn history() -> Vec<StatsSnapshot> {
+    (0..10)
+        .map(|i| StatsSnapshot {
+            map_id: 1,
+            taken_at: NOW + i * 3 * 86_400,
+            downloads: 1_000 + i * i * 150,
+            subscribers: 200 + i * 12,
+            ratings_positive: 40 + i as i32,
+            ratings_negative: 10,
+        })
+        .collect()
+}
+
+#[test]
+fn summary_compares_first_and_last_snapshot() {
+    let summary = summarize(&history()).unwrap();
+    assert_eq!((summary.from, summary.to), (NOW, NOW + 27 * 86_400));
+    assert_eq!(summary.downloads_gained, 81 * 150);
+    assert_eq!(summary.subscribers_gained, 108);
+    assert_eq!(summary.rating_from, Some(80.0));
+    assert!(summary.rating_to.unwrap() > 83.0);
+
+    assert!(summarize(&history()[..1]).is_none());
+    assert!(summarize(&[]).is_none());
+}
+
+#[test]
+fn chart_is_a_png_of_the_expected_size() {
+    let bytes = render_trend_png("Berlin Plaza", &history()).unwrap();
+    let decoder = png::Decoder::new(bytes.as_slice());
+    let reader = decoder.read_info().unwrap();
+    assert_eq!((reader.info().width, reader.info().height), (CHART_WIDTH, CHART_HEIGHT));
+
+    // Flat series still render.
+    let flat: Vec<StatsSnapshot> = history().into_iter().map(|s| StatsSnapshot { downloads: 5, subscribers: 0, ..s }).collect();
+    assert!(render_trend_png("Flat", &flat).is_ok());
+    assert!(render_trend_png("Too short", &history()[..1]).is_err());
+}
+
+#[test]
+fn trend_embed_shows_changes_and_attaches_the_chart() {
+    let summary = summarize(&history()).unwrap();
+    let embed = embed_json(&build_trend_embed(&map(1, "Berlin Plaza", &["Map"]), TrendRange::Month, &summary, "tester"));
+
+    assert_eq!(embed["title"], "📈 Berlin Plaza · Last 30 days");
+    assert_eq!(embed_field(&embed, "Downloads"), Some("+12,150"));
+    assert_eq!(embed_field(&embed, "Subscribers"), Some("+108"));
+    assert_eq!(embed["image"]["url"], "attachment://trend.png");
+}
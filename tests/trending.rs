@@ -0,0 +1,88 @@
+mod common;
+
+use common::map;
+use skatebit_bot::{
+    map_cache,
+    repository::{InMemoryMapRepository, InMemoryModRepository},
+    stats::{self, download_gains, trending_line, TrendingWindow},
+    types::{ApiModioMap, Data},
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const NOW: i64 = 1_700_000_000;
+const DAY: i64 = 86_400;
+const HOUR: i64 = 3_600;
+
+fn with_downloads(id: i32, name: &str, downloads: i32) -> ApiModioMap {
+    let mut entry = map(id, name, &["Map"]);
+    entry.stats.downloads_total = downloads;
+    entry
+}
+
+/// Berlin gained steadily over the month; Brooklyn only took off in the last day.
+async fn seeded() -> (Data, Arc<InMemoryMapRepository>) {
+    let maps = Arc::new(InMemoryMapRepository::new());
+    let data = Data::with_repositories(maps.clone(), Arc::new(InMemoryModRepository::new()));
+    for (at, berlin, brooklyn) in [(NOW - 30 * DAY + HOUR, 100, 50), (NOW - 7 * DAY + HOUR, 1_000, 60), (NOW - DAY + HOUR, 1_900, 70)] {
+        maps.insert(with_downloads(1, "Berlin Plaza", berlin));
+        maps.insert(with_downloads(2, "Brooklyn Banks", brooklyn));
+        stats::take_snapshots(&data, at).await;
+    }
+    maps.insert(with_downloads(1, "Berlin Plaza", 2_000));
+    maps.insert(with_downloads(2, "Brooklyn Banks", 1_070));
+    (data, maps)
+}
+
+async fn ranking(data: &Data, window: TrendingWindow) -> Vec<(i32, i64)> {
+    map_cache::refresh_map_indexes(data).await;
+    let ids: Vec<i32> = data.map_list.read().await.iter().map(|m| m.id).collect();
+    let baselines = stats::window_baselines(data, &ids, NOW - window.secs()).await.unwrap();
+    download_gains(&*data.map_list.read().await, &baselines).iter().map(|g| (g.map_id, g.gained)).collect()
+}
+
+#[tokio::test]
+async fn ranking_depends_on_the_window() {
+    let (data, _maps) = seeded().await;
+    assert_eq!(ranking(&data, TrendingWindow::Day).await, vec![(2, 1_000), (1, 100)]);
+    assert_eq!(ranking(&data, TrendingWindow::Week).await, vec![(2, 1_010), (1, 1_000)]);
+    assert_eq!(ranking(&data, TrendingWindow::Month).await, vec![(1, 1_900), (2, 1_020)]);
+}
+
+#[tokio::test]
+async fn maps_without_gains_are_not_trending() {
+    let maps = Arc::new(InMemoryMapRepository::with_maps([with_downloads(1, "Berlin Plaza", 100)]));
+    let data = Data::with_repositories(maps, Arc::new(InMemoryModRepository::new()));
+    assert!(ranking(&data, TrendingWindow::Week).await.is_empty(), "no snapshots yet");
+    stats::take_snapshots(&data, NOW - DAY).await;
+    assert!(ranking(&data, TrendingWindow::Day).await.is_empty(), "no downloads since the snapshot");
+}
+
+#[tokio::test]
+async fn maps_first_snapshotted_late_in_the_window_are_left_out() {
+    let (data, maps) = seeded().await;
+    maps.insert(with_downloads(3, "Love Park", 10));
+    stats::take_snapshots(&data, NOW - 2 * DAY).await;
+    maps.insert(with_downloads(3, "Love Park", 5_000));
+    assert_eq!(ranking(&data, TrendingWindow::Week).await, vec![(2, 1_010), (1, 1_000)], "its first snapshot is days into the week");
+    assert_eq!(ranking(&data, TrendingWindow::Day).await, vec![(2, 1_000), (1, 100)], "no snapshot since the day started");
+}
+
+#[test]
+fn baselines_must_start_within_one_snapshot_interval() {
+    assert_eq!(stats::snapshot_interval("0 0 */3 * * *", NOW), Some(3 * HOUR));
+    assert_eq!(stats::snapshot_interval("0 0 0,6 * * *", NOW), Some(18 * HOUR));
+    let baselines = HashMap::from([(1, stats::snapshot_of(&map(1, "Berlin Plaza", &["Map"]), NOW + 3 * HOUR)), (2, stats::snapshot_of(&map(2, "Love Park", &["Map"]), NOW + DAY))]);
+    assert_eq!(stats::baselines_within(baselines, NOW, 3 * HOUR).into_keys().collect::<Vec<_>>(), vec![1]);
+}
+
+#[test]
+fn trending_line_shows_rank_author_and_gain() {
+    let entry = with_downloads(1, "Berlin Plaza", 5_000);
+    let baselines = [(1, stats::snapshot_of(&with_downloads(1, "Berlin Plaza", 3_766), NOW))].into();
+    let gain = &download_gains([&entry], &baselines)[0];
+    assert_eq!(
+        trending_line(3, gain),
+        "**3.** [Berlin Plaza](https://mod.io/g/skaterxl/m/berlin-plaza) by builder · ⬇️ +1,234"
+    );
+}
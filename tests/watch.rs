@@ -0,0 +1,137 @@
+mod common;
+
+use common::{embed_json, fixture_mods_12104, map, redis_stub::RedisStub};
+use skatebit_bot::{
+    components::{item_action_rows, ComponentAction},
+    config::RedisConfig,
+    repository::{InMemoryMapRepository, InMemoryModRepository, InMemoryWatchRepository, RedisWatchRepository, WatchRepository},
+    types::{Data, FavoriteItem, ModVersionBranch},
+    watch::{self, build_update_embed},
+};
+use std::sync::Arc;
+
+fn gear_modifier() -> FavoriteItem {
+    FavoriteItem::Mod { title: "XLGearModifier".to_string(), branch: ModVersionBranch::BetaPublic }
+}
+
+async fn data_with(maps: Arc<InMemoryMapRepository>) -> Data {
+    let data = Data::with_repositories(maps, Arc::new(InMemoryModRepository::new()));
+    data.mod_cache.write().await.insert("12104".to_string(), fixture_mods_12104());
+    data
+}
+
+#[test]
+fn watch_buttons_sit_next_to_favorite() {
+    let action = ComponentAction::ToggleWatch(FavoriteItem::Map { id: 7 });
+    assert_eq!(action.custom_id(), "watch:map:7");
+    assert_eq!(ComponentAction::parse("watch:map:7"), Some(action));
+
+    let rows = serde_json::to_value(item_action_rows(&FavoriteItem::Map { id: 7 })).unwrap();
+    let ids: Vec<&str> = rows[0]["components"].as_array().unwrap().iter().map(|b| b["custom_id"].as_str().unwrap()).collect();
+    assert_eq!(ids, ["fav:map:7", "watch:map:7"]);
+}
+
+#[tokio::test]
+async fn first_check_only_records_a_baseline() {
+    let maps = Arc::new(InMemoryMapRepository::with_maps([map(1, "Big Plaza", &["Map"])]));
+    let data = data_with(maps).await;
+    data.watches.toggle(10, &FavoriteItem::Map { id: 1 }).await.unwrap();
+    data.watches.toggle(10, &gear_modifier()).await.unwrap();
+
+    assert!(watch::find_updates(&data).await.unwrap().is_empty());
+    assert!(watch::find_updates(&data).await.unwrap().is_empty());
+    assert_eq!(data.watches.last_seen(&gear_modifier()).await.unwrap().as_deref(), Some("1.2.4"));
+}
+
+#[tokio::test]
+async fn new_map_file_notifies_every_watcher() {
+    let maps = Arc::new(InMemoryMapRepository::with_maps([map(1, "Big Plaza", &["Map"]), map(2, "Quiet Park", &["Map"])]));
+    let data = data_with(maps.clone()).await;
+    for user in [10, 11] {
+        data.watches.toggle(user, &FavoriteItem::Map { id: 1 }).await.unwrap();
+        watch::record_baseline(&data, &FavoriteItem::Map { id: 1 }).await.unwrap();
+    }
+    data.watches.toggle(12, &FavoriteItem::Map { id: 2 }).await.unwrap();
+    watch::record_baseline(&data, &FavoriteItem::Map { id: 2 }).await.unwrap();
+
+    let mut updated = map(1, "Big Plaza", &["Map"]);
+    updated.date_updated += 3600;
+    let modfile = updated.modfile.as_mut().unwrap();
+    modfile.id += 1;
+    modfile.version = Some("2.0".to_string());
+    maps.insert(updated);
+
+    let updates = watch::find_updates(&data).await.unwrap();
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].item, FavoriteItem::Map { id: 1 });
+    assert_eq!(updates[0].watchers, [10, 11]);
+    assert!(updates[0].details.contains("**2.0**"), "{}", updates[0].details);
+
+    let embed = embed_json(&build_update_embed(&updates[0]));
+    assert_eq!(embed["title"], "🔔 Big Plaza was updated");
+    assert_eq!(embed["url"], "https://mod.io/g/skaterxl/m/big-plaza");
+
+    assert_eq!(watch::find_updates(&data).await.unwrap().len(), 1, "an update stays pending until its watchers were notified");
+    watch::mark_notified(&data, &updates[0]).await.unwrap();
+    assert!(watch::find_updates(&data).await.unwrap().is_empty(), "an update is only reported once");
+}
+
+#[tokio::test]
+async fn changed_working_version_notifies_mod_watchers() {
+    let data = data_with(Arc::new(InMemoryMapRepository::new())).await;
+    data.watches.toggle(10, &gear_modifier()).await.unwrap();
+    watch::record_baseline(&data, &gear_modifier()).await.unwrap();
+
+    let mut mods = fixture_mods_12104();
+    mods[0].working_version = Some("1.2.5".to_string());
+    data.mod_cache.write().await.insert("12104".to_string(), mods);
+
+    let updates = watch::find_updates(&data).await.unwrap();
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].name, "XLGearModifier (Beta/Public)");
+    assert_eq!(updates[0].details, "Working version changed: **1.2.4** → **1.2.5**.");
+}
+
+#[tokio::test]
+async fn vanished_items_keep_their_last_seen_state() {
+    let data = data_with(Arc::new(InMemoryMapRepository::new())).await;
+    data.watches.toggle(10, &gear_modifier()).await.unwrap();
+    watch::record_baseline(&data, &gear_modifier()).await.unwrap();
+    data.mod_cache.write().await.clear();
+
+    assert!(watch::find_updates(&data).await.unwrap().is_empty());
+    assert_eq!(data.watches.last_seen(&gear_modifier()).await.unwrap().as_deref(), Some("1.2.4"));
+}
+
+async fn exercise_watches(repo: &dyn WatchRepository) {
+    let item = FavoriteItem::Map { id: 5 };
+    assert!(repo.toggle(1, &item).await.unwrap());
+    assert!(repo.toggle(2, &item).await.unwrap());
+    assert!(repo.toggle(1, &gear_modifier()).await.unwrap());
+    repo.set_last_seen(&item, "50:1700100000").await.unwrap();
+
+    assert_eq!(repo.list(1).await.unwrap(), [item.clone(), gear_modifier()]);
+    assert_eq!(repo.watchers(&item).await.unwrap(), [1, 2]);
+    assert_eq!(repo.watched_items().await.unwrap(), [item.clone(), gear_modifier()]);
+
+    assert!(!repo.toggle(1, &item).await.unwrap());
+    assert_eq!(repo.watchers(&item).await.unwrap(), [2]);
+    assert_eq!(repo.last_seen(&item).await.unwrap().as_deref(), Some("50:1700100000"));
+
+    assert!(repo.remove(2, &item).await.unwrap());
+    assert!(!repo.remove(2, &item).await.unwrap());
+    assert_eq!(repo.watched_items().await.unwrap(), [gear_modifier()]);
+    assert_eq!(repo.last_seen(&item).await.unwrap(), None, "state is dropped once nobody watches");
+}
+
+#[tokio::test]
+async fn in_memory_watches_track_watchers() {
+    exercise_watches(&InMemoryWatchRepository::new()).await;
+}
+
+#[tokio::test]
+async fn redis_watches_track_watchers() {
+    let redis = RedisStub::start().await;
+    exercise_watches(&RedisWatchRepository::new(redis.pool(), RedisConfig::default())).await;
+    assert_eq!(redis.smembers("bot:watch:1"), [gear_modifier().key()]);
+}